@@ -43,6 +43,7 @@ const ArrayBoxConfig = {
             const routes = {
                 apl: `${backendUrl}/api/apl`,
                 log: `${backendUrl}/api/log`,
+                eval: `${backendUrl}/api/eval`,
                 permalink: `${backendUrl}/api`,  // Becomes /api/p when /p is appended
                 image: `${backendUrl}/api`        // Becomes /api/image when /image/vertical is appended
             };
@@ -53,6 +54,7 @@ const ArrayBoxConfig = {
         const localPorts = {
             apl: 'http://localhost:8081',
             log: 'http://localhost:8082',
+            eval: 'http://localhost:8083',
             permalink: 'http://localhost:8084',
             image: 'http://localhost:8084'
         };
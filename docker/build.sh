@@ -3,6 +3,7 @@
 # Usage: ./build.sh [options]
 # Examples:
 #   ./build.sh           # Build APL Docker image
+#   ./build.sh bqn       # Build one language image (apl, bqn, j, uiua, kap, tinyapl)
 #   ./build.sh --wasm    # Build Docker image + WASM modules
 #   ./build.sh --all     # Build everything (Docker + WASM)
 #   ./build.sh --check   # Check dependencies without building
@@ -230,8 +231,11 @@ while [[ $# -gt 0 ]]; do
             echo "  --check   Check dependencies without building"
             echo "  -h        Show this help"
             echo ""
+            echo "Languages: apl, bqn, j, uiua, kap, tinyapl (Dockerfile.<lang>)"
+            echo ""
             echo "Examples:"
             echo "  ./build.sh           # Build APL Docker image"
+            echo "  ./build.sh bqn       # Build the BQN sandbox image"
             echo "  ./build.sh --wasm    # Build Docker image + WASM modules"
             echo "  ./build.sh --check   # Check what dependencies are installed"
             exit 0
@@ -0,0 +1,46 @@
+#!/bin/bash
+# Array Box sandbox runner (every language except APL)
+#
+# Runs as the entrypoint of a warm container and executes one program per
+# request, each in a fresh interpreter process so no state leaks between runs.
+#
+# Protocol (one request per line on stdin):
+#   <id> <base64-encoded UTF-8 source>
+# Response on stdout:
+#   ___ARRAYBOX_START_<id>___
+#   <interpreter stdout and stderr>
+#   ___ARRAYBOX_END_<id>___ <exit status>
+#
+# Environment:
+#   ARRAYBOX_LANG     bqn | j | uiua | kap | tinyapl (set by each Dockerfile)
+#   ARRAYBOX_TIMEOUT  per-run time limit in seconds (set by servers/sandbox.cjs)
+
+TIMEOUT="${ARRAYBOX_TIMEOUT:-10}"
+
+run_code() {
+    case "$ARRAYBOX_LANG" in
+        bqn)     bqn -p "$(cat "$1")" ;;
+        j)       jconsole -js "0!:101 (1!:1) <'$1'" "exit 0" ;;
+        uiua)    uiua run --no-format "$1" ;;
+        kap)     kap --lib-path=/opt/kap/standard-lib --no-repl "$1" ;;
+        tinyapl) node --no-warnings /opt/arraybox/tinyapl-eval.mjs "$1" ;;
+        *)       echo "Unsupported language: $ARRAYBOX_LANG" >&2; return 2 ;;
+    esac
+}
+export -f run_code
+
+echo "___ARRAYBOX_READY___"
+
+while IFS=' ' read -r id payload; do
+    [ -z "$id" ] && continue
+    file="/tmp/program.$ARRAYBOX_LANG"
+    printf '%s' "$payload" | base64 -d > "$file"
+
+    echo "___ARRAYBOX_START_${id}___"
+    timeout -k 1 "$TIMEOUT" bash -c 'run_code "$0"' "$file" < /dev/null 2>&1
+    status=$?
+    echo
+    echo "___ARRAYBOX_END_${id}___ $status"
+
+    rm -f "$file"
+done
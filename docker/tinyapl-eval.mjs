@@ -0,0 +1,118 @@
+#!/usr/bin/env node
+/**
+ * TinyAPL evaluator for the sandbox container
+ *
+ * TinyAPL only ships as a GHC WebAssembly build, so the container runs the
+ * same tinyapl.js / tinyapl-js.wasm the website uses, under Node's built-in
+ * WASI instead of the browser shim it imports from unpkg.
+ *
+ * Usage: node --no-warnings tinyapl-eval.mjs <file>
+ * Prints the result (or error) and exits non-zero on failure.
+ */
+
+import fs from 'node:fs';
+import { pathToFileURL } from 'node:url';
+import { WASI as NodeWASI } from 'node:wasi';
+
+const TINYAPL_DIR = process.env.TINYAPL_DIR || '/opt/tinyapl';
+
+// Minimal stand-ins for the parts of @bjorn3/browser_wasi_shim tinyapl.js uses
+globalThis.__arrayboxWasiShim = {
+    File: class { constructor(data) { this.data = data; } },
+    OpenFile: class { constructor(file) { this.file = file; } },
+    ConsoleStdout: { lineBuffered: (write) => ({ write }) },
+    WASI: class {
+        constructor(args) {
+            // GHC's RTS needs at least argv[0]
+            this.inner = new NodeWASI({ version: 'preview1', args: args.length ? args : ['tinyapl'], env: {}, returnOnExit: true });
+            this.wasiImport = this.inner.wasiImport;
+        }
+        initialize(instance) {
+            this.inner.initialize(instance);
+        }
+    }
+};
+
+async function loadTinyapl() {
+    const dirUrl = pathToFileURL(TINYAPL_DIR + '/').href;
+    const source = fs.readFileSync(`${TINYAPL_DIR}/tinyapl.js`, 'utf8')
+        .replace(/^import \{ WASI, OpenFile, File, ConsoleStdout \} from .*$/m,
+            'const { WASI, OpenFile, File, ConsoleStdout } = globalThis.__arrayboxWasiShim;')
+        .replace(`from './ghc_wasm_jsffi.js'`, `from '${dirUrl}ghc_wasm_jsffi.js'`)
+        .replace(/^const url = .*$/m, '')
+        .replace('WebAssembly.instantiateStreaming(fetch(url),',
+            `WebAssembly.instantiate(globalThis.__arrayboxTinyaplWasm,`);
+    globalThis.__arrayboxTinyaplWasm = fs.readFileSync(`${TINYAPL_DIR}/tinyapl-js.wasm`);
+    return import('data:text/javascript;base64,' + Buffer.from(source).toString('base64'));
+}
+
+// Same error patterns the browser uses to spot errors reported as values
+const TINYAPL_ERROR_PATTERNS = [
+    /^(?:Syntax|Parse|Value|Domain|Rank|Length|Index|NYI|Internal|Invalid|Assertion) error:/im,
+    /does not exist$/im,
+    /not assigned$/im
+];
+
+async function main() {
+    const file = process.argv[2];
+    if (!file) {
+        console.error('Usage: tinyapl-eval.mjs <file>');
+        process.exit(2);
+    }
+
+    // Join lines with ⋄ and drop comments, as the browser does
+    const processedCode = fs.readFileSync(file, 'utf8').split('\n')
+        .map(line => line.trim())
+        .filter(line => line && !line.startsWith('⍝'))
+        .map(line => {
+            const commentIndex = line.indexOf('⍝');
+            return commentIndex >= 0 ? line.substring(0, commentIndex).trim() : line;
+        })
+        .filter(line => line)
+        .join(' ⋄ ');
+
+    const tinyapl = await loadTinyapl();
+    let outputBuffer = '';
+    let errorBuffer = '';
+    const context = await tinyapl.newContext(
+        async () => '',
+        async (what) => { outputBuffer += what; },
+        async (what) => { errorBuffer += what; },
+        {}
+    );
+
+    const result = await tinyapl.runCode(context, processedCode);
+    let value = result;
+    let success = true;
+    if (Array.isArray(result)) {
+        [value, success] = result;
+    } else if (result && typeof result === 'object' && 'success' in result) {
+        success = result.success;
+        value = result.value || result.error || result;
+    }
+    // Errors come back as { code, message } rather than as a Value
+    if (value && typeof value === 'object' && 'code' in value && 'message' in value) {
+        success = false;
+    }
+
+    let output;
+    if (!success) {
+        output = errorBuffer + (errorBuffer ? '\n' : '') + (value.message || await tinyapl.show(value));
+    } else {
+        output = await tinyapl.show(value);
+        if (outputBuffer) {
+            output = outputBuffer.replace(/\n$/, '') + '\n' + output;
+        }
+        if (TINYAPL_ERROR_PATTERNS.some(p => p.test(output) || p.test(errorBuffer))) {
+            success = false;
+        }
+    }
+
+    process.stdout.write(output + '\n');
+    process.exit(success ? 0 : 1);
+}
+
+main().catch((error) => {
+    console.error(`TinyAPL execution error: ${error.message || String(error)}`);
+    process.exit(1);
+});
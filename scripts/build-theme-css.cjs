@@ -0,0 +1,50 @@
+#!/usr/bin/env node
+/**
+ * Theme CSS Builder
+ *
+ * Writes the default theme from src/themes.js into the :root block of
+ * src/theme.css, so pages that use theme.css without running themes.js still
+ * get its colors. The block sits between the "Default theme" and "End of
+ * default theme" comments; the rest of theme.css is left alone.
+ *
+ * Usage: node scripts/build-theme-css.cjs
+ *
+ * Run this after changing the default theme in src/themes.js.
+ */
+
+const fs = require('fs');
+const path = require('path');
+
+const CSS_PATH = path.join(__dirname, '..', 'src', 'theme.css');
+const START = '/* Default theme';
+const END = '/* End of default theme */';
+
+async function main() {
+    const { DEFAULT_THEME, themeVariables } = await import('../src/themes.js');
+
+    const css = fs.readFileSync(CSS_PATH, 'utf8');
+    const start = css.indexOf(START);
+    const end = css.indexOf(END);
+    if (start === -1 || end === -1) {
+        throw new Error(`theme.css has no "${START}" ... "${END}" block`);
+    }
+
+    const lines = Object.entries(themeVariables(DEFAULT_THEME))
+        .map(([name, value]) => `    ${name}: ${value};`);
+    const block = [
+        `${START} (${DEFAULT_THEME}), generated from src/themes.js by`,
+        '   scripts/build-theme-css.cjs - edit the theme there, not here */',
+        ':root {',
+        ...lines,
+        '}',
+        ''
+    ].join('\n');
+
+    fs.writeFileSync(CSS_PATH, css.slice(0, start) + block + css.slice(end));
+    console.log(`Wrote ${lines.length} variables for ${DEFAULT_THEME} to src/theme.css`);
+}
+
+main().catch(e => {
+    console.error(e.message);
+    process.exit(1);
+});
@@ -0,0 +1,202 @@
+#!/usr/bin/env node
+/**
+ * Idiom Importer (APLcart / BQNcrate)
+ *
+ * Downloads the APLcart and BQNcrate idiom tables and generates
+ * src/idiom-data.js for the idiom library (src/idioms.js).
+ *
+ * Usage: node scripts/import-idioms.cjs [--aplcart <url|file>] [--bqncrate <url|file>]
+ *
+ * Both tables are tab-separated. A header row naming the columns (SYNTAX,
+ * DESCRIPTION, KEYWORDS, ...) is used when present; otherwise the first
+ * column is taken as the code and the second as the description.
+ *
+ * Run this periodically to pick up new idioms from upstream.
+ */
+
+const https = require('https');
+const fs = require('fs');
+const path = require('path');
+
+const SOURCES = {
+    aplcart: {
+        name: 'APLcart',
+        language: 'apl',
+        url: 'https://raw.githubusercontent.com/abrudz/aplcart/master/table.tsv',
+        homepage: 'https://aplcart.info'
+    },
+    bqncrate: {
+        name: 'BQNcrate',
+        language: 'bqn',
+        url: 'https://raw.githubusercontent.com/mlochbaum/bqncrate/master/table.tsv',
+        homepage: 'https://mlochbaum.github.io/bqncrate/'
+    }
+};
+
+// Output path
+const JS_OUTPUT_FILE = path.join(__dirname, '..', 'src', 'idiom-data.js');
+
+/**
+ * Fetch a URL and return the text content
+ */
+function fetchUrl(url) {
+    return new Promise((resolve, reject) => {
+        https.get(url, (res) => {
+            if (res.statusCode === 301 || res.statusCode === 302) {
+                fetchUrl(res.headers.location).then(resolve).catch(reject);
+                return;
+            }
+
+            if (res.statusCode !== 200) {
+                reject(new Error(`HTTP ${res.statusCode} for ${url}`));
+                return;
+            }
+
+            res.setEncoding('utf8');
+            let data = '';
+            res.on('data', chunk => data += chunk);
+            res.on('end', () => resolve(data));
+            res.on('error', reject);
+        }).on('error', reject);
+    });
+}
+
+/**
+ * Read a table from a URL or a local file
+ */
+function readSource(location) {
+    if (/^https?:\/\//.test(location)) return fetchUrl(location);
+    return Promise.resolve(fs.readFileSync(location, 'utf8'));
+}
+
+/**
+ * Parse a tab-separated idiom table into idioms
+ * @param {string} text - Table contents
+ * @param {string} language - Language of the idioms
+ * @param {string} source - Source id stored on each idiom
+ */
+function parseTable(text, language, source) {
+    const rows = text.split(/\r?\n/).filter(line => line.trim()).map(line => line.split('\t'));
+    if (rows.length === 0) return [];
+
+    // Column positions from the header row, if there is one
+    const header = rows[0].map(cell => cell.trim().toLowerCase());
+    const find = (...names) => header.findIndex(cell => names.includes(cell));
+    let codeColumn = find('syntax', 'code');
+    let descriptionColumn = find('description', 'desc');
+    const tagColumns = ['keywords', 'category', 'group', 'class', 'type']
+        .map(name => header.indexOf(name))
+        .filter(index => index !== -1);
+
+    let body = rows;
+    if (codeColumn !== -1 && descriptionColumn !== -1) {
+        body = rows.slice(1);
+    } else {
+        codeColumn = 0;
+        descriptionColumn = 1;
+    }
+
+    const idioms = [];
+    const seen = new Set();
+    for (const row of body) {
+        const code = (row[codeColumn] || '').trim();
+        const description = (row[descriptionColumn] || '').trim();
+        if (!code || !description) continue;
+
+        const key = `${code}\t${description}`;
+        if (seen.has(key)) continue;
+        seen.add(key);
+
+        const tags = [...new Set(tagColumns
+            .flatMap(index => (row[index] || '').split(/[\s,]+/))
+            .map(tag => tag.trim().toLowerCase())
+            .filter(Boolean))];
+        idioms.push({ description, code, language, tags, source });
+    }
+    return idioms;
+}
+
+/**
+ * Generate the src/idiom-data.js module (one idiom per line, so diffs stay readable)
+ */
+function generateJsModule(meta, idioms) {
+    return `/**
+ * Imported Idioms
+ *
+ * Auto-generated by scripts/import-idioms.cjs
+ * Sources: ${meta.sources.map(s => s.url).join(', ') || '(none imported yet)'}
+ * Generated: ${meta.importedAt || '(never)'}
+ *
+ * Run \`node scripts/import-idioms.cjs\` to update from upstream.
+ *
+ * ATTRIBUTION:
+ * APLcart (https://aplcart.info) is maintained by Adám Brudzewsky,
+ * repository https://github.com/abrudz/aplcart.
+ * BQNcrate (https://mlochbaum.github.io/bqncrate/) is maintained by
+ * Marshall Lochbaum, repository https://github.com/mlochbaum/bqncrate.
+ * Check each project's license before redistributing imported idioms.
+ */
+
+/**
+ * Metadata about the import
+ */
+export const idiomDataMeta = ${JSON.stringify(meta, null, 4)};
+
+/**
+ * Imported idioms: { description, code, language, tags, source }
+ */
+export const importedIdioms = [${idioms.map(idiom => `\n    ${JSON.stringify(idiom)}`).join(',')}${idioms.length ? '\n' : ''}];
+
+export default importedIdioms;
+`;
+}
+
+function parseArgs(argv) {
+    const locations = Object.fromEntries(Object.entries(SOURCES).map(([id, source]) => [id, source.url]));
+    for (let i = 0; i < argv.length; i++) {
+        const id = argv[i].replace(/^--/, '');
+        if (!SOURCES[id] || argv[i + 1] === undefined) {
+            console.error(`Usage: node scripts/import-idioms.cjs [--aplcart <url|file>] [--bqncrate <url|file>]`);
+            process.exit(2);
+        }
+        locations[id] = argv[++i];
+    }
+    return locations;
+}
+
+async function importIdioms(locations) {
+    const idioms = [];
+    const sources = [];
+
+    for (const [id, source] of Object.entries(SOURCES)) {
+        console.log(`Fetching ${source.name} from ${locations[id]}...`);
+        try {
+            const text = await readSource(locations[id]);
+            const imported = parseTable(text, source.language, id);
+            console.log(`  ${imported.length} idioms`);
+            idioms.push(...imported);
+            sources.push({ id, name: source.name, url: locations[id], homepage: source.homepage, count: imported.length });
+        } catch (err) {
+            console.error(`  Failed to import ${source.name}: ${err.message}`);
+        }
+    }
+
+    if (sources.length === 0) {
+        throw new Error('No idiom source could be imported; leaving src/idiom-data.js unchanged');
+    }
+
+    const meta = { sources, importedAt: new Date().toISOString() };
+    fs.writeFileSync(JS_OUTPUT_FILE, generateJsModule(meta, idioms));
+    console.log(`\nWrote ${idioms.length} idioms to ${JS_OUTPUT_FILE}`);
+    return idioms;
+}
+
+// Run if called directly
+if (require.main === module) {
+    importIdioms(parseArgs(process.argv.slice(2))).catch(err => {
+        console.error('Error:', err.message);
+        process.exit(1);
+    });
+}
+
+module.exports = { importIdioms, parseTable, generateJsModule };
@@ -0,0 +1,90 @@
+#!/usr/bin/env node
+/**
+ * Permalink Migration
+ *
+ * Copies every permalink from one storage backend to another (see
+ * servers/permalink-store.cjs), keeping the codes so existing links still work.
+ *
+ * Usage: node scripts/migrate-permalinks.cjs [--from <spec>] [--to <spec>]
+ *   spec: json, sqlite, or <backend>:<path>
+ *   default: --from json --to sqlite (storage/permalinks.json -> storage/permalinks.db)
+ *
+ * Codes already in the destination are skipped (reported if their content
+ * differs), so the migration can be re-run while the old server is still
+ * taking new links. Then start the server with --storage=sqlite.
+ */
+
+const { createStore, contentHash } = require('../servers/permalink-store.cjs');
+
+// Permalinks written per transaction (SQLite) or file write (JSON)
+const BATCH_SIZE = 5000;
+
+function parseArgs(argv) {
+    const options = { from: 'json', to: 'sqlite' };
+    for (let i = 0; i < argv.length; i++) {
+        const arg = argv[i];
+        if ((arg === '--from' || arg === '--to') && argv[i + 1]) {
+            options[arg.slice(2)] = argv[++i];
+        } else {
+            console.error('Usage: node scripts/migrate-permalinks.cjs [--from <spec>] [--to <spec>]');
+            console.error('  spec: json, sqlite, or <backend>:<path>');
+            process.exit(2);
+        }
+    }
+    return options;
+}
+
+async function migratePermalinks(fromSpec, toSpec) {
+    const from = createStore(fromSpec);
+    const to = createStore(toSpec);
+    if (from.location === to.location) {
+        throw new Error('Source and destination are the same file');
+    }
+    console.log(`Migrating ${from.count()} permalinks`);
+    console.log(`  from ${from.location} (${from.backend})`);
+    console.log(`  to   ${to.location} (${to.backend})`);
+
+    const counts = { copied: 0, skipped: 0, conflicts: 0 };
+    let batch = [];
+    const flush = async () => {
+        await to.insertMany(batch);
+        counts.copied += batch.length;
+        batch = [];
+        console.log(`  ${counts.copied} copied...`);
+    };
+
+    for (const [id, content] of from.entries()) {
+        const existing = to.get(id);
+        if (existing) {
+            if (contentHash(existing) === contentHash(content)) {
+                counts.skipped++;
+            } else {
+                counts.conflicts++;
+                console.warn(`  Conflict: ${id} already exists with different content (kept the destination's)`);
+            }
+            continue;
+        }
+        batch.push([id, content]);
+        if (batch.length === BATCH_SIZE) await flush();
+    }
+    if (batch.length) await flush();
+
+    await from.close();
+    await to.close();
+    console.log(`\nCopied ${counts.copied}, skipped ${counts.skipped} already present, ${counts.conflicts} conflicts`);
+    return counts;
+}
+
+// Run if called directly
+if (require.main === module) {
+    const options = parseArgs(process.argv.slice(2));
+    migratePermalinks(options.from, options.to).then(
+        (counts) => process.exit(counts.conflicts > 0 ? 1 : 0),
+        (err) => {
+            console.error('Error:', err.message);
+            process.exit(1);
+        }
+    );
+}
+
+module.exports = { migratePermalinks };
@@ -0,0 +1,113 @@
+#!/usr/bin/env node
+/**
+ * Permalink Admin
+ *
+ * Inspect permalinks and take abusive ones down.
+ *
+ * Usage: node scripts/permalink-admin.cjs <command> [codes...] [--storage=<spec>] [--server=<url>]
+ *   show <code>...     print each permalink's versions, owner and code
+ *   delete <code>...   delete permalinks with all their versions and OG images
+ *
+ *   --storage=<spec>   storage to open directly (json, sqlite or <backend>:<path>;
+ *                      default: PERMALINK_STORAGE or json)
+ *   --server=<url>     delete through a running permalink server instead, using
+ *                      PERMALINK_ADMIN_TOKEN (the token the server was started with)
+ *
+ * The JSON backend is held in memory by the server, so while it runs, deletes
+ * must go through --server or the server would write the links back.
+ */
+
+const { createStore, versionId, countVersions, deletePermalink } = require('../servers/permalink-store.cjs');
+
+function parseArgs(argv) {
+    const options = { command: null, codes: [], storage: process.env.PERMALINK_STORAGE || 'json', server: null };
+    for (const arg of argv) {
+        if (arg.startsWith('--storage=')) {
+            options.storage = arg.slice('--storage='.length);
+        } else if (arg.startsWith('--server=')) {
+            options.server = arg.slice('--server='.length).replace(/\/$/, '');
+        } else if (!options.command) {
+            options.command = arg;
+        } else {
+            options.codes.push(arg);
+        }
+    }
+    if (!['show', 'delete'].includes(options.command) || options.codes.length === 0) {
+        console.error('Usage: node scripts/permalink-admin.cjs <show|delete> <code>... [--storage=<spec>] [--server=<url>]');
+        process.exit(2);
+    }
+    return options;
+}
+
+function showPermalink(store, code) {
+    if (!store.has(code)) {
+        console.log(`${code}: not found`);
+        return false;
+    }
+    const root = store.get(code);
+    const versions = countVersions(store, code);
+    console.log(`${code}: ${versions} version${versions === 1 ? '' : 's'}, ${root.owner ? 'has an owner' : 'no owner'}` +
+        (root.forkOf ? `, fork of ${root.forkOf}` : ''));
+    for (let version = 1; version <= versions; version++) {
+        const data = store.get(versionId(code, version));
+        const kind = data.problem ? 'problem' : data.notebook ? 'notebook' : data.collection ? 'collection' : data.lang;
+        console.log(`  v${version} (${kind})`);
+        for (const line of data.code.split('\n')) console.log(`    ${line}`);
+    }
+    return true;
+}
+
+async function deleteOnServer(server, code) {
+    const token = process.env.PERMALINK_ADMIN_TOKEN;
+    if (!token) throw new Error('PERMALINK_ADMIN_TOKEN is not set');
+    const response = await fetch(`${server}/p/${code}`, {
+        method: 'DELETE',
+        headers: { 'Authorization': `Bearer ${token}` }
+    });
+    const data = await response.json();
+    if (!data.success) throw new Error(data.error);
+    return data.deleted;
+}
+
+async function main(options) {
+    let failed = false;
+
+    if (options.command === 'delete' && options.server) {
+        for (const code of options.codes) {
+            try {
+                const deleted = await deleteOnServer(options.server, code);
+                console.log(`Deleted ${deleted.join(', ')}`);
+            } catch (e) {
+                console.error(`${code}: ${e.message}`);
+                failed = true;
+            }
+        }
+        return failed;
+    }
+
+    const store = createStore(options.storage);
+    for (const code of options.codes) {
+        if (options.command === 'show') {
+            if (!showPermalink(store, code)) failed = true;
+        } else if (!store.has(code)) {
+            console.error(`${code}: not found`);
+            failed = true;
+        } else {
+            const deleted = await deletePermalink(store, code);
+            console.log(`Deleted ${deleted.join(', ')}`);
+        }
+    }
+    await store.close();
+    return failed;
+}
+
+// Run if called directly
+if (require.main === module) {
+    main(parseArgs(process.argv.slice(2))).then(
+        (failed) => process.exit(failed ? 1 : 0),
+        (err) => {
+            console.error('Error:', err.message);
+            process.exit(1);
+        }
+    );
+}
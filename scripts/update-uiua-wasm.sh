@@ -73,7 +73,7 @@ edition = "2024"
 crate-type = ["cdylib"]
 
 [dependencies]
-uiua = { git = "https://github.com/uiua-lang/uiua", default-features = false, features = ["batteries", "web"] }
+uiua = { version = "=0.18.0", default-features = false, features = ["batteries", "web"] }
 wasm-bindgen = "0.2"
 serde = { version = "1", features = ["derive"] }
 serde_json = "1"
@@ -91,7 +91,10 @@ CARGO_EOF
 mkdir -p src
 cat > src/lib.rs << 'LIB_EOF'
 use serde::Serialize;
-use uiua::{Uiua, UiuaError, Value};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use uiua::{SysBackend, Uiua, UiuaError, Value};
 use wasm_bindgen::prelude::*;
 
 #[derive(Serialize)]
@@ -123,10 +126,56 @@ fn format_error(error: &UiuaError) -> String {
     error.to_string()
 }
 
+/// Backend whose stdin is the program's input data: &sc takes its next line
+/// when it runs (0 once they run out); everything else is unsupported as in
+/// the safe backend, and printing is discarded
+struct InputSys {
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl InputSys {
+    fn new(input: &str) -> Self {
+        let input = input.strip_suffix('\n').unwrap_or(input);
+        let lines = if input.is_empty() {
+            VecDeque::new()
+        } else {
+            input.split('\n').map(|line| line.trim_end_matches('\r').to_string()).collect()
+        };
+        InputSys { lines: Mutex::new(lines) }
+    }
+}
+
+impl SysBackend for InputSys {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn print_str_stdout(&self, _s: &str) -> Result<(), String> {
+        Ok(())
+    }
+    fn print_str_stderr(&self, _s: &str) -> Result<(), String> {
+        Ok(())
+    }
+    fn print_str_trace(&self, _s: &str) {}
+    fn scan_line_stdin(&self) -> Result<Option<String>, String> {
+        Ok(self.lines.lock().unwrap().pop_front())
+    }
+}
+
 #[wasm_bindgen]
 pub fn eval_uiua(code: &str) -> String {
-    let mut env = Uiua::with_safe_sys();
-    
+    run(Uiua::with_safe_sys(), code)
+}
+
+/// eval_uiua with `input` as stdin (src/runtimes.js uses it when present)
+#[wasm_bindgen]
+pub fn eval_uiua_with_input(code: &str, input: &str) -> String {
+    run(Uiua::with_backend(InputSys::new(input)), code)
+}
+
+fn run(mut env: Uiua, code: &str) -> String {
     // Try to format the code (converts keyboard prefixes to symbols)
     let formatted = uiua::format::format_str(code, &Default::default())
         .ok()
@@ -8,6 +8,7 @@
  * Routes:
  *   /api/apl/*   -> APL server (8081)
  *   /api/log/*   -> Log server (8082)
+ *   /api/eval/*  -> Multi-language eval server (8083)
  *   /api/p/*     -> Permalink server (8084)
  *   /api/image/* -> OG image server (8084)
  * 
@@ -26,6 +27,7 @@ const IS_PRODUCTION = process.argv.includes('--production');
 const SERVICES = {
     apl: { port: 8081, path: '/api/apl' },
     log: { port: 8082, path: '/api/log' },
+    eval: { port: 8083, path: '/api/eval' },
     permalink: { port: 8084, path: '/api/p' },
     image: { port: 8084, path: '/api/image' }
 };
@@ -38,8 +40,8 @@ function setCorsHeaders(res, origin) {
         : '*';             // In development, allow all
     
     res.setHeader('Access-Control-Allow-Origin', allowedOrigin);
-    res.setHeader('Access-Control-Allow-Methods', 'GET, POST, OPTIONS');
-    res.setHeader('Access-Control-Allow-Headers', 'Content-Type, Accept');
+    res.setHeader('Access-Control-Allow-Methods', 'GET, POST, PUT, DELETE, OPTIONS');
+    res.setHeader('Access-Control-Allow-Headers', 'Content-Type, Accept, Authorization');
     res.setHeader('Access-Control-Allow-Credentials', 'true');
     res.setHeader('Access-Control-Max-Age', '86400'); // 24 hours
 }
@@ -64,6 +66,9 @@ function proxyRequest(req, res, targetPort, targetPath) {
         
         // Forward the original host so upstream can generate correct absolute URLs (e.g., OG tags)
         const originalHost = req.headers.host;
+        // Append the peer to x-forwarded-for; upstream trusts only this last entry
+        const forwardedFor = req.headers['x-forwarded-for'];
+        const peer = req.socket.remoteAddress;
         const options = {
             hostname: 'localhost',
             port: targetPort,
@@ -73,6 +78,7 @@ function proxyRequest(req, res, targetPort, targetPath) {
                 ...req.headers,
                 host: `localhost:${targetPort}`,
                 'x-forwarded-host': originalHost,
+                'x-forwarded-for': forwardedFor ? `${forwardedFor}, ${peer}` : peer,
                 'x-forwarded-proto': req.headers['x-forwarded-proto'] || 'https'
             }
         };
@@ -135,6 +141,13 @@ function routeRequest(req, res) {
         return;
     }
     
+    // Eval server: /api/eval/eval -> localhost:8083/eval
+    if (pathname.startsWith('/api/eval/')) {
+        const targetPath = pathname.replace('/api/eval', '') + (parsedUrl.search || '');
+        proxyRequest(req, res, SERVICES.eval.port, targetPath || '/');
+        return;
+    }
+    
     // Permalink server: /api/p/* -> localhost:8084/p/*
     if (pathname.startsWith('/api/p')) {
         // Keep /p in the path for permalink server
@@ -155,7 +168,7 @@ function routeRequest(req, res) {
     res.writeHead(404, { 'Content-Type': 'application/json' });
     res.end(JSON.stringify({ 
         error: 'Not found',
-        hint: 'Available routes: /api/apl/*, /api/log/*, /api/p/*, /api/image/*'
+        hint: 'Available routes: /api/apl/*, /api/log/*, /api/eval/*, /api/p/*, /api/image/*'
     }));
 }
 
@@ -173,6 +186,7 @@ server.listen(GATEWAY_PORT, '0.0.0.0', () => {
 ║  Routes:                                                      ║
 ║    /api/apl/*   -> APL server (${SERVICES.apl.port})                        ║
 ║    /api/log/*   -> Log server (${SERVICES.log.port})                        ║
+║    /api/eval/*  -> Eval server (${SERVICES.eval.port})                       ║
 ║    /api/p/*     -> Permalink server (${SERVICES.permalink.port})                    ║
 ║    /api/image/* -> OG image generator (${SERVICES.image.port})                  ║
 ║    /health      -> Health check                               ║
@@ -8,6 +8,26 @@
  * 
  * With --sandbox: Uses Docker container for isolated execution (recommended for shared use)
  * With --no-sandbox: Uses local APL installation directly (default for local dev)
+ *
+ * POST /eval           { "code": "...", "session": "<id>" } -> { success, output, session }
+ * POST /session/reset  { "session": "<id>" } -> { success }
+ *
+ * With "stream": true, /eval answers with JSON lines instead: { "output": "..." }
+ * for each line the code prints (⎕←) as it is printed, then the result. The
+ * sandbox prints as it goes from its warm container; if that fails and the
+ * code runs in a one-off container, the result comes alone.
+ *
+ * "session" is optional: in sandbox mode code then runs in that session's own
+ * Safe3 namespace, so definitions persist between requests. Direct mode starts
+ * a fresh interpreter per request and reports "session": false.
+ *
+ * "input" is optional text for the program to read: the code runs with it in
+ * the character vector `input` (lines separated by ⎕UCS 10).
+ *
+ * "files" optionally holds project files by path ({ "lib.apln": "..." }):
+ * ⎕FIX 'file://lib.apln' fixes the file's source instead of reading the disk.
+ * The sandbox has no ⎕FIX, so there the file must hold one function (⎕FX);
+ * namespace and class scripts and files with several functions are an error.
  */
 
 const http = require('http');
@@ -103,24 +123,121 @@ function validateAPLCode(code) {
     }
 })();
 
+// Character vector expression for text: quoted runs of text, with line
+// breaks and other control characters as ⎕UCS
+function aplText(text) {
+    const parts = (text.match(/[\x00-\x1f\x7f]+|[^\x00-\x1f\x7f]+/g) || []).map(run =>
+        /^[\x00-\x1f\x7f]/.test(run)
+            ? `(⎕UCS ${Array.from(run, c => c.charCodeAt(0)).join(' ')})`
+            : `'${run.replace(/'/g, "''")}'`);
+    return parts.length ? ',' + parts.join(',') : "''";
+}
+
+// Statement giving the program its input data
+function inputAssignment(input) {
+    return `input←${aplText(input)}`;
+}
+
+const FIX_FILE_PATTERN = /⎕FIX\s*'file:\/\/([^']*)'/g;
+
+function projectFile(files, filePath) {
+    const text = files[filePath.replace(/^\.\//, '')];
+    return typeof text === 'string' ? text : null;
+}
+
+// ⎕FIX 'file://path' of a project file becomes ⎕FIX (⎕FX in the sandbox) of
+// the file's lines
+function fixProjectFiles(code, files) {
+    return code.replace(FIX_FILE_PATTERN, (match, filePath) => {
+        const text = projectFile(files, filePath);
+        if (text === null) return match;
+        const lines = text.replace(/\r?\n$/, '').split(/\r?\n/).map(line => `(⊂${aplText(line)})`);
+        return `${sandboxMode ? '⎕FX' : '⎕FIX'} ${lines.length === 1 ? ',' : ''}${lines.join(',')}`;
+    });
+}
+
+// Why ⎕FX can't fix a file's source as one function, or null if it can: a
+// :Namespace, :Class or :Interface script, or more than one function
+function unfixableSource(text) {
+    const lines = text.split(/\r?\n/)
+        .map(line => line.replace(/('[^']*')|⍝.*$/g, (match, quoted) => quoted || '').trim())
+        .filter(Boolean);
+    if (lines.length === 0) return 'is empty';
+    const script = /^:(namespace|class|interface)\b/i.exec(lines[0]);
+    if (script) return `is a :${script[1]} script`;
+
+    // ∇Header … ∇ and then another ∇
+    if (lines[0].startsWith('∇')) {
+        const end = lines.findIndex((line, i) => i > 0 && line.startsWith('∇'));
+        return end >= 0 && end < lines.length - 1 ? 'holds more than one function' : null;
+    }
+    // Name←{…} and then anything else
+    if (/^[\w∆⍙]+\s*←\s*\{/.test(lines[0])) {
+        let depth = 0;
+        for (let i = 0; i < lines.length - 1; i++) {
+            for (const c of lines[i].replace(/'[^']*'/g, '')) depth += c === '{' ? 1 : c === '}' ? -1 : 0;
+            if (depth <= 0) return 'holds more than one function';
+        }
+    }
+    return null;
+}
+
+// The sandbox's error for a ⎕FIX 'file://…' it can't do with ⎕FX, or null
+function sandboxFixError(code, files) {
+    for (const [, filePath] of code.matchAll(FIX_FILE_PATTERN)) {
+        const text = projectFile(files, filePath);
+        const reason = text === null ? null : unfixableSource(text);
+        if (reason) {
+            return `${filePath} ${reason}: the sandbox has no ⎕FIX, so ⎕FIX 'file://${filePath}' ` +
+                'there fixes the file with ⎕FX and needs it to hold one function';
+        }
+    }
+    return null;
+}
+
 // Execute code in sandbox
-async function executeAPLCodeSandbox(code) {
+async function executeAPLCodeSandbox(code, options = {}) {
+    if (options.files) {
+        const error = sandboxFixError(code, options.files);
+        if (error) return { success: false, output: error };
+    }
     try {
+        // The assignment runs inside Safe3.Exec like a first line of the code
+        let program = options.files ? fixProjectFiles(code, options.files) : code;
+        if (typeof options.input === 'string') program = `${inputAssignment(options.input)}\n${program}`;
         // Let the sandbox find the Dyalog path itself (handles symlinks properly)
-        const result = await sandbox.executeInSandbox('apl', code);
+        const result = await sandbox.executeInSandbox('apl', program, options);
         return result;  // Return full result with success flag
     } catch (e) {
         if (e.message === 'SANDBOX_UNAVAILABLE' || e.message === 'DYALOG_NOT_FOUND') {
             // Fall back to direct execution
             sandboxMode = false;
-            return await executeAPLCodeDirect(code);
+            return await executeAPLCodeDirect(code, options);
         }
         throw e;
     }
 }
 
 // Execute code directly (original implementation)
-function executeAPLCodeDirect(code) {
+// Whether a line of Dyalog's stdout is output rather than our own commands
+function isOutputLine(line) {
+    const trimmed = line.trim();
+    // Filter out ]boxing output (e.g., "Was OFF -style=min")
+    if (trimmed.startsWith('Was ')) return false;
+    if (trimmed.match(/^Was (ON|OFF)/)) return false;
+    if (trimmed.includes('-style=')) return false;
+    // Filter out user command status/error messages (start with *)
+    if (trimmed.startsWith('*')) return false;
+    // Filter out input echo (our commands)
+    if (trimmed.startsWith('⎕←')) return false;
+    if (trimmed.startsWith('input←')) return false;
+    return true;
+}
+
+// options.onOutput(text), when given, receives the output lines as they are
+// printed; aborting options.signal ends the interpreter
+function executeAPLCodeDirect(code, options = {}) {
+    const { onOutput, signal, input, files } = options;
     return new Promise((resolve, reject) => {
         // For multiline code, we need to handle it specially in Dyalog APL
         // Enable boxing with min style and train tree view (trains like (+/÷≢) display as tree)
@@ -134,23 +251,28 @@ function executeAPLCodeDirect(code) {
         const cleanedLines = lines.map(line => {
             const commentIndex = line.indexOf('⍝');
             return commentIndex >= 0 ? line.substring(0, commentIndex).trim() : line;
-        }).filter(line => line);  // Remove any lines that became empty after stripping comments
+        }).filter(line => line)  // Remove any lines that became empty after stripping comments
+            // Project files go in after stripping, as their source may hold ⍝
+            .map(line => files ? fixProjectFiles(line, files) : line);
         
         // Build the APL input
         // Send code directly without ⍎ (execute) wrapper to avoid quote escaping issues
+        // The input data is assigned on a line of its own before the code
+        const setup = ']boxing on -s=min -trains=tree\n' +
+            (typeof input === 'string' ? inputAssignment(input) + '\n' : '');
         let aplInput;
         if (cleanedLines.length === 0) {
             // Only comments - return empty
-            aplInput = `]boxing on -s=min -trains=tree\n⎕←''\n`;
+            aplInput = `${setup}⎕←''\n`;
         } else if (cleanedLines.length === 1) {
             // Single line - send directly with output
-            aplInput = `]boxing on -s=min -trains=tree\n⎕←${cleanedLines[0]}\n`;
+            aplInput = `${setup}⎕←${cleanedLines[0]}\n`;
         } else {
             // Multiline - wrap in a dfn and execute it
             // The dfn runs each line and returns the last expression's result
             // We use ⋄ (statement separator) to join lines within the dfn
             const joinedCode = cleanedLines.join(' ⋄ ');
-            aplInput = `]boxing on -s=min -trains=tree\n⎕←{${joinedCode}}⍬\n`;
+            aplInput = `${setup}⎕←{${joinedCode}}⍬\n`;
         }
         
         // Run in batch mode (-b) to get cleaner output
@@ -165,9 +287,15 @@ function executeAPLCodeDirect(code) {
             let stdout = '';
             let stderr = '';
             let timedOut = false;
+            let streamed = 0;  // Length of stdout already passed to onOutput
 
             aplProcess.stdout.on('data', (data) => {
                 stdout += data.toString();
+                if (!onOutput) return;
+                const end = stdout.lastIndexOf('\n') + 1;
+                const lines = stdout.slice(streamed, end).split('\n').slice(0, -1).filter(isOutputLine);
+                streamed = Math.max(streamed, end);
+                if (lines.length) onOutput(lines.join('\n') + '\n');
             });
 
             aplProcess.stderr.on('data', (data) => {
@@ -199,6 +327,7 @@ function executeAPLCodeDirect(code) {
                     if (trimmed.includes('ANGLE')) return false;
                     if (trimmed.includes('glX')) return false;
                     if (trimmed.startsWith('⎕←')) return false;  // Filter input echo
+                    if (trimmed.startsWith('input←')) return false;
                     if (trimmed.includes(']boxing')) return false;
                     return true;
                 })
@@ -211,18 +340,7 @@ function executeAPLCodeDirect(code) {
                 // Return the stdout, filtering out ]boxing command output and input echo
                 const cleanOutput = stdout
                     .split('\n')
-                    .filter(line => {
-                        const trimmed = line.trim();
-                        // Filter out ]boxing output (e.g., "Was OFF -style=min")
-                        if (trimmed.startsWith('Was ')) return false;
-                        if (trimmed.match(/^Was (ON|OFF)/)) return false;
-                        if (trimmed.includes('-style=')) return false;
-                        // Filter out user command status/error messages (start with *)
-                        if (trimmed.startsWith('*')) return false;
-                        // Filter out input echo (our commands)
-                        if (trimmed.startsWith('⎕←')) return false;
-                        return true;
-                    })
+                    .filter(isOutputLine)
                     .join('\n')
                     .replace(/^\n+/, '')   // Remove leading newlines only
                     .replace(/\n+$/, '');  // Remove trailing newlines only (preserve spaces for box-drawing)
@@ -245,8 +363,17 @@ function executeAPLCodeDirect(code) {
             reject(new Error('APL execution timed out (10 seconds)'));
         }, 10000);
 
+        // The client went away (Stop in the editor)
+        const stop = () => {
+            timedOut = true;
+            aplProcess.kill('SIGTERM');
+            reject(new Error('Stopped'));
+        };
+        if (signal) signal.addEventListener('abort', stop);
+
         aplProcess.on('close', () => {
             clearTimeout(timeout);
+            if (signal) signal.removeEventListener('abort', stop);
         });
 
         // Send input to APL
@@ -256,11 +383,11 @@ function executeAPLCodeDirect(code) {
 }
 
 // Main execution function - routes to sandbox or direct based on mode
-async function executeAPLCode(code) {
+async function executeAPLCode(code, options = {}) {
     if (sandboxMode) {
-        return executeAPLCodeSandbox(code);
+        return executeAPLCodeSandbox(code, options);
     }
-    return executeAPLCodeDirect(code);
+    return executeAPLCodeDirect(code, options);
 }
 
 const server = http.createServer((req, res) => {
@@ -275,7 +402,30 @@ const server = http.createServer((req, res) => {
         return;
     }
 
-    if (req.method === 'POST' && req.url === '/eval') {
+    if (req.method === 'POST' && req.url === '/session/reset') {
+        let body = '';
+        
+        req.on('data', (chunk) => {
+            body += chunk.toString();
+        });
+
+        req.on('end', () => {
+            try {
+                const data = JSON.parse(body);
+                if (!sandbox.isValidSessionId(data.session)) {
+                    res.writeHead(400, { 'Content-Type': 'application/json' });
+                    res.end(JSON.stringify({ success: false, output: 'Invalid session id' }));
+                    return;
+                }
+                sandbox.resetAplSession(data.session);
+                res.writeHead(200, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: true }));
+            } catch (error) {
+                res.writeHead(400, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, output: 'Invalid JSON' }));
+            }
+        });
+    } else if (req.method === 'POST' && req.url === '/eval') {
         let body = '';
         
         req.on('data', (chunk) => {
@@ -283,9 +433,12 @@ const server = http.createServer((req, res) => {
         });
 
         req.on('end', async () => {
+            let stream = false;
             try {
                 const data = JSON.parse(body);
+                stream = !!data.stream;
                 const code = data.code || '';
+                const session = data.session || null;
 
                 if (!code) {
                     res.writeHead(400, { 'Content-Type': 'application/json' });
@@ -293,29 +446,55 @@ const server = http.createServer((req, res) => {
                     return;
                 }
 
-                // Validate code for blocked commands
-                const validation = validateAPLCode(code);
+                if (session && !sandbox.isValidSessionId(session)) {
+                    res.writeHead(400, { 'Content-Type': 'application/json' });
+                    res.end(JSON.stringify({ success: false, output: 'Invalid session id' }));
+                    return;
+                }
+
+                // Project files (for ⎕FIX), text only
+                const files = data.files && typeof data.files === 'object' ? Object.fromEntries(
+                    Object.entries(data.files).filter(([, text]) => typeof text === 'string')) : null;
+
+                // Validate code for blocked commands, including the files it can fix
+                const validation = validateAPLCode([code, ...Object.values(files || {})].join('\n'));
                 if (!validation.valid) {
                     res.writeHead(200, { 'Content-Type': 'application/json' });
                     res.end(JSON.stringify({ success: false, output: validation.error }));
                     return;
                 }
 
-                const result = await executeAPLCode(code);
+                const options = session ? { session } : {};
+                if (typeof data.input === 'string') options.input = data.input;
+                if (files) options.files = files;
+                const cancel = new AbortController();
+                res.on('close', () => {
+                    if (!res.writableFinished) cancel.abort();
+                });
+                options.signal = cancel.signal;
+                if (stream) {
+                    res.writeHead(200, { 'Content-Type': 'application/x-ndjson' });
+                    options.onOutput = (text) => res.write(JSON.stringify({ output: text }) + '\n');
+                }
+                const result = await executeAPLCode(code, options);
                 
-                res.writeHead(200, { 'Content-Type': 'application/json' });
+                if (!stream) res.writeHead(200, { 'Content-Type': 'application/json' });
+                const end = (response) => res.end(JSON.stringify(response) + (stream ? '\n' : ''));
                 // Result is either {success, output} object from sandbox or string from direct
                 if (typeof result === 'object' && result !== null) {
-                    res.end(JSON.stringify({ success: result.success, output: result.output }));
+                    const response = { success: result.success, output: result.output };
+                    // Only the warm sandbox container keeps session namespaces
+                    if (session) response.session = !!result.warm;
+                    end(response);
                 } else {
-                    res.end(JSON.stringify({ success: true, output: result }));
+                    end({ success: true, output: result });
                 }
             } catch (error) {
-                res.writeHead(500, { 'Content-Type': 'application/json' });
+                if (!res.headersSent) res.writeHead(500, { 'Content-Type': 'application/json' });
                 res.end(JSON.stringify({ 
                     success: false, 
                     output: error.message || String(error) 
-                }));
+                }) + (stream ? '\n' : ''));
             }
         });
     } else {
@@ -0,0 +1,98 @@
+/**
+ * APNG Encoder
+ * Joins same-sized PNG frames (as rendered by resvg) into one looping animated
+ * PNG. Each frame's compressed image data is reused as is, so nothing is
+ * decoded or recompressed.
+ */
+
+const PNG_SIGNATURE = Buffer.from([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a]);
+
+// CRC-32 as used by PNG chunks
+const CRC_TABLE = new Int32Array(256).map((_, n) => {
+    let c = n;
+    for (let k = 0; k < 8; k++) c = c & 1 ? 0xedb88320 ^ (c >>> 1) : c >>> 1;
+    return c;
+});
+
+function crc32(buffer) {
+    let crc = -1;
+    for (const byte of buffer) crc = CRC_TABLE[(crc ^ byte) & 0xff] ^ (crc >>> 8);
+    return (crc ^ -1) >>> 0;
+}
+
+function chunk(type, data) {
+    const typeAndData = Buffer.concat([Buffer.from(type, 'latin1'), data]);
+    const length = Buffer.alloc(4);
+    length.writeUInt32BE(data.length);
+    const crc = Buffer.alloc(4);
+    crc.writeUInt32BE(crc32(typeAndData));
+    return Buffer.concat([length, typeAndData, crc]);
+}
+
+// The chunks of a PNG file, as [{ type, data }]
+function readChunks(png) {
+    if (!png.subarray(0, 8).equals(PNG_SIGNATURE)) throw new Error('Not a PNG');
+    const chunks = [];
+    let offset = 8;
+    while (offset < png.length) {
+        const length = png.readUInt32BE(offset);
+        const type = png.toString('latin1', offset + 4, offset + 8);
+        chunks.push({ type, data: png.subarray(offset + 8, offset + 8 + length) });
+        offset += 12 + length;
+    }
+    return chunks;
+}
+
+/**
+ * Encode an animated PNG that loops forever
+ * @param {Buffer[]} frames - PNG files, all the same size and format
+ * @param {number[]} delays - How long to show each frame, in milliseconds
+ * @returns {Buffer} APNG file
+ */
+function encodeApng(frames, delays) {
+    const parsed = frames.map(readChunks);
+    const header = parsed[0].find(c => c.type === 'IHDR').data;
+    const width = header.readUInt32BE(0);
+    const height = header.readUInt32BE(4);
+
+    const out = [PNG_SIGNATURE, chunk('IHDR', header)];
+    const animationControl = Buffer.alloc(8);
+    animationControl.writeUInt32BE(frames.length, 0);
+    animationControl.writeUInt32BE(0, 4);  // Loop forever
+    out.push(chunk('acTL', animationControl));
+
+    let sequence = 0;
+    parsed.forEach((chunks, i) => {
+        if (!chunks.find(c => c.type === 'IHDR').data.equals(header)) {
+            throw new Error(`Frame ${i} differs in size or format from the first`);
+        }
+        const frameControl = Buffer.alloc(26);
+        frameControl.writeUInt32BE(sequence++, 0);
+        frameControl.writeUInt32BE(width, 4);
+        frameControl.writeUInt32BE(height, 8);
+        frameControl.writeUInt32BE(0, 12);  // x offset
+        frameControl.writeUInt32BE(0, 16);  // y offset
+        frameControl.writeUInt16BE(Math.min(Math.round(delays[i]), 0xffff), 20);
+        frameControl.writeUInt16BE(1000, 22);  // Delay is in milliseconds
+        frameControl.writeUInt8(0, 24);  // Dispose: leave the frame
+        frameControl.writeUInt8(0, 25);  // Blend: replace
+        out.push(chunk('fcTL', frameControl));
+
+        // The first frame is the plain image; later ones are fdAT chunks
+        for (const { type, data } of chunks) {
+            if (type !== 'IDAT') continue;
+            if (i === 0) {
+                out.push(chunk('IDAT', data));
+            } else {
+                const number = Buffer.alloc(4);
+                number.writeUInt32BE(sequence++);
+                out.push(chunk('fdAT', Buffer.concat([number, data])));
+            }
+        }
+    });
+
+    out.push(chunk('IEND', Buffer.alloc(0)));
+    return Buffer.concat(out);
+}
+
+module.exports = { encodeApng };
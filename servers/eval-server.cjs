@@ -0,0 +1,246 @@
+#!/usr/bin/env node
+/**
+ * Multi-language Eval Server - Executes code for every Array Box language
+ * in the Docker sandbox's warm-container pool (see sandbox.cjs)
+ *
+ * Usage: node eval-server.cjs [port]
+ * Default port: 8083
+ *
+ * POST /eval       { "lang": "bqn", "code": "+´↕10" } -> { success, output }
+ * GET  /languages  -> { languages: [...] }
+ * GET  /health     -> sandbox status
+ *
+ * LeetGolf (problems from problems/*.json, see src/golf.js):
+ * GET  /golf/problems                  -> { problems: [{ id, title, languages }] }
+ * GET  /golf/problems/:id              -> { problem } (hidden cases as { hidden: true })
+ * POST /golf/submit { problem, lang, code, name }
+ *                                      -> { passed, chars, bytes, cases, rank? }
+ *                                         (hidden cases only say whether they passed)
+ * GET  /golf/leaderboard/:id[?lang=]   -> { entries: [{ rank, name, lang, code, chars, bytes }] }
+ *
+ * Unlike apl-server.cjs there is no direct-execution fallback: without Docker
+ * every request fails with "Sandbox unavailable".
+ */
+
+const http = require('http');
+const sandbox = require('./sandbox.cjs');
+const leaderboard = require('./leaderboard.cjs');
+
+const PORT = process.argv[2] ? parseInt(process.argv[2]) : 8083;
+const LANGUAGES = Object.keys(sandbox.CONFIG.images);
+
+// Limit request bodies so a single request can't exhaust server memory
+const MAX_BODY_BYTES = 256 * 1024;
+
+// Keep a warm container for every language, not just APL
+sandbox.CONFIG.prewarmLanguages = LANGUAGES;
+
+function sendJson(res, status, data) {
+    res.writeHead(status, { 'Content-Type': 'application/json' });
+    res.end(JSON.stringify(data));
+}
+
+// Read a request body as UTF-8, dropping the connection once it passes
+// MAX_BODY_BYTES (counted in bytes, not UTF-16 units)
+function readBody(req, onBody) {
+    const chunks = [];
+    let size = 0;
+    let tooLarge = false;
+    req.on('data', (chunk) => {
+        if (tooLarge) return;
+        size += chunk.length;
+        if (size > MAX_BODY_BYTES) {
+            tooLarge = true;
+            req.destroy();
+            return;
+        }
+        chunks.push(chunk);
+    });
+    req.on('end', () => {
+        if (!tooLarge) onBody(Buffer.concat(chunks).toString('utf8'));
+    });
+}
+
+// Problems are loaded once at startup; restart the server to pick up new ones
+let problems = {};
+
+async function evaluateForJudge(lang, code) {
+    const result = await sandbox.executeInSandbox(lang, code);
+    return { success: result.success, output: result.output };
+}
+
+/**
+ * Handle the LeetGolf routes; returns false if the request is not one of them
+ */
+function handleGolf(req, res) {
+    const url = new URL(req.url, 'http://localhost');
+
+    if (req.method === 'GET' && url.pathname === '/golf/problems') {
+        sendJson(res, 200, {
+            success: true,
+            problems: Object.values(problems).map(({ id, title, tests }) => ({ id, title, languages: Object.keys(tests) }))
+        });
+        return true;
+    }
+
+    const problemMatch = url.pathname.match(/^\/golf\/problems\/([\w-]+)$/);
+    if (req.method === 'GET' && problemMatch) {
+        const problem = problems[problemMatch[1]];
+        if (!problem) {
+            sendJson(res, 404, { success: false, error: 'Problem not found' });
+            return true;
+        }
+        // Hidden cases are reduced to { hidden: true }
+        leaderboard.loadGolf().then(({ publicProblem }) => sendJson(res, 200, { success: true, problem: publicProblem(problem) }));
+        return true;
+    }
+
+    const leaderboardMatch = url.pathname.match(/^\/golf\/leaderboard\/([\w-]+)$/);
+    if (req.method === 'GET' && leaderboardMatch) {
+        if (!problems[leaderboardMatch[1]]) {
+            sendJson(res, 404, { success: false, error: 'Problem not found' });
+            return true;
+        }
+        leaderboard.getLeaderboard(leaderboardMatch[1], url.searchParams.get('lang'))
+            .then(entries => sendJson(res, 200, { success: true, entries }));
+        return true;
+    }
+
+    if (req.method === 'POST' && url.pathname === '/golf/submit') {
+        readBody(req, async (body) => {
+            try {
+                const data = JSON.parse(body) || {};
+                const problem = problems[data.problem];
+                const lang = typeof data.lang === 'string' ? data.lang.toLowerCase() : '';
+                const code = data.code;
+
+                if (!problem) {
+                    sendJson(res, 404, { success: false, error: 'Problem not found' });
+                    return;
+                }
+                if (typeof code !== 'string') {
+                    sendJson(res, 400, { success: false, error: 'Code must be a string' });
+                    return;
+                }
+                if (!problem.tests[lang]) {
+                    sendJson(res, 400, { success: false, error: `${problem.title} has no tests for ${lang || '(none)'}` });
+                    return;
+                }
+
+                // judge() records evaluation errors as failed cases, so check first
+                if (!(await sandbox.isSandboxAvailable(lang))) {
+                    sendJson(res, 503, { success: false, error: 'Sandbox unavailable' });
+                    return;
+                }
+
+                // Solvers only learn whether each hidden case passed
+                const { judge, publicVerdict } = await leaderboard.loadGolf();
+                const verdict = publicVerdict(await judge(problem, lang, code, evaluateForJudge));
+                if (!verdict.passed) {
+                    sendJson(res, 200, { success: true, ...verdict });
+                    return;
+                }
+
+                const { improved, rank, saved } = await leaderboard.recordSubmission(problem.id, {
+                    name: data.name, lang, code: code.replace(/\s+$/, ''), chars: verdict.chars, bytes: verdict.bytes
+                });
+                sendJson(res, saved ? 200 : 500, {
+                    success: saved, ...verdict, rank, improved, ...(saved ? {} : { error: 'Failed to save' })
+                });
+            } catch (error) {
+                sendJson(res, 400, { success: false, error: error instanceof SyntaxError ? 'Invalid JSON' : error.message });
+            }
+        });
+        return true;
+    }
+
+    return false;
+}
+
+const server = http.createServer((req, res) => {
+    // Handle CORS
+    res.setHeader('Access-Control-Allow-Origin', '*');
+    res.setHeader('Access-Control-Allow-Methods', 'GET, POST, OPTIONS');
+    res.setHeader('Access-Control-Allow-Headers', 'Content-Type');
+
+    if (req.method === 'OPTIONS') {
+        res.writeHead(200);
+        res.end();
+        return;
+    }
+
+    if (req.method === 'GET' && req.url === '/languages') {
+        sendJson(res, 200, { languages: LANGUAGES });
+        return;
+    }
+
+    if (req.method === 'GET' && req.url === '/health') {
+        sendJson(res, 200, { status: 'ok', sandbox: sandbox.getStatus() });
+        return;
+    }
+
+    if (req.url.startsWith('/golf/') && handleGolf(req, res)) {
+        return;
+    }
+
+    if (req.method === 'POST' && req.url === '/eval') {
+        readBody(req, async (body) => {
+            try {
+                const data = JSON.parse(body) || {};
+                const requested = data.lang || data.language || '';
+                const code = data.code || '';
+
+                if (typeof requested !== 'string' || !LANGUAGES.includes(requested.toLowerCase())) {
+                    const shown = typeof requested === 'string' ? requested || '(none)' : JSON.stringify(requested);
+                    sendJson(res, 400, {
+                        success: false,
+                        output: `Unsupported language: ${shown}. Expected one of: ${LANGUAGES.join(', ')}`
+                    });
+                    return;
+                }
+                const lang = requested.toLowerCase();
+
+                if (typeof code !== 'string') {
+                    sendJson(res, 400, { success: false, output: 'Code must be a string' });
+                    return;
+                }
+
+                if (!code) {
+                    sendJson(res, 400, { success: false, output: 'No code provided' });
+                    return;
+                }
+
+                const result = await sandbox.executeInSandbox(lang, code);
+                sendJson(res, 200, { success: result.success, output: result.output });
+            } catch (error) {
+                if (error instanceof SyntaxError) {
+                    sendJson(res, 400, { success: false, output: 'Invalid JSON' });
+                    return;
+                }
+                if (error.message === 'SANDBOX_UNAVAILABLE' || error.message === 'SANDBOX_IMAGE_BUILD_FAILED') {
+                    sendJson(res, 503, { success: false, output: 'Sandbox unavailable' });
+                    return;
+                }
+                sendJson(res, 500, {
+                    success: false,
+                    output: error.message || String(error)
+                });
+            }
+        });
+    } else {
+        res.writeHead(404);
+        res.end();
+    }
+});
+
+// Listen once the problems are loaded, so no request finds the list empty
+leaderboard.loadProblems().then((loaded) => {
+    problems = loaded;
+    console.log(`Loaded ${Object.keys(problems).length} LeetGolf problems`);
+
+    server.listen(PORT, () => {
+        console.log(`Eval Server running on http://localhost:${PORT}`);
+        console.log(`Languages: ${LANGUAGES.join(', ')}`);
+        console.log('Press Ctrl+C to stop');
+    });
+});
@@ -0,0 +1,130 @@
+/**
+ * LeetGolf Leaderboard
+ * Loads problems from problems/*.json and persists accepted submissions
+ * to storage/leaderboard.json (next to storage/permalinks.json)
+ *
+ * Each player keeps their best (shortest) accepted entry per problem and language.
+ */
+
+const fs = require('fs');
+const path = require('path');
+
+const PROBLEMS_DIR = path.join(__dirname, '..', 'problems');
+const LEADERBOARD_FILE = path.join(__dirname, '..', 'storage', 'leaderboard.json');
+
+const MAX_NAME_LENGTH = 32;
+
+// src/golf.js is an ES module shared with the browser and bin/arraybox
+let golfModule = null;
+async function loadGolf() {
+    if (!golfModule) golfModule = await import('../src/golf.js');
+    return golfModule;
+}
+
+/**
+ * Load and validate every problem in problems/
+ * @returns {Promise<Object<string, object>>} - Problems by id
+ */
+async function loadProblems() {
+    const { normalizeProblem } = await loadGolf();
+    const problems = {};
+    if (!fs.existsSync(PROBLEMS_DIR)) return problems;
+
+    for (const file of fs.readdirSync(PROBLEMS_DIR).filter(f => f.endsWith('.json')).sort()) {
+        try {
+            const { problem, error } = normalizeProblem(JSON.parse(fs.readFileSync(path.join(PROBLEMS_DIR, file), 'utf8')));
+            if (error) {
+                console.error(`Skipping problems/${file}: ${error}`);
+                continue;
+            }
+            problems[problem.id] = problem;
+        } catch (e) {
+            console.error(`Skipping problems/${file}: ${e.message}`);
+        }
+    }
+    return problems;
+}
+
+// Load leaderboard from file
+function loadLeaderboard() {
+    try {
+        if (fs.existsSync(LEADERBOARD_FILE)) {
+            return JSON.parse(fs.readFileSync(LEADERBOARD_FILE, 'utf8'));
+        }
+    } catch (e) {
+        console.error('Error loading leaderboard:', e.message);
+    }
+    return {};
+}
+
+// Save leaderboard to file
+function saveLeaderboard() {
+    try {
+        const dir = path.dirname(LEADERBOARD_FILE);
+        if (!fs.existsSync(dir)) {
+            fs.mkdirSync(dir, { recursive: true });
+        }
+        fs.writeFileSync(LEADERBOARD_FILE, JSON.stringify(leaderboard, null, 2));
+        return true;
+    } catch (e) {
+        console.error('Error saving leaderboard:', e.message);
+        return false;
+    }
+}
+
+// In-memory cache: problem id -> [{ name, lang, code, chars, bytes, submittedAt }]
+let leaderboard = loadLeaderboard();
+
+/**
+ * Clean up a player name (falls back to "anonymous")
+ */
+function cleanName(name) {
+    const cleaned = typeof name === 'string' ? name.replace(/[\u0000-\u001f]/g, '').trim().slice(0, MAX_NAME_LENGTH) : '';
+    return cleaned || 'anonymous';
+}
+
+/**
+ * Ranked entries for a problem
+ * @param {string} problemId - Problem id
+ * @param {string} [lang] - Only this language (ranks are then per language)
+ */
+async function getLeaderboard(problemId, lang) {
+    const { rankEntries } = await loadGolf();
+    const entries = (leaderboard[problemId] || []).filter(entry => !lang || entry.lang === lang);
+    return rankEntries(entries).map((entry, index) => ({ rank: index + 1, ...entry }));
+}
+
+/**
+ * Record an accepted submission, keeping each player's best per language
+ * @returns {Promise<{improved: boolean, rank: number, saved: boolean}>} - rank is
+ *   the player's place among submissions in the same language
+ */
+async function recordSubmission(problemId, { name, lang, code, chars, bytes }) {
+    const player = cleanName(name);
+    const entries = leaderboard[problemId] || (leaderboard[problemId] = []);
+    const existing = entries.findIndex(entry => entry.name === player && entry.lang === lang);
+    const entry = { name: player, lang, code, chars, bytes, submittedAt: new Date().toISOString() };
+
+    let improved = true;
+    if (existing === -1) {
+        entries.push(entry);
+    } else if (bytes < entries[existing].bytes || (bytes === entries[existing].bytes && chars < entries[existing].chars)) {
+        entries[existing] = entry;
+    } else {
+        improved = false;
+    }
+
+    const saved = improved ? saveLeaderboard() : true;
+    const ranked = await getLeaderboard(problemId, lang);
+    const rank = ranked.find(e => e.name === player).rank;
+    return { improved, rank, saved };
+}
+
+module.exports = {
+    loadGolf,
+    loadProblems,
+    getLeaderboard,
+    recordSubmission,
+    cleanName,
+    LEADERBOARD_FILE
+};
@@ -6,6 +6,7 @@
 const { Resvg } = require('@resvg/resvg-js');
 const fs = require('fs');
 const path = require('path');
+const { encodeApng } = require('./apng.cjs');
 
 // Satori is an ES module, so we need to use dynamic import
 let satoriModule = null;
@@ -19,23 +20,33 @@ async function getSatori() {
 // Padding around content
 const PADDING = 50;
 
-// Colors from theme.css (Dracula palette)
-const COLORS = {
-    bgGradientStart: '#111827',  // gray-900
-    bgGradientMid: '#1f2937',    // gray-800
-    bgGradientEnd: '#111827',    // gray-900
-    text: '#e5e7eb',             // gray-200
-    border: '#4b5563',           // gray-600
-    inputBg: '#1f2937',          // gray-800
-    // Dracula palette for syntax
-    cyan: '#8BE9FD',
-    green: '#50FA7B',
-    yellow: '#F1FA8C',
-    pink: '#FF79C6',
-    purple: '#BD93F9',
-    comment: '#6272A4',
-    fg: '#F8F8F2',
-};
+// Themes are ES modules too (src/themes.js, shared with the editor)
+let themesModule = null;
+async function getThemes() {
+    if (!themesModule) {
+        themesModule = await import('../src/themes.js');
+    }
+    return themesModule;
+}
+
+/**
+ * Image colors for a theme: the page colors used here, plus one color per
+ * syntax role (number, comment, default, string, function, ...)
+ * @param {string} [theme] - Theme id from src/themes.js (default theme if unknown)
+ */
+async function themeColors(theme) {
+    const { getTheme } = await getThemes();
+    const { ui, syntax } = getTheme(theme);
+    return {
+        bgGradientStart: ui.bgGradient[0],
+        bgGradientMid: ui.bgGradient[1],
+        bgGradientEnd: ui.bgGradient[2],
+        text: ui.text,
+        border: ui.border,
+        inputBg: ui.inputBg,
+        ...syntax,
+    };
+}
 
 // Syntax highlighting rules (simplified from syntax.js)
 const syntaxRules = {
@@ -110,34 +121,35 @@ const syntaxRules = {
 };
 
 // Get color for a single character based on syntax rules
-function getCharColor(char, lang) {
+// colors: from themeColors (one color per syntax role)
+function getCharColor(char, lang, colors) {
     const rules = syntaxRules[lang];
-    if (!rules) return COLORS.fg;
+    if (!rules) return colors.default;
     
     // Check number
-    if (/\d/.test(char)) return COLORS.purple;
+    if (/\d/.test(char)) return colors.number;
     
     // Check constants
-    if (rules.constants && rules.constants.includes(char)) return COLORS.purple;
+    if (rules.constants && rules.constants.includes(char)) return colors.number;
     
     if (lang === 'uiua') {
         // Uiua has different semantics
-        if (rules.monadic && rules.monadic.includes(char)) return COLORS.cyan;
-        if (rules.functions && rules.functions.includes(char)) return COLORS.green;
-        if (rules.dyadic && rules.dyadic.includes(char)) return COLORS.yellow;
-        if (rules.modifier && rules.modifier.includes(char)) return COLORS.pink;
+        if (rules.monadic && rules.monadic.includes(char)) return colors.uiuaFunctionMonadic;
+        if (rules.functions && rules.functions.includes(char)) return colors.uiuaFunctionDyadic;
+        if (rules.dyadic && rules.dyadic.includes(char)) return colors.uiuaModifierMonadic;
+        if (rules.modifier && rules.modifier.includes(char)) return colors.uiuaModifierDyadic;
     } else {
         // Other languages
-        if (rules.functions && rules.functions.includes(char)) return COLORS.cyan;
-        if (rules.monadic && rules.monadic.includes(char)) return COLORS.green;
-        if (rules.dyadic && rules.dyadic.includes(char)) return COLORS.yellow;
+        if (rules.functions && rules.functions.includes(char)) return colors.function;
+        if (rules.monadic && rules.monadic.includes(char)) return colors.modifierMonadic;
+        if (rules.dyadic && rules.dyadic.includes(char)) return colors.modifierDyadic;
     }
     
-    return COLORS.fg;
+    return colors.default;
 }
 
 // Tokenize a line with pattern-based syntax highlighting
-function tokenizeLine(line, lang) {
+function tokenizeLine(line, lang, colors) {
     const rules = syntaxRules[lang];
     const tokens = [];
     let i = 0;
@@ -148,7 +160,7 @@ function tokenizeLine(line, lang) {
         // Check for comment (rest of line)
         const commentChar = rules?.comments?.[0];
         if (commentChar && line[i] === commentChar) {
-            tokens.push({ text: line.slice(i), color: COLORS.comment });
+            tokens.push({ text: line.slice(i), color: colors.comment });
             break;
         }
         
@@ -161,7 +173,7 @@ function tokenizeLine(line, lang) {
                 j++;
             }
             if (j < line.length) j++; // include closing quote
-            tokens.push({ text: line.slice(i, j), color: COLORS.yellow });
+            tokens.push({ text: line.slice(i, j), color: colors.string });
             i = j;
             continue;
         }
@@ -169,7 +181,7 @@ function tokenizeLine(line, lang) {
         // Check for 2-modifier identifier (_Name_)
         const twoModMatch = remaining.match(/^_[A-Za-z][A-Za-z0-9]*_/);
         if (twoModMatch) {
-            tokens.push({ text: twoModMatch[0], color: COLORS.yellow });
+            tokens.push({ text: twoModMatch[0], color: colors.modifierDyadic });
             i += twoModMatch[0].length;
             continue;
         }
@@ -177,7 +189,7 @@ function tokenizeLine(line, lang) {
         // Check for 1-modifier identifier (_name)
         const oneModMatch = remaining.match(/^_[A-Za-z][A-Za-z0-9]*/);
         if (oneModMatch) {
-            tokens.push({ text: oneModMatch[0], color: COLORS.green });
+            tokens.push({ text: oneModMatch[0], color: colors.modifierMonadic });
             i += oneModMatch[0].length;
             continue;
         }
@@ -185,7 +197,7 @@ function tokenizeLine(line, lang) {
         // Check for function identifier (Capitalized)
         const funcMatch = remaining.match(/^[A-Z][A-Za-z0-9]*/);
         if (funcMatch) {
-            tokens.push({ text: funcMatch[0], color: COLORS.cyan });
+            tokens.push({ text: funcMatch[0], color: colors.function });
             i += funcMatch[0].length;
             continue;
         }
@@ -193,14 +205,14 @@ function tokenizeLine(line, lang) {
         // Check for number
         const numMatch = remaining.match(/^¯?(\d+\.?\d*|\.\d+)(e[+-]?\d+)?/i);
         if (numMatch) {
-            tokens.push({ text: numMatch[0], color: COLORS.purple });
+            tokens.push({ text: numMatch[0], color: colors.number });
             i += numMatch[0].length;
             continue;
         }
         
         // Single character - use character-based coloring
         const char = line[i];
-        tokens.push({ text: char, color: getCharColor(char, lang) });
+        tokens.push({ text: char, color: getCharColor(char, lang, colors) });
         i++;
     }
     
@@ -209,13 +221,23 @@ function tokenizeLine(line, lang) {
 
 // Create colored text spans for Satori, handling newlines
 // compact: when true (for APL train trees), use gap:0 and tight styling so box-drawing chars connect
-function createColoredTextElements(text, lang, compact = false) {
+// visible: when set, only the first `visible` characters are drawn (newlines count as
+// one); later lines stay as empty lines, and tokens keep the colors of the full text
+function createColoredTextElements(text, lang, colors, compact = false, visible = null) {
     const lines = text.split('\n');
+    let remaining = visible === null ? Infinity : visible;
     
     // Always wrap each line in a flex div (even single lines)
     // This ensures consistent layout with flexDirection: column container
     return lines.map((line, idx) => {
-        const tokens = tokenizeLine(line, lang);
+        const tokens = [];
+        for (const token of tokenizeLine(line, lang, colors)) {
+            const chars = [...token.text];
+            if (remaining <= 0) break;
+            tokens.push(chars.length > remaining ? { ...token, text: chars.slice(0, remaining).join('') } : token);
+            remaining -= chars.length;
+        }
+        remaining--;
         const lineElements = tokens.map((token, tidx) => ({
             type: 'span',
             props: {
@@ -230,7 +252,7 @@ function createColoredTextElements(text, lang, compact = false) {
             lineElements.push({
                 type: 'span',
                 props: {
-                    style: { color: COLORS.fg },
+                    style: { color: colors.default },
                     children: ' ',
                 },
             });
@@ -248,10 +270,18 @@ function createColoredTextElements(text, lang, compact = false) {
     });
 }
 
+// Text of an HTML table cell (array-display.js escapes &, < and >)
+function cellText(html) {
+    return html.replace(/&lt;/g, '<').replace(/&gt;/g, '>').replace(/&amp;/g, '&');
+}
+
 // Parse HTML table and convert to Satori table element
 // Returns { element, width, height } or null
-function parseHtmlTableToSatori(html) {
+// Only a single flat table is drawn; nested boxes and stacked slices
+// (src/array-display.js) fall back to the text result
+function parseHtmlTableToSatori(html, colors) {
     if (!html || !html.includes('<table')) return null;
+    if (html.split('<table').length > 2 || html.includes('<details')) return null;
     
     // Extract table rows
     const rows = [];
@@ -261,7 +291,7 @@ function parseHtmlTableToSatori(html) {
         const cells = [];
         const cellMatches = rowMatch[1].matchAll(/<td[^>]*>(.*?)<\/td>/gs);
         for (const cellMatch of cellMatches) {
-            cells.push(cellMatch[1]);
+            cells.push(cellText(cellMatch[1]));
         }
         if (cells.length > 0) {
             rows.push(cells);
@@ -300,11 +330,11 @@ function parseHtmlTableToSatori(html) {
                         display: 'flex',
                         alignItems: 'center',
                         justifyContent: 'center',
-                        border: `1px solid ${COLORS.fg}`,
-                        borderTop: rowIdx === 0 ? `1px solid ${COLORS.fg}` : 'none',
-                        borderLeft: colIdx === 0 ? `1px solid ${COLORS.fg}` : 'none',
+                        border: `1px solid ${colors.default}`,
+                        borderTop: rowIdx === 0 ? `1px solid ${colors.default}` : 'none',
+                        borderLeft: colIdx === 0 ? `1px solid ${colors.default}` : 'none',
                         fontSize: '28px',
-                        color: COLORS.fg,
+                        color: colors.default,
                         fontFamily: 'ArrayLang',
                     },
                     children: cell,
@@ -388,30 +418,73 @@ function loadLogoAsDataUri(lang) {
     }
 }
 
+// The font list for satori when everything is drawn in the language's font
+function arrayLangFonts(lang) {
+    return [{ name: 'ArrayLang', data: loadFont(lang), weight: 400, style: 'normal' }];
+}
+
+/**
+ * Render a satori element tree
+ * With format 'svg' the text is kept as <text> rather than outlined, and the
+ * fonts are embedded as data: URLs so it still draws in the array font
+ * @param {object} element - Satori element tree
+ * @param {object} options
+ * @param {number} options.width
+ * @param {number} [options.height] - Default: the height of the content
+ * @param {Array<object>} options.fonts - Satori fonts ({ name, data, weight, style })
+ * @param {object} options.colors - From themeColors (the PNG background)
+ * @param {string} [options.format='png'] - 'png' or 'svg'
+ * @returns {Promise<Buffer|string>} PNG image buffer, or SVG source
+ */
+async function renderImage(element, { width, height, fonts, colors, format = 'png' }) {
+    const satori = await getSatori();
+    const svg = await satori(element, { width, height, fonts, embedFont: format !== 'svg' });
+    
+    if (format === 'svg') {
+        const fontFaces = fonts.map(font =>
+            `@font-face{font-family:'${font.name}';src:url(data:font/ttf;base64,${font.data.toString('base64')})}`
+        ).join('');
+        return svg.replace(/^<svg[^>]*>/, (open) => `${open}<defs><style>${fontFaces}</style></defs>`);
+    }
+    
+    // Convert SVG to PNG
+    const resvg = new Resvg(svg, {
+        background: colors.bgGradientStart,
+        fitTo: {
+            mode: 'width',
+            value: width,
+        },
+    });
+    return resvg.render().asPng();
+}
+
 /**
  * Generate an OG image for a permalink
  * @param {string} code - The code snippet
  * @param {string} lang - The language (bqn, uiua, apl, j, kap, tinyapl)
  * @param {string} [result] - Optional result to display (plain text)
  * @param {string} [resultHtml] - Optional HTML result (for TinyAPL tables)
- * @returns {Promise<Buffer>} PNG image buffer
+ * @param {object} [options]
+ * @param {string} [options.format='png'] - 'png', or 'svg' to keep the text as text
+ * @param {string} [options.theme] - Theme id from src/themes.js (default: the default theme)
+ * @returns {Promise<Buffer|string>} PNG image buffer (SVG source for 'svg')
  */
-async function generateOGImage(code, lang, result = null, resultHtml = null) {
-    const fontData = loadFont(lang);
+async function generateOGImage(code, lang, result = null, resultHtml = null, { format = 'png', theme } = {}) {
+    const colors = await themeColors(theme);
     const logoDataUri = loadLogoAsDataUri(lang);
     
     // Use full code - image will size dynamically
     const displayCode = code;
     
     // Try to parse HTML table for visual display (TinyAPL)
-    const tableInfo = resultHtml ? parseHtmlTableToSatori(resultHtml) : null;
+    const tableInfo = resultHtml ? parseHtmlTableToSatori(resultHtml, colors) : null;
     const tableElement = tableInfo ? tableInfo.element : null;
     
     // Use full result - image will size dynamically, trim trailing whitespace for centering
     const displayResult = (!tableElement && result) ? trimTrailingWhitespace(result) : null;
     
     // Create colored code elements
-    const codeElements = createColoredTextElements(displayCode, lang);
+    const codeElements = createColoredTextElements(displayCode, lang, colors);
     
     // Calculate dimensions
     const codeFontSize = 48;
@@ -542,7 +615,7 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
                                 props: {
                                     style: {
                                         fontSize: '28px',
-                                        color: COLORS.text,
+                                        color: colors.text,
                                         fontWeight: 600,
                                     },
                                     children: 'ArrayBox',
@@ -556,8 +629,8 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
                     type: 'div',
                     props: {
                         style: {
-                            background: COLORS.inputBg,
-                            border: `3px solid ${COLORS.border}`,
+                            background: colors.inputBg,
+                            border: `3px solid ${colors.border}`,
                             borderRadius: '16px',
                             padding: `${boxPadding}px`,
                             display: 'flex',
@@ -590,8 +663,8 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
             type: 'div',
             props: {
                 style: {
-                    background: COLORS.inputBg,
-                    border: `3px solid ${COLORS.border}`,
+                    background: colors.inputBg,
+                    border: `3px solid ${colors.border}`,
                     borderRadius: '16px',
                     padding: `${boxPadding}px`,
                     display: 'flex',
@@ -604,7 +677,7 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
     } else if (displayResult) {
         const resultInnerStyle = {
             fontSize: `${resultFontSize}px`,
-            color: COLORS.fg,
+            color: colors.default,
             fontFamily: 'ArrayLang',
             lineHeight: resultLineHeight,
             whiteSpace: 'pre',
@@ -617,8 +690,8 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
             type: 'div',
             props: {
                 style: {
-                    background: COLORS.inputBg,
-                    border: `3px solid ${COLORS.border}`,
+                    background: colors.inputBg,
+                    border: `3px solid ${colors.border}`,
                     borderRadius: '16px',
                     padding: `${boxPadding}px`,
                     display: 'flex',
@@ -652,9 +725,7 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
         },
     ] : [leftSide];
     
-    // Create the SVG using satori
-    const satori = await getSatori();
-    const svg = await satori(
+    return renderImage(
         {
             type: 'div',
             props: {
@@ -665,37 +736,14 @@ async function generateOGImage(code, lang, result = null, resultHtml = null) {
                     flexDirection: 'column',
                     alignItems: 'center',
                     justifyContent: 'center',
-                    background: `linear-gradient(135deg, ${COLORS.bgGradientStart} 0%, ${COLORS.bgGradientMid} 50%, ${COLORS.bgGradientEnd} 100%)`,
+                    background: `linear-gradient(135deg, ${colors.bgGradientStart} 0%, ${colors.bgGradientMid} 50%, ${colors.bgGradientEnd} 100%)`,
                     padding: `${PADDING}px`,
                 },
                 children,
             },
         },
-        {
-            width: WIDTH,
-            height: HEIGHT,
-            fonts: [
-                {
-                    name: 'ArrayLang',
-                    data: fontData,
-                    weight: 400,
-                    style: 'normal',
-                },
-            ],
-        }
+        { width: WIDTH, height: HEIGHT, fonts: arrayLangFonts(lang), colors, format }
     );
-    
-    // Convert SVG to PNG
-    const resvg = new Resvg(svg, {
-        background: COLORS.bgGradientStart,
-        fitTo: {
-            mode: 'width',
-            value: WIDTH,
-        },
-    });
-    
-    const pngData = resvg.render();
-    return pngData.asPng();
 }
 
 /**
@@ -714,26 +762,29 @@ function isAplTrainTree(text) {
 }
 
 /**
- * Generate a vertical layout image (for clipboard copy)
+ * Build the vertical layout (header, code box, result box)
  * No forced aspect ratio - just wraps content with padding
  * @param {string} code - The code snippet
  * @param {string} lang - The language
  * @param {string} [result] - Optional result to display (plain text)
  * @param {string} [resultHtml] - Optional HTML result (for TinyAPL tables)
- * @returns {Promise<Buffer>} PNG image buffer
+ * @param {object} colors - From themeColors
+ * @param {object} [frame] - For animation frames: { typed, showResult } draws only
+ *   the first `typed` characters of the code and the result only if `showResult`,
+ *   with every box already at its final size so frames line up
+ * @returns {{element: object, width: number, height: number}}
  */
-async function generateVerticalImage(code, lang, result = null, resultHtml = null) {
-    const fontData = loadFont(lang);
+function buildVerticalLayout(code, lang, result, resultHtml, colors, frame = null) {
     const logoDataUri = loadLogoAsDataUri(lang);
     
     const displayCode = code;
-    const tableInfo = resultHtml ? parseHtmlTableToSatori(resultHtml) : null;
+    const tableInfo = resultHtml ? parseHtmlTableToSatori(resultHtml, colors) : null;
     const tableElement = tableInfo ? tableInfo.element : null;
     // Trim trailing whitespace from result for proper centering
     const displayResult = (!tableElement && result) ? trimTrailingWhitespace(result) : null;
     
     // Create colored code elements
-    const codeElements = createColoredTextElements(displayCode, lang);
+    const codeElements = createColoredTextElements(displayCode, lang, colors, false, frame ? frame.typed : null);
     
     // Calculate dimensions
     const codeFontSize = 48;
@@ -819,7 +870,7 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
                     props: {
                         style: {
                             fontSize: '28px',
-                            color: COLORS.text,
+                            color: colors.text,
                             fontWeight: 600,
                         },
                         children: 'ArrayBox',
@@ -835,8 +886,8 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
         props: {
             style: {
                 width: `${unifiedBoxWidth}px`,
-                background: COLORS.inputBg,
-                border: `3px solid ${COLORS.border}`,
+                background: colors.inputBg,
+                border: `3px solid ${colors.border}`,
                 borderRadius: '16px',
                 padding: `${boxPadding}px`,
                 display: 'flex',
@@ -854,6 +905,9 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
                         display: 'flex',
                         flexDirection: 'column',
                         alignItems: 'flex-start',
+                        // Frames pin the code to its final size so typing doesn't recenter it
+                        width: frame ? `${codeContentWidth}px` : undefined,
+                        height: frame ? `${codeContentHeight}px` : undefined,
                     },
                     children: codeElements,
                 },
@@ -869,8 +923,8 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
             props: {
                 style: {
                     width: `${unifiedBoxWidth}px`,
-                    background: COLORS.inputBg,
-                    border: `3px solid ${COLORS.border}`,
+                    background: colors.inputBg,
+                    border: `3px solid ${colors.border}`,
                     borderRadius: '16px',
                     padding: `${boxPadding}px`,
                     display: 'flex',
@@ -885,7 +939,7 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
         // APL train tree: use syntax-colored glyphs (same as in-browser); otherwise plain lines
         const useColoredResult = lang === 'apl' && isAplTrainTree(displayResult);
         const resultLineElements = useColoredResult
-            ? createColoredTextElements(displayResult, 'apl', true)
+            ? createColoredTextElements(displayResult, 'apl', colors, true)
             : displayResult.split('\n').map((line, idx) => ({
                 type: 'div',
                 props: {
@@ -900,8 +954,8 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
             props: {
                 style: {
                     width: `${unifiedBoxWidth}px`,
-                    background: COLORS.inputBg,
-                    border: `3px solid ${COLORS.border}`,
+                    background: colors.inputBg,
+                    border: `3px solid ${colors.border}`,
                     borderRadius: '16px',
                     padding: `${boxPadding}px`,
                     display: 'flex',
@@ -914,7 +968,7 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
                     props: {
                         style: {
                             fontSize: `${resultFontSize}px`,
-                            color: COLORS.fg,
+                            color: colors.default,
                             fontFamily: 'ArrayLang',
                             lineHeight: resultLineHeight,
                             display: 'flex',
@@ -930,49 +984,211 @@ async function generateVerticalImage(code, lang, result = null, resultHtml = nul
         };
     }
     
-    // Create the SVG using satori
-    const satori = await getSatori();
-    const svg = await satori(
-        {
+    const showResult = !frame || frame.showResult;
+    return {
+        element: {
             type: 'div',
             props: {
                 style: {
                     width: '100%',
+                    height: frame ? '100%' : undefined,
                     display: 'flex',
                     flexDirection: 'column',
                     alignItems: 'center',
                     justifyContent: 'flex-start',
-                    background: `linear-gradient(135deg, ${COLORS.bgGradientStart} 0%, ${COLORS.bgGradientMid} 50%, ${COLORS.bgGradientEnd} 100%)`,
+                    background: `linear-gradient(135deg, ${colors.bgGradientStart} 0%, ${colors.bgGradientMid} 50%, ${colors.bgGradientEnd} 100%)`,
                     padding: `${PADDING}px`,
                     gap: `${gap}px`,
                 },
-                children: [header, codeBox, resultBox].filter(Boolean),
+                children: [header, codeBox, showResult ? resultBox : null].filter(Boolean),
             },
         },
+        width: WIDTH,
+        height: Math.ceil(HEIGHT),
+    };
+}
+
+/**
+ * Generate a vertical layout image (for clipboard copy)
+ * @param {string} code - The code snippet
+ * @param {string} lang - The language
+ * @param {string} [result] - Optional result to display (plain text)
+ * @param {string} [resultHtml] - Optional HTML result (for TinyAPL tables)
+ * @param {object} [options]
+ * @param {string} [options.format='png'] - 'png', or 'svg' to keep the text as text
+ * @param {string} [options.theme] - Theme id from src/themes.js (default: the default theme)
+ * @returns {Promise<Buffer|string>} PNG image buffer (SVG source for 'svg')
+ */
+async function generateVerticalImage(code, lang, result = null, resultHtml = null, { format = 'png', theme } = {}) {
+    const colors = await themeColors(theme);
+    const layout = buildVerticalLayout(code, lang, result, resultHtml, colors);
+    return renderImage(layout.element, { width: layout.width, fonts: arrayLangFonts(lang), colors, format });
+}
+
+// Typing animation: most frames spent typing (longer code types several
+// characters a frame), and how long frames show, in milliseconds
+const TYPING_MAX_FRAMES = 60;
+const TYPING_FRAME_DELAY = 70;
+const TYPING_START_DELAY = 600;
+const TYPING_END_DELAY = 3000;
+
+/**
+ * Generate the vertical layout as an animation: the code is typed out
+ * character by character, then the result appears and stays for a few
+ * seconds before it loops
+ * @param {string} code - The code snippet
+ * @param {string} lang - The language
+ * @param {string} [result] - Optional result to display (plain text)
+ * @param {string} [resultHtml] - Optional HTML result (for TinyAPL tables)
+ * @param {object} [options]
+ * @param {string} [options.theme] - Theme id from src/themes.js (default: the default theme)
+ * @returns {Promise<Buffer>} Animated PNG (APNG) buffer
+ */
+async function generateTypingAnimation(code, lang, result = null, resultHtml = null, { theme } = {}) {
+    const colors = await themeColors(theme);
+    const fonts = arrayLangFonts(lang);
+    const total = [...code].length;
+    const step = Math.max(1, Math.ceil(total / TYPING_MAX_FRAMES));
+    
+    const frames = [];
+    for (let typed = 0; typed < total; typed += step) {
+        frames.push({ typed, showResult: false });
+    }
+    frames.push({ typed: total, showResult: false });
+    if (result || resultHtml) frames.push({ typed: total, showResult: true });
+    
+    const images = [];
+    for (const frame of frames) {
+        const layout = buildVerticalLayout(code, lang, result, resultHtml, colors, frame);
+        images.push(await renderImage(layout.element, { width: layout.width, height: layout.height, fonts, colors }));
+    }
+    const delays = frames.map((frame, i) =>
+        i === 0 ? TYPING_START_DELAY : i === frames.length - 1 ? TYPING_END_DELAY : TYPING_FRAME_DELAY
+    );
+    return encodeApng(images, delays);
+}
+
+// Snippets shown in a collection preview, and code lines per snippet
+const COLLECTION_PREVIEW_SNIPPETS = 3;
+const COLLECTION_PREVIEW_LINES = 2;
+
+/**
+ * Generate an OG image for a collection: its title and the first few snippets
+ * (logo, name and the start of the code), plus how many more there are
+ * @param {object} collection - { title?, snippets: [{ title?, lang, code }] }
+ * @param {object} [options]
+ * @param {string} [options.format='png'] - 'png', or 'svg' to keep the text as text
+ * @param {string} [options.theme] - Theme id from src/themes.js (default: the default theme)
+ * @returns {Promise<Buffer|string>} PNG image buffer (SVG source for 'svg')
+ */
+async function generateCollectionOGImage(collection, { format = 'png', theme } = {}) {
+    const colors = await themeColors(theme);
+    const WIDTH = 1200;
+    const HEIGHT = 630;
+    const shown = collection.snippets.slice(0, COLLECTION_PREVIEW_SNIPPETS);
+    const more = collection.snippets.length - shown.length;
+
+    // Each language's code is drawn in its own font
+    const langs = [...new Set(shown.map(snippet => snippet.lang))];
+    const fonts = langs.map(lang => ({
+        name: `ArrayLang-${lang}`,
+        data: loadFont(lang),
+        weight: 400,
+        style: 'normal',
+    }));
+
+    const rows = shown.map(snippet => {
+        const lines = trimTrailingWhitespace(snippet.code).split('\n');
+        const preview = lines.slice(0, COLLECTION_PREVIEW_LINES)
+            .map(line => line.length > 48 ? line.slice(0, 47) + '…' : line)
+            .join('\n') + (lines.length > COLLECTION_PREVIEW_LINES ? '\n…' : '');
+        const logoDataUri = loadLogoAsDataUri(snippet.lang);
+        return {
+            type: 'div',
+            props: {
+                style: {
+                    display: 'flex',
+                    alignItems: 'center',
+                    gap: '24px',
+                    background: colors.inputBg,
+                    border: `3px solid ${colors.border}`,
+                    borderRadius: '12px',
+                    padding: '16px 24px',
+                },
+                children: [
+                    logoDataUri ? {
+                        type: 'img',
+                        props: { src: logoDataUri, width: 48, height: 48, style: { objectFit: 'contain' } },
+                    } : null,
+                    {
+                        type: 'div',
+                        props: {
+                            style: { display: 'flex', flexDirection: 'column', gap: '4px' },
+                            children: [
+                                {
+                                    type: 'div',
+                                    props: {
+                                        style: { fontSize: '22px', color: colors.comment },
+                                        children: snippet.title || getLangDisplayName(snippet.lang),
+                                    },
+                                },
+                                {
+                                    type: 'div',
+                                    props: {
+                                        style: {
+                                            display: 'flex',
+                                            flexDirection: 'column',
+                                            fontFamily: `ArrayLang-${snippet.lang}`,
+                                            fontSize: '30px',
+                                            lineHeight: 1.2,
+                                            whiteSpace: 'pre',
+                                        },
+                                        children: createColoredTextElements(preview, snippet.lang, colors),
+                                    },
+                                },
+                            ],
+                        },
+                    },
+                ].filter(Boolean),
+            },
+        };
+    });
+
+    return renderImage(
         {
-            width: WIDTH,
-            fonts: [
-                {
-                    name: 'ArrayLang',
-                    data: fontData,
-                    weight: 400,
-                    style: 'normal',
+            type: 'div',
+            props: {
+                style: {
+                    width: '100%',
+                    height: '100%',
+                    display: 'flex',
+                    flexDirection: 'column',
+                    justifyContent: 'center',
+                    gap: '20px',
+                    background: `linear-gradient(135deg, ${colors.bgGradientStart} 0%, ${colors.bgGradientMid} 50%, ${colors.bgGradientEnd} 100%)`,
+                    padding: `${PADDING}px`,
                 },
-            ],
-        }
-    );
-    
-    // Convert SVG to PNG
-    const resvg = new Resvg(svg, {
-        background: COLORS.bgGradientStart,
-        fitTo: {
-            mode: 'width',
-            value: WIDTH,
+                children: [
+                    {
+                        type: 'div',
+                        props: {
+                            style: { fontSize: '36px', color: colors.text, fontWeight: 600 },
+                            children: `ArrayBox · ${collection.title || 'Collection'}`,
+                        },
+                    },
+                    ...rows,
+                    more > 0 ? {
+                        type: 'div',
+                        props: {
+                            style: { fontSize: '24px', color: colors.comment },
+                            children: `+ ${more} more`,
+                        },
+                    } : null,
+                ].filter(Boolean),
+            },
         },
-    });
-    
-    const pngData = resvg.render();
-    return pngData.asPng();
+        { width: WIDTH, height: HEIGHT, fonts, colors, format }
+    );
 }
 
 /**
@@ -999,9 +1215,27 @@ async function generateAndSaveOGImage(shortCode, code, lang, result = null, resu
     return imagePath;
 }
 
+/**
+ * Generate and save the OG image for a collection
+ * @param {string} shortCode - The permalink code (e.g., 'wkZ7')
+ * @param {object} collection - { title?, snippets }
+ * @returns {Promise<string>} Path to the saved image
+ */
+async function generateAndSaveCollectionOGImage(shortCode, collection) {
+    const ogDir = path.join(__dirname, '..', 'storage', 'og');
+    fs.mkdirSync(ogDir, { recursive: true });
+
+    const imagePath = path.join(ogDir, `${shortCode}.png`);
+    fs.writeFileSync(imagePath, await generateCollectionOGImage(collection));
+    return imagePath;
+}
+
 module.exports = {
     generateOGImage,
     generateVerticalImage,
+    generateTypingAnimation,
+    generateCollectionOGImage,
     generateAndSaveOGImage,
+    generateAndSaveCollectionOGImage,
     getLangDisplayName,
 };
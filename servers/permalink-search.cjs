@@ -0,0 +1,176 @@
+/**
+ * Permalink Search
+ * In-memory index over the latest version of every permalink, built from the
+ * store at startup and updated by the server on every save and delete.
+ *
+ * A query is a list of terms, each matched as a case-insensitive substring
+ * of a permalink's text (its code, every notebook cell or collection snippet,
+ * a project's files, a problem's title and statement; never hidden test cases). A single glyph
+ * such as ⍤ is a term like any other.
+ *
+ * Each character maps to the permalinks whose text contains it, so a query
+ * only checks the permalinks in the smallest of its characters' sets.
+ */
+
+// Longest query accepted, and the most results returned at once
+const MAX_QUERY = 200;
+const MAX_LIMIT = 50;
+
+/**
+ * Split a query into terms: whitespace-separated, "quoted phrases" kept whole
+ * @returns {string[]} - Lowercased terms
+ */
+function parseQuery(query) {
+    const terms = [];
+    const pattern = /"([^"]+)"|(\S+)/g;
+    let match;
+    while ((match = pattern.exec(String(query || '').slice(0, MAX_QUERY)))) {
+        terms.push((match[1] || match[2]).toLowerCase());
+    }
+    return terms;
+}
+
+// What kind of permalink stored content is
+function contentKind(content) {
+    if (content.problem) return 'problem';
+    if (content.notebook) return 'notebook';
+    if (content.collection) return 'collection';
+    if (content.project) return 'project';
+    return 'code';
+}
+
+// Languages used by stored content
+function contentLangs(content) {
+    if (content.problem) return Object.keys(content.problem.tests);
+    if (content.notebook) {
+        return [...new Set(content.notebook.cells.filter(cell => cell.type === 'code').map(cell => cell.lang))];
+    }
+    if (content.collection) return [...new Set(content.collection.snippets.map(snippet => snippet.lang))];
+    return [content.lang];
+}
+
+// The text searched for a permalink
+function searchableText(content) {
+    if (content.problem) return [content.problem.title, content.problem.statement || ''].join('\n');
+    if (content.notebook) {
+        return content.notebook.cells.map(cell => cell.type === 'code' ? cell.code : cell.text).join('\n');
+    }
+    if (content.collection) {
+        return [content.collection.title || '', ...content.collection.snippets.map(snippet => snippet.code)].join('\n');
+    }
+    if (content.project) return [content.code, ...Object.values(content.project.files)].join('\n');
+    return content.code;
+}
+
+// The line that best shows why a permalink matched: the first containing a
+// term, else the first non-empty line
+function previewLine(text, terms) {
+    const lines = text.split('\n');
+    const line = lines.find(candidate => terms.some(term => candidate.toLowerCase().includes(term)))
+        || lines.find(candidate => candidate.trim())
+        || '';
+    return line.length > 80 ? line.slice(0, 77) + '...' : line;
+}
+
+/**
+ * Create an empty index
+ * @returns {object} - { set, remove, search, size }
+ */
+function createSearchIndex() {
+    const entries = new Map();   // code -> { code, version, kind, langs, text, lower, createdAt, seq }
+    const postings = new Map();  // character -> Set of codes
+    let seq = 0;
+
+    function remove(code) {
+        const entry = entries.get(code);
+        if (!entry) return;
+        for (const char of new Set(entry.lower)) {
+            const codes = postings.get(char);
+            codes.delete(code);
+            if (codes.size === 0) postings.delete(char);
+        }
+        entries.delete(code);
+    }
+
+    /**
+     * Index (or re-index) a permalink
+     * @param {string} code - Permalink code
+     * @param {object} content - Its latest version
+     * @param {number} version - That version's number
+     */
+    function set(code, content, version) {
+        remove(code);
+        const text = searchableText(content);
+        const entry = {
+            code,
+            version,
+            kind: contentKind(content),
+            langs: contentLangs(content),
+            text,
+            lower: text.toLowerCase(),
+            createdAt: content.createdAt || null,
+            seq: seq++
+        };
+        entries.set(code, entry);
+        for (const char of new Set(entry.lower)) {
+            if (!postings.has(char)) postings.set(char, new Set());
+            postings.get(char).add(code);
+        }
+    }
+
+    /**
+     * Find permalinks containing every term, newest first
+     * @param {object} query
+     * @param {string[]} query.terms - From parseQuery (none lists everything)
+     * @param {string} [query.lang] - Only permalinks using this language
+     * @param {number} [query.limit=20]
+     * @param {number} [query.offset=0]
+     * @param {Function} [query.filter] - (code) => boolean, e.g. to skip expired links
+     * @returns {{total: number, results: object[]}}
+     */
+    function search({ terms, lang, limit = 20, offset = 0, filter }) {
+        let candidates = entries.keys();
+        if (terms.length) {
+            let smallest = null;
+            for (const char of new Set(terms.join(''))) {
+                const codes = postings.get(char);
+                if (!codes) return { total: 0, results: [] };
+                if (!smallest || codes.size < smallest.size) smallest = codes;
+            }
+            candidates = smallest;
+        }
+
+        const matches = [];
+        for (const code of candidates) {
+            const entry = entries.get(code);
+            if (lang && !entry.langs.includes(lang)) continue;
+            if (!terms.every(term => entry.lower.includes(term))) continue;
+            if (filter && !filter(code)) continue;
+            matches.push(entry);
+        }
+
+        // Newest first; links saved before timestamps were kept sort by load order
+        matches.sort((a, b) => (b.createdAt || '').localeCompare(a.createdAt || '') || b.seq - a.seq);
+        const count = Math.min(Math.max(1, limit), MAX_LIMIT);
+        return {
+            total: matches.length,
+            results: matches.slice(offset, offset + count).map(entry => ({
+                code: entry.code,
+                version: entry.version,
+                kind: entry.kind,
+                langs: entry.langs,
+                preview: previewLine(entry.text, terms),
+                createdAt: entry.createdAt
+            }))
+        };
+    }
+
+    return {
+        set,
+        remove,
+        search,
+        size: () => entries.size
+    };
+}
+
+module.exports = { createSearchIndex, parseQuery, MAX_LIMIT };
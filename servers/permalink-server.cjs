@@ -3,18 +3,74 @@
  * Permalink Server for ArrayBox
  * Stores and retrieves short permalinks (4-char codes)
  * Serves OG meta tags for social media previews
+ *
+ * POST /p { lang, code, result?, resultHtml? }  - single box
+ * POST /p { notebook: { cells: [...] } }         - notebook (see src/notebook.js)
+ * POST /p { problem: { title, statement, tests } } - golf problem (see src/golf.js)
+ * POST /p { collection: { title?, snippets: [{ title?, lang, code, result? }] } }
+ *                                                - collection of snippets (see src/collection-view.js)
+ * POST /p { lang, code, ..., project: { files: { path: text } } }
+ *                                                - box with its project files (see src/vfs.js)
+ * POST /p { ..., forkOf: "AbCd@2" }              - fork: a new code that remembers its parent
+ * POST /p/:code/versions { ... }                 - add a version (same body as POST /p)
+ * GET  /p/:code/history                          - versions of a permalink
+ * GET  /p?q=⍤ rank&lang=apl&limit=20&offset=0    - search, newest first (see permalink-search.cjs)
+ * PUT  /p/:code { ... }                          - add a version, like POST /p/:code/versions
+ * DELETE /p/:code                                - delete a permalink, its versions and OG images
+ * POST /p/:code/submit { lang, code }            - judge a solution to a problem
+ *
+ * A new permalink comes with a secret owner token (only its sha256 is stored).
+ * Adding versions (POST or PUT) and DELETE need it as `Authorization: Bearer <token>`;
+ * the PERMALINK_ADMIN_TOKEN env var sets a token that works for every link
+ * (scripts/permalink-admin.cjs uses it to take content down).
+ *
+ * Limits (--name=value or env var):
+ *   --max-body   PERMALINK_MAX_BODY    bytes per request (default 1 MB)
+ *   --max-code   PERMALINK_MAX_CODE    characters of code per snippet, cell or project file, and of
+ *                                      markdown per cell and problem title, statement or test input (default 20000)
+ *   --max-result PERMALINK_MAX_RESULT  characters of result per snippet or cell, and of expected
+ *                                      output per problem test (default 200000)
+ *   --ttl        PERMALINK_TTL         lifetime of new permalinks, e.g. 30d or 12h (default: forever);
+ *                                      POST /p { ..., expiresIn: <seconds> } asks for a shorter one
+ *   --rate-limit PERMALINK_RATE_LIMIT  token bucket per client IP for POST/PUT/DELETE,
+ *                                      <burst>/<seconds> or off (default 30/60)
+ *   --trusted-proxy PERMALINK_TRUSTED_PROXY
+ *                                      comma-separated proxy addresses whose appended
+ *                                      x-forwarded-for entry is the client IP, or off
+ *                                      (default 127.0.0.1,::1, i.e. api-gateway.cjs)
+ * Oversized requests get 413 and limited clients 429, as
+ * { success: false, reason: 'TOO_LARGE' | 'RATE_LIMITED', error, limit? | retryAfter? }.
+ *
+ * Permalinks are chains of versions: /p/AbCd is the latest, /p/AbCd@3 is
+ * version 3. Version 1 is stored under the code itself and later ones under
+ * code@n, so links shared before an edit keep working. Versions never change
+ * once stored (PUT /p/:code@n is refused): a correction is a new version, and
+ * DELETE takes the whole link down.
+ *
+ * Problem permalinks never return hidden test cases; submissions are judged
+ * here and evaluated by the eval server (eval-server.cjs, sandboxed), so
+ * solvers only learn whether each hidden case passed.
  * 
- * Usage: node permalink-server.cjs [port]
+ * Usage: node permalink-server.cjs [port] [--eval-server=<url>] [--storage=<backend>[:<path>]]
+ *
+ * Storage (see permalink-store.cjs): json (default, storage/permalinks.json) or
+ * sqlite (storage/permalinks.db); also settable with PERMALINK_STORAGE.
+ * scripts/migrate-permalinks.cjs copies links from one to the other.
  */
 
 const http = require('http');
 const fs = require('fs');
 const path = require('path');
-const { generateAndSaveOGImage, generateVerticalImage, getLangDisplayName } = require('./og-generator.cjs');
+const crypto = require('crypto');
+const {
+    generateOGImage, generateAndSaveOGImage, generateCollectionOGImage, generateAndSaveCollectionOGImage,
+    generateVerticalImage, generateTypingAnimation, getLangDisplayName
+} = require('./og-generator.cjs');
+const { createStore, contentHash, versionId, countVersions, deletePermalink, OG_DIR } = require('./permalink-store.cjs');
+const { parseRateLimit, createRateLimiter } = require('./rate-limit.cjs');
+const { createSearchIndex, parseQuery } = require('./permalink-search.cjs');
 
 const PORT = parseInt(process.argv[2]) || 8084;
-const STORAGE_FILE = path.join(__dirname, '..', 'storage', 'permalinks.json');
-const OG_DIR = path.join(__dirname, '..', 'storage', 'og');
 const INDEX_FILE = path.join(__dirname, '..', 'index.html');
 const CHARS = 'ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789';
 
@@ -26,34 +82,67 @@ const BASE_URL = (baseUrlArg ? baseUrlArg.split('=')[1] : null)
     || process.env.BASE_URL 
     || `http://localhost:${PORT}`;
 
-// Load permalinks from file
-function loadPermalinks() {
-    try {
-        if (fs.existsSync(STORAGE_FILE)) {
-            const data = fs.readFileSync(STORAGE_FILE, 'utf8');
-            return JSON.parse(data);
-        }
-    } catch (e) {
-        console.error('Error loading permalinks:', e.message);
-    }
-    return {};
+// Eval server used to judge problem submissions
+const evalServerArg = process.argv.find(a => a.startsWith('--eval-server='));
+const EVAL_SERVER = ((evalServerArg ? evalServerArg.split('=')[1] : null)
+    || process.env.EVAL_SERVER
+    || 'http://localhost:8083').replace(/\/$/, '');
+
+// src/golf.js is an ES module shared with the browser
+let golfModule = null;
+async function loadGolf() {
+    if (!golfModule) golfModule = await import('../src/golf.js');
+    return golfModule;
 }
 
-// Save permalinks to file
-function savePermalinks(permalinks) {
-    try {
-        const dir = path.dirname(STORAGE_FILE);
-        if (!fs.existsSync(dir)) {
-            fs.mkdirSync(dir, { recursive: true });
-        }
-        fs.writeFileSync(STORAGE_FILE, JSON.stringify(permalinks, null, 2));
-        return true;
-    } catch (e) {
-        console.error('Error saving permalinks:', e.message);
-        return false;
-    }
+// Token accepted in place of any owner token (for taking content down)
+const ADMIN_TOKEN = process.env.PERMALINK_ADMIN_TOKEN || null;
+
+// Limits: --name=value, or the env var
+function limitOption(name, envName) {
+    const arg = process.argv.find(a => a.startsWith(`--${name}=`));
+    return arg ? arg.slice(name.length + 3) : process.env[envName];
+}
+
+// Parse a duration: seconds, or a number with s, m, h or d (0 or unset: none)
+function parseDuration(value) {
+    if (!value) return 0;
+    const match = /^(\d+)([smhd]?)$/.exec(value.trim());
+    if (!match) throw new Error(`Invalid duration "${value}" (expected e.g. 3600, 12h or 30d)`);
+    return parseInt(match[1]) * { '': 1, s: 1, m: 60, h: 3600, d: 86400 }[match[2]];
+}
+
+const MAX_BODY = parseInt(limitOption('max-body', 'PERMALINK_MAX_BODY')) || 1024 * 1024;
+const MAX_CODE = parseInt(limitOption('max-code', 'PERMALINK_MAX_CODE')) || 20000;
+const MAX_RESULT = parseInt(limitOption('max-result', 'PERMALINK_MAX_RESULT')) || 200000;
+let TTL;
+let rateLimiter;
+try {
+    TTL = parseDuration(limitOption('ttl', 'PERMALINK_TTL'));
+    const rateLimit = parseRateLimit(limitOption('rate-limit', 'PERMALINK_RATE_LIMIT') || '30/60');
+    rateLimiter = rateLimit && createRateLimiter(rateLimit);
+} catch (e) {
+    console.error(e.message);
+    process.exit(1);
 }
 
+// IPv4 peers show up as ::ffff:1.2.3.4 on dual-stack sockets
+function normalizeAddress(address) {
+    return String(address || '').replace(/^::ffff:(?=\d+\.)/, '');
+}
+
+// Proxies whose x-forwarded-for is believed (default: loopback, where the gateway runs)
+const trustedProxyOption = limitOption('trusted-proxy', 'PERMALINK_TRUSTED_PROXY') ?? '127.0.0.1,::1';
+const TRUSTED_PROXIES = new Set(trustedProxyOption === 'off'
+    ? []
+    : trustedProxyOption.split(',').map(entry => normalizeAddress(entry.trim())).filter(Boolean));
+
+// Permalink storage backend
+const storageArg = process.argv.find(a => a.startsWith('--storage='));
+const STORAGE = (storageArg ? storageArg.slice('--storage='.length) : null)
+    || process.env.PERMALINK_STORAGE
+    || 'json';
+
 // Generate random 4-char code
 function generateCode() {
     let code = '';
@@ -77,19 +166,449 @@ function incrementCode(code) {
     return CHARS[0] + chars.join('');
 }
 
-// In-memory cache
-let permalinks = loadPermalinks();
+let store;
+try {
+    store = createStore(STORAGE);
+} catch (e) {
+    console.error(`Error opening permalink storage: ${e.message}`);
+    process.exit(1);
+}
+
+/**
+ * Store content under a new code
+ * @returns {Promise<string>} - The code
+ */
+async function insertPermalink(content) {
+    let newCode = generateCode();
+    for (let attempt = 0; ; attempt++) {
+        while (store.has(newCode)) {
+            newCode = incrementCode(newCode);
+        }
+        try {
+            await store.insert(newCode, content);
+            return newCode;
+        } catch (e) {
+            // Another process took the code between has() and insert(): try the next one
+            if (attempt < 5 && store.has(newCode)) continue;
+            throw e;
+        }
+    }
+}
+
+// Expired permalinks are treated as gone until sweepExpired() deletes them
+function isExpired(data) {
+    return Boolean(data.expiresAt) && Date.parse(data.expiresAt) <= Date.now();
+}
+
+function isLive(code) {
+    return store.has(code) && !isExpired(store.get(code));
+}
+
+// An existing permalink can stand in for new content unless it expires sooner
+function outlasts(existingId, content) {
+    const { expiresAt } = store.get(existingId.split('@')[0]);
+    if (!expiresAt) return true;
+    return Boolean(content.expiresAt) && expiresAt >= content.expiresAt;
+}
+
+// Delete expired permalinks with their versions and OG images
+async function sweepExpired() {
+    const expired = [];
+    for (const [id, data] of store.entries()) {
+        if (!id.includes('@') && isExpired(data)) expired.push(id);
+    }
+    for (const code of expired) {
+        await deletePermalink(store, code);
+        searchIndex.remove(code);
+    }
+    if (expired.length) console.log(`Removed ${expired.length} expired permalinks`);
+}
+
+// A permalink reference: code, or code@version
+const REF_PATTERN = /^([A-Za-z0-9]+)(?:@(\d+))?$/;
+
+/**
+ * Look up a reference: "AbCd" is the latest version, "AbCd@3" version 3
+ * @returns {{code, version, versions, id, data}|null}
+ */
+function resolvePermalink(ref) {
+    const match = REF_PATTERN.exec(ref);
+    if (!match || !isLive(match[1])) return null;
+    const code = match[1];
+    const versions = countVersions(store, code);
+    const version = match[2] ? parseInt(match[2]) : versions;
+    if (version < 1 || version > versions) return null;
+    const id = versionId(code, version);
+    return { code, version, versions, id, data: store.get(id) };
+}
+
+// Search index over the latest version of every live permalink
+const searchIndex = createSearchIndex();
+
+// Bring a permalink's search entry up to date after it changes
+function reindex(code) {
+    const latest = resolvePermalink(code);
+    if (latest) searchIndex.set(code, latest.data, latest.version);
+    else searchIndex.remove(code);
+}
+
+for (const [id] of store.entries()) {
+    if (!id.includes('@')) reindex(id);
+}
+
+// An id that keeps pointing at the same content: a bare code is pinned to
+// version 1 once it has later versions
+function pinnedId(id) {
+    return !id.includes('@') && countVersions(store, id) > 1 ? `${id}@1` : id;
+}
+
+/**
+ * Store content as the next version of a permalink
+ * @returns {Promise<number>} - The new version
+ */
+async function insertVersion(code, content) {
+    for (let attempt = 0; ; attempt++) {
+        const version = countVersions(store, code) + 1;
+        try {
+            await store.insert(versionId(code, version), content);
+            return version;
+        } catch (e) {
+            // Another process added the same version first: take the next one
+            if (attempt < 5 && store.has(versionId(code, version))) continue;
+            throw e;
+        }
+    }
+}
+
+// Owner tokens: the client keeps the token, the store keeps its hash
+function generateToken() {
+    return crypto.randomBytes(24).toString('base64url');
+}
+
+function hashToken(token) {
+    return crypto.createHash('sha256').update(token).digest('hex');
+}
+
+function tokensMatch(token, hash) {
+    const a = Buffer.from(hashToken(token), 'hex');
+    const b = Buffer.from(hash, 'hex');
+    return a.length === b.length && crypto.timingSafeEqual(a, b);
+}
+
+/**
+ * Check the bearer token on a request that changes a permalink
+ * @returns {{status: number, error: string}|null} - null when allowed
+ */
+function checkOwner(req, code) {
+    const match = /^Bearer\s+(\S+)$/.exec(req.headers.authorization || '');
+    if (!match) return { status: 401, error: 'Owner token required' };
+    const token = match[1];
+    if (ADMIN_TOKEN && tokensMatch(token, hashToken(ADMIN_TOKEN))) return null;
+    const owner = store.get(code).owner;
+    if (owner && tokensMatch(token, owner)) return null;
+    return { status: 403, error: 'Invalid owner token' };
+}
+
+function sendJson(res, status, data, headers = {}) {
+    res.writeHead(status, { 'Content-Type': 'application/json', ...headers });
+    res.end(JSON.stringify(data));
+}
+
+// Read a request body, answering 413 instead once it passes MAX_BODY
+function readBody(req, res, onBody) {
+    const chunks = [];
+    let size = 0;
+    let tooLarge = false;
+    req.on('data', chunk => {
+        if (tooLarge) return;
+        size += chunk.length;
+        if (size > MAX_BODY) {
+            tooLarge = true;
+            sendJson(res, 413, tooLargeError('Request', MAX_BODY, 'bytes'));
+            return;
+        }
+        chunks.push(chunk);
+    });
+    req.on('end', () => {
+        if (!tooLarge) onBody(Buffer.concat(chunks).toString('utf8'));
+    });
+}
+
+function tooLargeError(what, limit, unit = 'characters') {
+    return { success: false, reason: 'TOO_LARGE', error: `${what} exceeds ${limit} ${unit}`, limit };
+}
+
+// Decode a path segment (null if it is not valid percent-encoding)
+function decodeRef(segment) {
+    try {
+        return decodeURIComponent(segment);
+    } catch (e) {
+        return null;
+    }
+}
+
+const MAX_NOTEBOOK_CELLS = 200;
+
+// Languages a permalink's code can be in (notebookLanguages in src/notebook.js)
+const LANGUAGES = ['apl', 'bqn', 'uiua', 'j', 'kap', 'tinyapl'];
+
+/**
+ * Validate a notebook and keep only known cell fields.
+ * Returns { notebook } or { error }.
+ */
+function sanitizeNotebook(notebook) {
+    if (!notebook || !Array.isArray(notebook.cells)) {
+        return { error: 'Notebook must have a cells array' };
+    }
+    if (notebook.cells.length > MAX_NOTEBOOK_CELLS) {
+        return { error: `Notebook has more than ${MAX_NOTEBOOK_CELLS} cells` };
+    }
+
+    const cells = [];
+    for (const cell of notebook.cells) {
+        if (cell && cell.type === 'markdown' && typeof cell.text === 'string') {
+            cells.push({ type: 'markdown', text: cell.text });
+        } else if (cell && cell.type === 'code' && typeof cell.lang === 'string' && typeof cell.code === 'string') {
+            if (!LANGUAGES.includes(cell.lang)) {
+                return { error: `Unknown notebook cell language: ${cell.lang.slice(0, 20)}` };
+            }
+            const clean = { type: 'code', lang: cell.lang, code: cell.code };
+            if (typeof cell.output === 'string') {
+                clean.output = cell.output;
+                clean.success = cell.success !== false;
+            }
+            cells.push(clean);
+        } else {
+            return { error: 'Invalid notebook cell' };
+        }
+    }
+
+    if (!cells.some(cell => cell.type === 'code' && cell.code.trim())) {
+        return { error: 'Notebook needs at least one code cell' };
+    }
+    return { notebook: { cells } };
+}
+
+const MAX_COLLECTION_SNIPPETS = 50;
+
+/**
+ * Validate a collection and keep only known snippet fields.
+ * Returns { collection } or { error }.
+ */
+function sanitizeCollection(collection) {
+    if (!collection || !Array.isArray(collection.snippets) || collection.snippets.length === 0) {
+        return { error: 'Collection must have a snippets array' };
+    }
+    if (collection.snippets.length > MAX_COLLECTION_SNIPPETS) {
+        return { error: `Collection has more than ${MAX_COLLECTION_SNIPPETS} snippets` };
+    }
+
+    const snippets = [];
+    for (const snippet of collection.snippets) {
+        if (!snippet || typeof snippet.lang !== 'string' || typeof snippet.code !== 'string' || !snippet.code.trim()) {
+            return { error: 'Invalid collection snippet' };
+        }
+        if (!LANGUAGES.includes(snippet.lang)) {
+            return { error: `Unknown snippet language: ${snippet.lang.slice(0, 20)}` };
+        }
+        const clean = { lang: snippet.lang, code: snippet.code };
+        if (typeof snippet.title === 'string' && snippet.title.trim()) clean.title = snippet.title.trim().slice(0, 100);
+        if (typeof snippet.result === 'string' && snippet.result) clean.result = snippet.result;
+        snippets.push(clean);
+    }
+
+    const clean = { snippets };
+    if (typeof collection.title === 'string' && collection.title.trim()) clean.title = collection.title.trim().slice(0, 100);
+    return { collection: clean };
+}
+
+const MAX_PROJECT_FILES = 100;
+const MAX_PROJECT_PATH = 200;
+
+/**
+ * Validate a project's files (relative paths, text contents).
+ * Returns { project } or { error }.
+ */
+function sanitizeProject(project) {
+    if (!project || !project.files || typeof project.files !== 'object' || Array.isArray(project.files)) {
+        return { error: 'Project must have a files object' };
+    }
+    const entries = Object.entries(project.files);
+    if (entries.length === 0) return { error: 'Project has no files' };
+    if (entries.length > MAX_PROJECT_FILES) {
+        return { error: `Project has more than ${MAX_PROJECT_FILES} files` };
+    }
+    for (const [path, text] of entries) {
+        const parts = path.split('/');
+        if (typeof text !== 'string' || path.length > MAX_PROJECT_PATH ||
+            parts.some(part => !part || part === '.' || part === '..')) {
+            return { error: 'Invalid project file' };
+        }
+    }
+    return { project: { files: Object.fromEntries(entries) } };
+}
+
+/**
+ * Build the stored content from a POST /p body (versions use the same body)
+ * Returns { content } or { error, status? } (error is a message, or a
+ * structured 413 body when status is set).
+ */
+async function buildContent(data) {
+    let { lang, code, result, resultHtml } = data;
+    let notebook = null;
+    let problem = null;
+    let collection = null;
+    let project = null;
+
+    // Notebooks are stored whole; lang/code come from the first code cell
+    // so OG previews and older clients still have something to show
+    if (data.notebook) {
+        const sanitized = sanitizeNotebook(data.notebook);
+        if (sanitized.error) return { error: sanitized.error };
+        notebook = sanitized.notebook;
+        const firstCode = notebook.cells.find(cell => cell.type === 'code' && cell.code.trim());
+        lang = firstCode.lang;
+        code = firstCode.code;
+        result = firstCode.success ? firstCode.output : null;
+        resultHtml = null;
+    }
+
+    // Collections are stored whole; lang/code/result come from the first snippet
+    if (data.collection) {
+        const sanitized = sanitizeCollection(data.collection);
+        if (sanitized.error) return { error: sanitized.error };
+        collection = sanitized.collection;
+        ({ lang, code } = collection.snippets[0]);
+        result = collection.snippets[0].result || null;
+        resultHtml = null;
+    }
+
+    // Problems are stored with their hidden cases; lang/code are the
+    // first language and the title, for OG previews
+    if (data.problem) {
+        const { normalizeProblem } = await loadGolf();
+        const normalized = normalizeProblem(data.problem, { requireId: false });
+        if (normalized.error) return { error: normalized.error };
+        problem = normalized.problem;
+        delete problem.id;
+        lang = Object.keys(problem.tests)[0];
+        code = problem.title;
+        result = null;
+        resultHtml = null;
+    }
+
+    if (!lang || !code) return { error: 'Missing lang or code' };
+    if (typeof lang !== 'string' || !LANGUAGES.includes(lang)) {
+        return { error: `Unknown language: ${String(lang).slice(0, 20)}` };
+    }
+
+    // Projects are a single box's code with the files it imports
+    if (data.project && !notebook && !collection && !problem) {
+        const sanitized = sanitizeProject(data.project);
+        if (sanitized.error) return { error: sanitized.error };
+        project = sanitized.project;
+    }
+
+    // Size limits: every text stored is code-like (MAX_CODE) or output (MAX_RESULT)
+    const sizes = notebook
+        ? notebook.cells.flatMap(cell => cell.type === 'markdown'
+            ? [['Markdown', cell.text, MAX_CODE]]
+            : [['Code', cell.code, MAX_CODE], ['Result', cell.output, MAX_RESULT]])
+        : collection
+        ? collection.snippets.flatMap(snippet => [['Code', snippet.code, MAX_CODE], ['Result', snippet.result, MAX_RESULT]])
+        : problem
+        ? [['Title', problem.title, MAX_CODE], ['Statement', problem.statement, MAX_CODE],
+            ...Object.values(problem.tests).flat().flatMap(testCase => [
+                ...[testCase.input || []].flat().map(input => ['Test input', input, MAX_CODE]),
+                ['Expected output', testCase.expected, MAX_RESULT]
+            ])]
+        : [['Code', code, MAX_CODE], ['Result', result, MAX_RESULT], ['Result', resultHtml, MAX_RESULT],
+            ...(project ? Object.values(project.files).map(text => ['Code', text, MAX_CODE]) : [])];
+    for (const [what, text, limit] of sizes) {
+        if (typeof text === 'string' && text.length > limit) {
+            return { error: tooLargeError(what, limit), status: 413 };
+        }
+    }
+
+    // Build content object (only include result/resultHtml if provided)
+    const content = { lang, code };
+    if (result) content.result = result;
+    if (resultHtml) content.resultHtml = resultHtml;
+    if (notebook) content.notebook = notebook;
+    if (problem) content.problem = problem;
+    if (collection) content.collection = collection;
+    if (project) content.project = project;
+    return { content };
+}
+
+function sendBuildError(res, built) {
+    if (built.status) {
+        sendJson(res, built.status, built.error);
+    } else {
+        sendJson(res, 400, { success: false, error: built.error });
+    }
+}
+
+// Generate and save the OG image for stored content (collections get their own layout)
+function generateOGFor(id, content) {
+    if (content.collection) return generateAndSaveCollectionOGImage(id, content.collection);
+    return generateAndSaveOGImage(id, content.code, content.lang, content.result || null, content.resultHtml || null);
+}
+
+// Generate the OG image for a new permalink or version in the background
+function generateOGInBackground(id, content) {
+    generateOGFor(id, content)
+        .then(() => console.log(`Generated OG image for ${id}`))
+        .catch(e => console.error(`Failed to generate OG image for ${id}:`, e.message));
+}
+
+/**
+ * Permalink data as returned to clients (no owner hash, problems without
+ * their hidden cases)
+ */
+async function publicData(data) {
+    const { owner, ...rest } = data;
+    if (!rest.problem) return rest;
+    const { publicProblem } = await loadGolf();
+    return { ...rest, problem: publicProblem(rest.problem) };
+}
+
+// Evaluate one test case on the eval server
+async function evaluateOnEvalServer(lang, code) {
+    let response;
+    try {
+        response = await fetch(`${EVAL_SERVER}/eval`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ lang, code })
+        });
+    } catch (e) {
+        throw new Error('EVAL_SERVER_UNAVAILABLE');
+    }
+    if (response.status === 503) throw new Error('EVAL_SERVER_UNAVAILABLE');
+    const data = await response.json();
+    return { success: data.success !== false, output: data.output || data.error || '' };
+}
 
 // Generate HTML page with OG meta tags for a permalink
+// shortCode: the code as requested (AbCd or AbCd@3); imageId: the version shown, so
+// the preview image changes with each version
 // reqBaseUrl: the public-facing base URL derived from the request, or falls back to BASE_URL
-function generateOGHtml(shortCode, data, reqBaseUrl) {
+function generateOGHtml(shortCode, imageId, data, reqBaseUrl) {
     const effectiveBaseUrl = reqBaseUrl || BASE_URL;
-    const langName = getLangDisplayName(data.lang);
-    const title = `ArrayBox · ${langName}`;
-    const description = data.code.length > 100 
-        ? data.code.slice(0, 97) + '...' 
-        : data.code;
-    const imageUrl = `${effectiveBaseUrl}/og/${shortCode}.png`;
+    let title, description;
+    if (data.collection) {
+        const { snippets } = data.collection;
+        const langNames = [...new Set(snippets.map(snippet => getLangDisplayName(snippet.lang)))];
+        title = `ArrayBox · ${escapeHtml(data.collection.title || 'Collection')}`;
+        description = `${snippets.length} snippet${snippets.length === 1 ? '' : 's'} in ${langNames.join(', ')}`;
+    } else {
+        title = `ArrayBox · ${getLangDisplayName(data.lang)}`;
+        description = data.code.length > 100
+            ? data.code.slice(0, 97) + '...'
+            : data.code;
+    }
+    const imageUrl = `${effectiveBaseUrl}/og/${imageId}.png`;
     const pageUrl = `${effectiveBaseUrl}/p/${shortCode}`;
     
     // Read the base index.html and inject OG tags
@@ -181,11 +700,30 @@ function getBaseUrlFromRequest(req) {
     return null;
 }
 
+// Cache headers for an OG image: caches keep it briefly and then revalidate
+// with the ETag, since a bare code follows the latest version and DELETE
+// takes every version's image down
+function ogCacheHeaders(body) {
+    const etag = `"${crypto.createHash('sha256').update(body).digest('hex').slice(0, 16)}"`;
+    return { 'Cache-Control': 'public, max-age=300', ETag: etag };
+}
+
+// Client IP for rate limiting. Clients can send any x-forwarded-for, so only
+// its right-most entry counts - the address a trusted proxy (api-gateway.cjs)
+// appended - and only when the request comes from that proxy; otherwise the peer
+function getClientIp(req) {
+    const peer = normalizeAddress(req.socket.remoteAddress);
+    const forwardedFor = req.headers['x-forwarded-for'];
+    if (!forwardedFor || !TRUSTED_PROXIES.has(peer)) return peer;
+    const entries = String(forwardedFor).split(',').map(entry => entry.trim()).filter(Boolean);
+    return entries.length > 0 ? normalizeAddress(entries[entries.length - 1]) : peer;
+}
+
 const server = http.createServer((req, res) => {
     // CORS headers
     res.setHeader('Access-Control-Allow-Origin', '*');
-    res.setHeader('Access-Control-Allow-Methods', 'GET, POST, OPTIONS');
-    res.setHeader('Access-Control-Allow-Headers', 'Content-Type');
+    res.setHeader('Access-Control-Allow-Methods', 'GET, POST, PUT, DELETE, OPTIONS');
+    res.setHeader('Access-Control-Allow-Headers', 'Content-Type, Authorization');
 
     if (req.method === 'OPTIONS') {
         res.writeHead(200);
@@ -193,55 +731,138 @@ const server = http.createServer((req, res) => {
         return;
     }
 
-    // GET /og/:code.png - Serve OG image
+    // Everything but reads (creating, editing, judging, rendering) is rate limited per client
+    if (rateLimiter && req.method !== 'GET') {
+        const limited = rateLimiter.take(getClientIp(req));
+        if (!limited.allowed) {
+            sendJson(res, 429, {
+                success: false,
+                reason: 'RATE_LIMITED',
+                error: `Too many requests, try again in ${limited.retryAfter}s`,
+                retryAfter: limited.retryAfter
+            }, { 'Retry-After': String(limited.retryAfter) });
+            return;
+        }
+    }
+
+    // GET /og/:code.png - Serve OG image (only for live permalinks; refs that
+    // aren't a code or code@n never reach the file system)
     if (req.method === 'GET' && req.url.startsWith('/og/') && req.url.endsWith('.png')) {
-        const code = req.url.slice(4, -4); // Remove '/og/' and '.png'
-        const imagePath = path.join(OG_DIR, `${code}.png`);
-        
-        if (fs.existsSync(imagePath)) {
-            const imageData = fs.readFileSync(imagePath);
-            res.writeHead(200, { 
-                'Content-Type': 'image/png',
-                'Cache-Control': 'public, max-age=31536000',
-            });
+        const ref = decodeRef(req.url.slice(4, -4)) || ''; // Remove '/og/' and '.png'
+        const resolved = resolvePermalink(ref);
+        if (!resolved) {
+            res.writeHead(404);
+            res.end('Not found');
+            return;
+        }
+
+        const imagePath = path.join(OG_DIR, `${resolved.id}.png`);
+        const sendImage = (imageData) => {
+            const headers = { 'Content-Type': 'image/png', ...ogCacheHeaders(imageData) };
+            if (req.headers['if-none-match'] === headers.ETag) {
+                res.writeHead(304, headers);
+                res.end();
+                return;
+            }
+            res.writeHead(200, headers);
             res.end(imageData);
+        };
+
+        if (fs.existsSync(imagePath)) {
+            sendImage(fs.readFileSync(imagePath));
         } else {
-            // Try to generate on-demand if permalink exists
-            const data = permalinks[code];
-            if (data) {
-                generateAndSaveOGImage(code, data.code, data.lang)
-                    .then(savedPath => {
-                        const imageData = fs.readFileSync(savedPath);
-                        res.writeHead(200, { 
-                            'Content-Type': 'image/png',
-                            'Cache-Control': 'public, max-age=31536000',
-                        });
-                        res.end(imageData);
-                    })
-                    .catch(e => {
-                        console.error('Error generating OG image:', e);
-                        res.writeHead(500);
-                        res.end('Error generating image');
-                    });
-            } else {
-                res.writeHead(404);
-                res.end('Not found');
-            }
+            // Generate on demand, e.g. after a restart lost a background render
+            generateOGFor(resolved.id, resolved.data)
+                .then(savedPath => sendImage(fs.readFileSync(savedPath)))
+                .catch(e => {
+                    console.error('Error generating OG image:', e);
+                    res.writeHead(500);
+                    res.end('Error generating image');
+                });
+        }
+        return;
+    }
+
+    // GET /og/:code.svg - OG image as SVG (text kept as text), generated on request
+    if (req.method === 'GET' && req.url.startsWith('/og/') && req.url.endsWith('.svg')) {
+        const ref = decodeRef(req.url.slice(4, -4)) || '';
+        const resolved = resolvePermalink(ref);
+        const data = resolved && resolved.data;
+        if (!data) {
+            res.writeHead(404);
+            res.end('Not found');
+            return;
+        }
+        const svg = data.collection
+            ? generateCollectionOGImage(data.collection, { format: 'svg' })
+            : generateOGImage(data.code, data.lang, data.result || null, data.resultHtml || null, { format: 'svg' });
+        svg.then(source => {
+            res.writeHead(200, { 'Content-Type': 'image/svg+xml', ...ogCacheHeaders(source) });
+            res.end(source);
+        }).catch(e => {
+            console.error('Error generating OG image:', e);
+            res.writeHead(500);
+            res.end('Error generating image');
+        });
+        return;
+    }
+
+    // GET /p?q=...&lang=... - Search permalinks, newest first
+    if (req.method === 'GET' && (req.url === '/p' || req.url.startsWith('/p?'))) {
+        const params = new URL(req.url, 'http://localhost').searchParams;
+        const { total, results } = searchIndex.search({
+            terms: parseQuery(params.get('q')),
+            lang: params.get('lang') || null,
+            limit: parseInt(params.get('limit')) || 20,
+            offset: Math.max(0, parseInt(params.get('offset')) || 0),
+            filter: isLive
+        });
+        sendJson(res, 200, { success: true, total, results });
+        return;
+    }
+
+    // GET /p/:code/history - Versions of a permalink, oldest first
+    const historyMatch = req.url.match(/^\/p\/([A-Za-z0-9]+)\/history$/);
+    if (req.method === 'GET' && historyMatch) {
+        const code = historyMatch[1];
+        if (!isLive(code)) {
+            res.writeHead(404, { 'Content-Type': 'application/json' });
+            res.end(JSON.stringify({ success: false, error: 'Not found' }));
+            return;
         }
+        const versions = [];
+        for (let version = 1; version <= countVersions(store, code); version++) {
+            const id = versionId(code, version);
+            const data = store.get(id);
+            const firstLine = data.code.split('\n')[0];
+            versions.push({
+                version,
+                id,
+                lang: data.lang,
+                preview: firstLine.length > 60 ? firstLine.slice(0, 57) + '...' : firstLine
+            });
+        }
+        const forkOf = store.get(code).forkOf || null;
+        res.writeHead(200, { 'Content-Type': 'application/json' });
+        res.end(JSON.stringify({ success: true, code, versions, forkOf }));
         return;
     }
 
     // GET /p/:code - Serve HTML page with OG tags (for social media crawlers)
     if (req.method === 'GET' && req.url.startsWith('/p/') && !req.url.includes('.')) {
-        const code = req.url.slice(3);
-        const data = permalinks[code];
+        const ref = decodeRef(req.url.slice(3));
+        const resolved = ref && resolvePermalink(ref);
+        const data = resolved && resolved.data;
         
         // Check if this is an API request (wants JSON)
         const acceptHeader = req.headers.accept || '';
         if (acceptHeader.includes('application/json')) {
             if (data) {
-                res.writeHead(200, { 'Content-Type': 'application/json' });
-                res.end(JSON.stringify({ success: true, ...data }));
+                publicData(data).then(publicContent => {
+                    const { id, version, versions } = resolved;
+                    res.writeHead(200, { 'Content-Type': 'application/json' });
+                    res.end(JSON.stringify({ success: true, ...publicContent, id, version, versions }));
+                });
             } else {
                 res.writeHead(404, { 'Content-Type': 'application/json' });
                 res.end(JSON.stringify({ success: false, error: 'Not found' }));
@@ -252,7 +873,7 @@ const server = http.createServer((req, res) => {
         // Serve HTML page with OG tags
         if (data) {
             const reqBaseUrl = getBaseUrlFromRequest(req);
-            const html = generateOGHtml(code, data, reqBaseUrl);
+            const html = generateOGHtml(ref, resolved.id, data, reqBaseUrl);
             res.writeHead(200, { 'Content-Type': 'text/html' });
             res.end(html);
         } else {
@@ -264,12 +885,16 @@ const server = http.createServer((req, res) => {
 
     // GET /p/:code (JSON API) - Retrieve permalink data
     if (req.method === 'GET' && req.url.startsWith('/p/')) {
-        const code = req.url.slice(3);
-        const data = permalinks[code];
+        const ref = decodeRef(req.url.slice(3));
+        const resolved = ref && resolvePermalink(ref);
+        const data = resolved && resolved.data;
         
         if (data) {
-            res.writeHead(200, { 'Content-Type': 'application/json' });
-            res.end(JSON.stringify({ success: true, ...data }));
+            publicData(data).then(publicContent => {
+                const { id, version, versions } = resolved;
+                res.writeHead(200, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: true, ...publicContent, id, version, versions }));
+            });
         } else {
             res.writeHead(404, { 'Content-Type': 'application/json' });
             res.end(JSON.stringify({ success: false, error: 'Not found' }));
@@ -278,25 +903,38 @@ const server = http.createServer((req, res) => {
     }
 
     // POST /image/vertical - Generate vertical image and return as PNG
+    // ("format": "svg" for SVG with the text kept as text, "apng" for the
+    // code typed out as an animated PNG; "theme": a theme id from src/themes.js)
     if (req.method === 'POST' && req.url === '/image/vertical') {
-        let body = '';
-        req.on('data', chunk => body += chunk.toString());
-        req.on('end', async () => {
+        readBody(req, res, async (body) => {
             try {
-                const { lang, code, result, resultHtml } = JSON.parse(body);
+                const { lang, code, result, resultHtml, format = 'png', theme } = JSON.parse(body);
                 
                 if (!lang || !code) {
                     res.writeHead(400, { 'Content-Type': 'application/json' });
                     res.end(JSON.stringify({ success: false, error: 'Missing lang or code' }));
                     return;
                 }
+                if (!['png', 'svg', 'apng'].includes(format)) {
+                    res.writeHead(400, { 'Content-Type': 'application/json' });
+                    res.end(JSON.stringify({ success: false, error: 'format must be png, svg or apng' }));
+                    return;
+                }
+                const { THEMES } = await import('../src/themes.js');
+                if (theme !== undefined && !Object.hasOwn(THEMES, theme)) {
+                    res.writeHead(400, { 'Content-Type': 'application/json' });
+                    res.end(JSON.stringify({ success: false, error: `Unknown theme (one of ${Object.keys(THEMES).join(', ')})` }));
+                    return;
+                }
                 
-                const pngBuffer = await generateVerticalImage(code, lang, result || null, resultHtml || null);
+                const image = format === 'apng'
+                    ? await generateTypingAnimation(code, lang, result || null, resultHtml || null, { theme })
+                    : await generateVerticalImage(code, lang, result || null, resultHtml || null, { format, theme });
                 res.writeHead(200, { 
-                    'Content-Type': 'image/png',
+                    'Content-Type': format === 'svg' ? 'image/svg+xml' : 'image/png',
                     'Cache-Control': 'no-cache',
                 });
-                res.end(pngBuffer);
+                res.end(image);
             } catch (e) {
                 console.error('Error generating vertical image:', e);
                 res.writeHead(500, { 'Content-Type': 'application/json' });
@@ -306,55 +944,206 @@ const server = http.createServer((req, res) => {
         return;
     }
 
+    // POST /p/:code/submit - Judge a solution to a problem permalink
+    const submitMatch = req.url.match(/^\/p\/([A-Za-z0-9]+(?:@\d+)?)\/submit$/);
+    if (req.method === 'POST' && submitMatch) {
+        readBody(req, res, async (body) => {
+            const resolved = resolvePermalink(submitMatch[1]);
+            const data = resolved && resolved.data;
+            if (!data || !data.problem) {
+                res.writeHead(404, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: 'Problem not found' }));
+                return;
+            }
+
+            let submission;
+            try {
+                submission = JSON.parse(body);
+            } catch (e) {
+                res.writeHead(400, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: 'Invalid JSON' }));
+                return;
+            }
+            const { lang } = submission;
+            const code = typeof submission.code === 'string' ? submission.code : '';
+            if (!data.problem.tests[lang]) {
+                res.writeHead(400, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: `No tests for ${lang || '(none)'}` }));
+                return;
+            }
+
+            try {
+                const { judge, publicVerdict } = await loadGolf();
+                const verdict = await judge(data.problem, lang, code, evaluateOnEvalServer);
+                // judge() records evaluation errors as failed cases
+                const unavailable = verdict.cases.some(c => !c.success && c.output === 'EVAL_SERVER_UNAVAILABLE');
+                if (unavailable) {
+                    res.writeHead(503, { 'Content-Type': 'application/json' });
+                    res.end(JSON.stringify({ success: false, error: 'Judge unavailable (eval server not running)' }));
+                    return;
+                }
+                res.writeHead(200, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: true, ...publicVerdict(verdict) }));
+            } catch (e) {
+                console.error('Error judging submission:', e);
+                res.writeHead(500, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: e.message }));
+            }
+        });
+        return;
+    }
+
+    // PUT /p/:code@n - Numbered versions never change, so shared links keep showing what they showed
+    if (req.method === 'PUT' && /^\/p\/[A-Za-z0-9]+@\d+$/.test(req.url)) {
+        sendJson(res, 405, { success: false, error: 'Versions cannot be changed; PUT /p/:code adds a new one' }, { Allow: 'GET' });
+        return;
+    }
+
+    // DELETE /p/:code - Delete a permalink with all its versions and OG images
+    const deleteMatch = req.url.match(/^\/p\/([A-Za-z0-9]+)$/);
+    if (req.method === 'DELETE' && deleteMatch) {
+        const code = deleteMatch[1];
+        if (!isLive(code)) {
+            res.writeHead(404, { 'Content-Type': 'application/json' });
+            res.end(JSON.stringify({ success: false, error: 'Not found' }));
+            return;
+        }
+        const denied = checkOwner(req, code);
+        if (denied) {
+            res.writeHead(denied.status, { 'Content-Type': 'application/json' });
+            res.end(JSON.stringify({ success: false, error: denied.error }));
+            return;
+        }
+        deletePermalink(store, code).then(deleted => {
+            searchIndex.remove(code);
+            console.log(`Deleted ${deleted.join(', ')}`);
+            res.writeHead(200, { 'Content-Type': 'application/json' });
+            res.end(JSON.stringify({ success: true, deleted }));
+        }).catch(e => {
+            console.error('Error deleting permalink:', e.message);
+            res.writeHead(500, { 'Content-Type': 'application/json' });
+            res.end(JSON.stringify({ success: false, error: 'Failed to delete' }));
+        });
+        return;
+    }
+
+    // POST /p/:code/versions or PUT /p/:code - Add a version to a permalink
+    const versionsMatch = (req.method === 'POST' && req.url.match(/^\/p\/([A-Za-z0-9]+)\/versions$/))
+        || (req.method === 'PUT' && req.url.match(/^\/p\/([A-Za-z0-9]+)$/));
+    if (versionsMatch) {
+        readBody(req, res, async (body) => {
+            const code = versionsMatch[1];
+            if (!isLive(code)) {
+                res.writeHead(404, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: 'Not found' }));
+                return;
+            }
+            const denied = checkOwner(req, code);
+            if (denied) {
+                res.writeHead(denied.status, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: denied.error }));
+                return;
+            }
+
+            let built;
+            try {
+                built = await buildContent(JSON.parse(body));
+            } catch (e) {
+                res.writeHead(400, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: 'Invalid JSON' }));
+                return;
+            }
+            if (built.error) {
+                sendBuildError(res, built);
+                return;
+            }
+
+            // Saving the latest version again does not add one
+            const latest = resolvePermalink(code);
+            if (contentHash(latest.data) === contentHash(built.content)) {
+                res.writeHead(200, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: true, id: latest.id, version: latest.version }));
+                return;
+            }
+
+            let version;
+            built.content.createdAt = new Date().toISOString();
+            try {
+                version = await insertVersion(code, built.content);
+            } catch (e) {
+                console.error('Error saving version:', e.message);
+                res.writeHead(500, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: false, error: 'Failed to save' }));
+                return;
+            }
+
+            const id = versionId(code, version);
+            reindex(code);
+            generateOGInBackground(id, built.content);
+            res.writeHead(200, { 'Content-Type': 'application/json' });
+            res.end(JSON.stringify({ success: true, id, version }));
+        });
+        return;
+    }
+
     // POST /p - Create permalink
     if (req.method === 'POST' && req.url === '/p') {
-        let body = '';
-        req.on('data', chunk => body += chunk.toString());
-        req.on('end', async () => {
+        readBody(req, res, async (body) => {
             try {
-                const { lang, code, result, resultHtml } = JSON.parse(body);
-                
-                if (!lang || !code) {
-                    res.writeHead(400, { 'Content-Type': 'application/json' });
-                    res.end(JSON.stringify({ success: false, error: 'Missing lang or code' }));
+                const data = JSON.parse(body);
+                const built = await buildContent(data);
+                if (built.error) {
+                    sendBuildError(res, built);
                     return;
                 }
+                const content = built.content;
 
-                // Build content object (only include result/resultHtml if provided)
-                const content = { lang, code };
-                if (result) content.result = result;
-                if (resultHtml) content.resultHtml = resultHtml;
-                const contentStr = JSON.stringify(content);
-
-                // Check for existing identical content
-                for (const [key, value] of Object.entries(permalinks)) {
-                    if (JSON.stringify(value) === contentStr) {
-                        res.writeHead(200, { 'Content-Type': 'application/json' });
-                        res.end(JSON.stringify({ success: true, id: key }));
+                // Forks remember the exact version they started from
+                if (data.forkOf !== undefined) {
+                    const parent = typeof data.forkOf === 'string' ? resolvePermalink(data.forkOf) : null;
+                    if (!parent) {
+                        res.writeHead(400, { 'Content-Type': 'application/json' });
+                        res.end(JSON.stringify({ success: false, error: 'Fork parent not found' }));
                         return;
                     }
+                    content.forkOf = `${parent.code}@${parent.version}`;
                 }
 
-                // Generate new code
-                let newCode = generateCode();
-                while (permalinks[newCode]) {
-                    newCode = incrementCode(newCode);
+                // Expiry: the server's TTL, or a shorter one the client asks for
+                const { expiresIn } = data;
+                if (expiresIn !== undefined && !(Number.isInteger(expiresIn) && expiresIn > 0)) {
+                    sendJson(res, 400, { success: false, error: 'expiresIn must be a positive number of seconds' });
+                    return;
                 }
+                const lifetime = TTL && expiresIn ? Math.min(TTL, expiresIn) : (expiresIn || TTL);
+                if (lifetime) content.expiresAt = new Date(Date.now() + lifetime * 1000).toISOString();
 
-                // Store and save
-                permalinks[newCode] = content;
-                if (savePermalinks(permalinks)) {
-                    // Generate OG image in background (don't wait)
-                    generateAndSaveOGImage(newCode, code, lang, result || null, resultHtml || null)
-                        .then(() => console.log(`Generated OG image for ${newCode}`))
-                        .catch(e => console.error(`Failed to generate OG image for ${newCode}:`, e.message));
-                    
+                // Identical content in an unowned (pre-token) permalink: share it, as nobody can change it
+                const existing = store.findByHash(contentHash(content));
+                if (existing && isLive(existing.split('@')[0]) && outlasts(existing, content)) {
                     res.writeHead(200, { 'Content-Type': 'application/json' });
-                    res.end(JSON.stringify({ success: true, id: newCode }));
-                } else {
+                    res.end(JSON.stringify({ success: true, id: pinnedId(existing) }));
+                    return;
+                }
+
+                // Store and save (with the hash of a fresh owner token)
+                const token = generateToken();
+                content.owner = hashToken(token);
+                content.createdAt = new Date().toISOString();
+                let newCode;
+                try {
+                    newCode = await insertPermalink(content);
+                } catch (e) {
+                    console.error('Error saving permalink:', e.message);
                     res.writeHead(500, { 'Content-Type': 'application/json' });
                     res.end(JSON.stringify({ success: false, error: 'Failed to save' }));
+                    return;
                 }
+
+                reindex(newCode);
+                generateOGInBackground(newCode, content);
+                res.writeHead(200, { 'Content-Type': 'application/json' });
+                res.end(JSON.stringify({ success: true, id: newCode, token, ...(content.expiresAt ? { expiresAt: content.expiresAt } : {}) }));
             } catch (e) {
                 res.writeHead(400, { 'Content-Type': 'application/json' });
                 res.end(JSON.stringify({ success: false, error: 'Invalid JSON' }));
@@ -366,7 +1155,7 @@ const server = http.createServer((req, res) => {
     // Health check
     if (req.method === 'GET' && req.url === '/health') {
         res.writeHead(200, { 'Content-Type': 'application/json' });
-        res.end(JSON.stringify({ status: 'ok', count: Object.keys(permalinks).length }));
+        res.end(JSON.stringify({ status: 'ok', storage: store.backend, count: store.count(), indexed: searchIndex.size() }));
         return;
     }
 
@@ -376,6 +1165,19 @@ const server = http.createServer((req, res) => {
 
 server.listen(PORT, () => {
     console.log(`Permalink server running on http://localhost:${PORT}`);
-    console.log(`Storage: ${STORAGE_FILE}`);
-    console.log(`Loaded ${Object.keys(permalinks).length} permalinks`);
+    console.log(`Storage: ${store.location} (${store.backend})`);
+    console.log(`Loaded ${store.count()} permalinks`);
 });
+
+// Expired permalinks are removed at startup and then hourly
+const sweepExpiredSafely = () => sweepExpired().catch(e => console.error('Error removing expired permalinks:', e.message));
+sweepExpiredSafely();
+setInterval(sweepExpiredSafely, 60 * 60 * 1000).unref();
+
+// Let pending writes finish before exiting
+for (const signal of ['SIGINT', 'SIGTERM']) {
+    process.on(signal, () => {
+        server.close();
+        store.close().finally(() => process.exit(0));
+    });
+}
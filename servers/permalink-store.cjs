@@ -0,0 +1,350 @@
+/**
+ * Permalink Storage
+ * One interface, two backends:
+ *   json   - storage/permalinks.json, kept in memory (the original format)
+ *   sqlite - storage/permalinks.db, for instances with many links
+ *
+ * Both keep a content-hash index of unowned permalinks (links from before
+ * owner tokens), so posting content one of them already holds is a lookup
+ * instead of a scan over every entry. Owned links are never shared this way:
+ * their owner could change what the other poster's link shows.
+ *
+ * Versions of a permalink are stored under code@n (version 1 is the code
+ * itself); see versionId() and countVersions().
+ *
+ * Store interface:
+ *   get(id)               -> content or null
+ *   has(id)               -> boolean
+ *   findByHash(hash)      -> id of an unowned permalink with that content, or null
+ *   insert(id, content)   -> Promise, resolves once the write is on disk
+ *   insertMany(entries)   -> Promise, for [id, content] pairs in one write (migrations)
+ *   replace(id, content)  -> Promise
+ *   delete(id)            -> Promise
+ *   count()               -> number of permalinks
+ *   entries()             -> iterable of [id, content]
+ *   close()               -> Promise
+ *
+ * Reads are synchronous (the JSON store is in memory; SQLite drivers are
+ * synchronous), so request handlers can look links up directly.
+ *
+ * SQLite uses the built-in node:sqlite (Node 22.5+) or, failing that, the
+ * optional better-sqlite3 package.
+ */
+
+const fs = require('fs');
+const path = require('path');
+const crypto = require('crypto');
+
+const STORAGE_DIR = path.join(__dirname, '..', 'storage');
+const OG_DIR = path.join(STORAGE_DIR, 'og');
+const DEFAULT_PATHS = {
+    json: path.join(STORAGE_DIR, 'permalinks.json'),
+    sqlite: path.join(STORAGE_DIR, 'permalinks.db')
+};
+
+// Fields about a permalink rather than its content, left out of the content
+// hash: content is the same whoever posted it and whenever it expires
+const META_FIELDS = ['owner', 'expiresAt', 'createdAt'];
+
+/**
+ * JSON with object keys sorted, so equal content always serializes the same way
+ */
+function canonicalJson(value) {
+    if (Array.isArray(value)) return `[${value.map(canonicalJson).join(',')}]`;
+    if (value && typeof value === 'object') {
+        return `{${Object.keys(value).sort().map(key => `${JSON.stringify(key)}:${canonicalJson(value[key])}`).join(',')}}`;
+    }
+    return JSON.stringify(value);
+}
+
+/**
+ * Hash used for dedup: sha256 of the canonical JSON, without META_FIELDS
+ */
+function contentHash(content) {
+    const hashed = { ...content };
+    for (const field of META_FIELDS) delete hashed[field];
+    return crypto.createHash('sha256').update(canonicalJson(hashed)).digest('hex');
+}
+
+// Whether posting the same content may reuse this entry: only the first
+// version of a permalink nobody owns
+function isShareable(id, content) {
+    return !id.includes('@') && !content.owner;
+}
+
+// Storage id of a version (version 1 is the code itself)
+function versionId(code, version) {
+    return version === 1 ? code : `${code}@${version}`;
+}
+
+// Number of versions of a permalink
+function countVersions(store, code) {
+    let versions = 1;
+    while (store.has(`${code}@${versions + 1}`)) versions++;
+    return versions;
+}
+
+/**
+ * Delete a permalink with all its versions and their OG images
+ * @returns {Promise<string[]>} - Deleted ids
+ */
+async function deletePermalink(store, code) {
+    const deleted = [];
+    // Latest first, so a failure never leaves a gap in the chain
+    for (let version = countVersions(store, code); version >= 1; version--) {
+        const id = versionId(code, version);
+        await store.delete(id);
+        deleted.push(id);
+        try {
+            await fs.promises.unlink(path.join(OG_DIR, `${id}.png`));
+        } catch (e) {
+            if (e.code !== 'ENOENT') console.error(`Could not remove OG image for ${id}:`, e.message);
+        }
+    }
+    return deleted;
+}
+
+// ========================================
+// JSON file backend
+// ========================================
+
+/**
+ * Writes go to a temporary file that is renamed over the old one, so a crash
+ * mid-write never leaves a truncated file. Writes are serialized, and inserts
+ * that arrive while a write is in flight share the next one.
+ */
+function createJsonStore(file = DEFAULT_PATHS.json) {
+    let permalinks = {};
+    try {
+        if (fs.existsSync(file)) {
+            permalinks = JSON.parse(fs.readFileSync(file, 'utf8'));
+        }
+    } catch (e) {
+        // Refuse to start rather than overwrite a file we could not read
+        throw new Error(`Could not load ${file}: ${e.message}`);
+    }
+
+    const hashIndex = new Map();  // hash -> Set of ids, oldest first
+    const hashes = new Map();     // id -> hash
+
+    function index(id, content) {
+        if (!isShareable(id, content)) return;
+        const hash = contentHash(content);
+        hashes.set(id, hash);
+        if (!hashIndex.has(hash)) hashIndex.set(hash, new Set());
+        hashIndex.get(hash).add(id);
+    }
+
+    function unindex(id) {
+        if (!hashes.has(id)) return;
+        const hash = hashes.get(id);
+        const ids = hashIndex.get(hash);
+        hashes.delete(id);
+        if (!ids) return;
+        ids.delete(id);
+        if (ids.size === 0) hashIndex.delete(hash);
+    }
+
+    for (const [id, content] of Object.entries(permalinks)) index(id, content);
+
+    let queue = Promise.resolve();  // Last scheduled write
+    let nextWrite = null;           // Write not started yet, shared by inserts queued behind it
+
+    async function writeFile() {
+        const dir = path.dirname(file);
+        await fs.promises.mkdir(dir, { recursive: true });
+        const temp = `${file}.${process.pid}.tmp`;
+        await fs.promises.writeFile(temp, JSON.stringify(permalinks, null, 2));
+        await fs.promises.rename(temp, file);
+    }
+
+    function scheduleWrite() {
+        if (nextWrite) return nextWrite;
+        nextWrite = queue.catch(() => {}).then(() => {
+            // Inserts from now on need another write
+            nextWrite = null;
+            return writeFile();
+        });
+        queue = nextWrite;
+        return nextWrite;
+    }
+
+    // Apply a change in memory and write it; undo it if the write fails
+    async function commit(apply, undo) {
+        apply();
+        try {
+            await scheduleWrite();
+        } catch (e) {
+            undo();
+            throw e;
+        }
+    }
+
+    function put(id, content) {
+        permalinks[id] = content;
+        index(id, content);
+    }
+
+    function remove(id) {
+        delete permalinks[id];
+        unindex(id);
+    }
+
+    return {
+        backend: 'json',
+        location: file,
+        get: (id) => Object.prototype.hasOwnProperty.call(permalinks, id) ? permalinks[id] : null,
+        has: (id) => Object.prototype.hasOwnProperty.call(permalinks, id),
+        findByHash(hash) {
+            const ids = hashIndex.get(hash);
+            return ids ? ids.values().next().value : null;
+        },
+        insert(id, content) {
+            return this.insertMany([[id, content]]);
+        },
+        insertMany(entries) {
+            return commit(
+                () => entries.forEach(([id, content]) => put(id, content)),
+                () => entries.forEach(([id]) => remove(id))
+            );
+        },
+        replace(id, content) {
+            const previous = permalinks[id];
+            return commit(
+                () => { remove(id); put(id, content); },
+                () => { remove(id); put(id, previous); }
+            );
+        },
+        delete(id) {
+            if (!Object.prototype.hasOwnProperty.call(permalinks, id)) return Promise.resolve();
+            const previous = permalinks[id];
+            return commit(() => remove(id), () => put(id, previous));
+        },
+        count: () => Object.keys(permalinks).length,
+        entries: () => Object.entries(permalinks),
+        async close() {
+            await queue.catch(() => {});
+        }
+    };
+}
+
+// ========================================
+// SQLite backend
+// ========================================
+
+/**
+ * Open a database with whichever driver is available
+ * Both drivers share the prepare().run/get/all API used below.
+ */
+function openSqlite(file) {
+    try {
+        const { DatabaseSync } = require('node:sqlite');
+        return new DatabaseSync(file);
+    } catch (e) {
+        // Older Node: fall through to better-sqlite3
+    }
+    try {
+        const Database = require('better-sqlite3');
+        return new Database(file);
+    } catch (e) {
+        throw new Error('SQLite storage needs Node 22.5+ (node:sqlite) or the better-sqlite3 package (npm install better-sqlite3)');
+    }
+}
+
+/**
+ * Every insert (or insertMany batch) is one transaction; WAL mode and a busy timeout let
+ * several processes (a server and the migration script) share the file.
+ */
+function createSqliteStore(file = DEFAULT_PATHS.sqlite) {
+    fs.mkdirSync(path.dirname(file), { recursive: true });
+    const db = openSqlite(file);
+    db.exec(`
+        PRAGMA journal_mode = WAL;
+        PRAGMA busy_timeout = 5000;
+        CREATE TABLE IF NOT EXISTS permalinks (
+            id TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            hash TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS permalinks_hash ON permalinks (hash);
+    `);
+
+    const statements = {
+        get: db.prepare('SELECT content FROM permalinks WHERE id = ?'),
+        findByHash: db.prepare('SELECT id, content FROM permalinks WHERE hash = ? ORDER BY created_at'),
+        insert: db.prepare('INSERT INTO permalinks (id, content, hash, created_at) VALUES (?, ?, ?, ?)'),
+        replace: db.prepare('UPDATE permalinks SET content = ?, hash = ? WHERE id = ?'),
+        delete: db.prepare('DELETE FROM permalinks WHERE id = ?'),
+        count: db.prepare('SELECT COUNT(*) AS count FROM permalinks'),
+        all: db.prepare('SELECT id, content FROM permalinks ORDER BY created_at')
+    };
+
+    return {
+        backend: 'sqlite',
+        location: file,
+        get(id) {
+            const row = statements.get.get(id);
+            return row ? JSON.parse(row.content) : null;
+        },
+        has: (id) => Boolean(statements.get.get(id)),
+        findByHash(hash) {
+            const row = statements.findByHash.all(hash).find(row => isShareable(row.id, JSON.parse(row.content)));
+            return row ? row.id : null;
+        },
+        async insert(id, content) {
+            // A primary key conflict (another process took the id) throws
+            statements.insert.run(id, JSON.stringify(content), contentHash(content), new Date().toISOString());
+        },
+        async insertMany(entries) {
+            const createdAt = new Date().toISOString();
+            db.exec('BEGIN IMMEDIATE');
+            try {
+                for (const [id, content] of entries) {
+                    statements.insert.run(id, JSON.stringify(content), contentHash(content), createdAt);
+                }
+                db.exec('COMMIT');
+            } catch (e) {
+                db.exec('ROLLBACK');
+                throw e;
+            }
+        },
+        async replace(id, content) {
+            statements.replace.run(JSON.stringify(content), contentHash(content), id);
+        },
+        async delete(id) {
+            statements.delete.run(id);
+        },
+        count: () => statements.count.get().count,
+        *entries() {
+            for (const row of statements.all.all()) yield [row.id, JSON.parse(row.content)];
+        },
+        async close() {
+            db.close();
+        }
+    };
+}
+
+/**
+ * Create the store named by a spec: "json", "sqlite", or "<backend>:<path>"
+ */
+function createStore(spec = 'json') {
+    const [backend, ...rest] = spec.split(':');
+    const file = rest.length ? path.resolve(rest.join(':')) : undefined;
+    if (backend === 'json') return createJsonStore(file);
+    if (backend === 'sqlite') return createSqliteStore(file);
+    throw new Error(`Unknown permalink storage: ${backend} (expected json or sqlite)`);
+}
+
+module.exports = {
+    createStore,
+    createJsonStore,
+    createSqliteStore,
+    contentHash,
+    canonicalJson,
+    versionId,
+    countVersions,
+    deletePermalink,
+    DEFAULT_PATHS,
+    OG_DIR
+};
@@ -0,0 +1,65 @@
+/**
+ * Token-bucket rate limiting, one bucket per key (client IP)
+ *
+ * Each bucket holds up to `burst` tokens and refills at burst / perSeconds
+ * tokens a second; a request takes one token. So "20/60" allows bursts of 20
+ * and 20 requests a minute on average.
+ */
+
+/**
+ * Parse a limit spec: "<burst>/<seconds>", or "off"
+ * @returns {{burst: number, perSeconds: number}|null} - null when disabled
+ */
+function parseRateLimit(spec) {
+    if (!spec || spec === 'off') return null;
+    const match = /^(\d+)\/(\d+)$/.exec(String(spec).trim());
+    if (!match || parseInt(match[1]) < 1 || parseInt(match[2]) < 1) {
+        throw new Error(`Invalid rate limit "${spec}" (expected <requests>/<seconds> or off)`);
+    }
+    return { burst: parseInt(match[1]), perSeconds: parseInt(match[2]) };
+}
+
+/**
+ * @param {{burst: number, perSeconds: number}} limit
+ * @returns {{take: Function, size: Function}} - take(key) returns
+ *   { allowed: true } or { allowed: false, retryAfter } (seconds)
+ */
+function createRateLimiter({ burst, perSeconds }) {
+    const refillPerMs = burst / (perSeconds * 1000);
+    const buckets = new Map(); // key -> { tokens, updated }
+
+    function refill(bucket, now) {
+        bucket.tokens = Math.min(burst, bucket.tokens + (now - bucket.updated) * refillPerMs);
+        bucket.updated = now;
+    }
+
+    // Full buckets are the same as no bucket, so drop them now and then
+    const sweep = setInterval(() => {
+        const now = Date.now();
+        for (const [key, bucket] of buckets) {
+            refill(bucket, now);
+            if (bucket.tokens >= burst) buckets.delete(key);
+        }
+    }, perSeconds * 1000);
+    sweep.unref();
+
+    return {
+        take(key) {
+            const now = Date.now();
+            let bucket = buckets.get(key);
+            if (!bucket) {
+                bucket = { tokens: burst, updated: now };
+                buckets.set(key, bucket);
+            }
+            refill(bucket, now);
+            if (bucket.tokens >= 1) {
+                bucket.tokens -= 1;
+                return { allowed: true };
+            }
+            return { allowed: false, retryAfter: Math.ceil((1 - bucket.tokens) / refillPerMs / 1000) };
+        },
+        size: () => buckets.size
+    };
+}
+
+module.exports = { parseRateLimit, createRateLimiter };
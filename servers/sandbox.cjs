@@ -24,7 +24,12 @@ const CONFIG = {
     
     // Container images (will be built on first use)
     images: {
-        apl: 'arraybox-sandbox-apl'
+        apl: 'arraybox-sandbox-apl',
+        bqn: 'arraybox-sandbox-bqn',
+        j: 'arraybox-sandbox-j',
+        uiua: 'arraybox-sandbox-uiua',
+        kap: 'arraybox-sandbox-kap',
+        tinyapl: 'arraybox-sandbox-tinyapl'
     },
     
     // Paths to Dockerfiles
@@ -37,7 +42,9 @@ const CONFIG = {
     useWarmContainers: true,
     warmContainerIdleTimeout: 0,       // 0 = never kill idle containers (always warm)
     maxRequestsPerContainer: 500,      // Recycle container after N requests (security/isolation)
-    prewarmOnStartup: true             // Start containers immediately on module load
+    prewarmOnStartup: true,            // Start containers immediately on module load
+    prewarmLanguages: ['apl'],         // Languages started by prewarmContainers()
+    maxAplSessions: 100                // APL session namespaces kept per container (oldest dropped first)
 };
 
 // Warm container pool: language -> { container, busy, requestCount, lastUsed }
@@ -270,6 +277,103 @@ function filterAplErrorOutput(stdout, stderr, code, markers = null) {
     return lines.join('\n').trim();
 }
 
+// Printed by docker/runner.sh once it is ready to accept requests
+const RUNNER_READY_MARKER = '___ARRAYBOX_READY___';
+
+const TINYAPL_ERROR_PATTERNS = [
+    /^(?:Syntax|Parse|Value|Domain|Rank|Length|Index|NYI|Internal|Invalid|Assertion) error:/im,
+    /does not exist$/im,
+    /not assigned$/im
+];
+
+/**
+ * Output handling for the runner-based images (every language except APL).
+ * The runner merges stdout/stderr and reports the interpreter's exit status,
+ * so each entry only tidies the text and spots errors that still exit with 0.
+ */
+const RUNNER_LANGUAGES = {
+    bqn: {
+        // CBQN reports "Error: <message>" followed by the stack trace
+        clean: (output) => output.replace(/^Error: /, ''),
+        isError: () => false
+    },
+    j: {
+        // (0!:101) echoes each input line with leading whitespace - same cleanup as the browser
+        clean: (output, code) => {
+            const codeLines = new Set(code.split('\n').map(l => l.trim()).filter(l => l));
+            return output.split('\n')
+                .filter(line => line.trim() !== '' && !codeLines.has(line.trim()))
+                .join('\n')
+                .replace(/^(?:[ \t]*\r?\n)+/, '');
+        },
+        isError: (output) => output.trimStart().startsWith('|')
+    },
+    uiua: {
+        clean: (output) => output,
+        isError: () => false
+    },
+    kap: {
+        clean: (output) => output,
+        isError: () => false
+    },
+    tinyapl: {
+        clean: (output) => output,
+        isError: (output) => TINYAPL_ERROR_PATTERNS.some(p => p.test(output))
+    }
+};
+
+/**
+ * Build a runner request line: "<id> <base64 code>"
+ * Base64 keeps multi-line code and arbitrary glyphs on a single line.
+ */
+function createRunnerRequest(code) {
+    const id = Math.random().toString(36).slice(2);
+    return {
+        markers: {
+            start: `___ARRAYBOX_START_${id}___`,
+            end: `___ARRAYBOX_END_${id}___`
+        },
+        input: `${id} ${Buffer.from(code, 'utf8').toString('base64')}\n`
+    };
+}
+
+/**
+ * Extract the program output and exit status from runner stdout.
+ * Returns null until the end marker (and its status) has arrived.
+ */
+function extractRunnerResult(stdout, markers) {
+    const endMatch = stdout.match(new RegExp(`${markers.end} (\\d+)\\r?\\n`));
+    if (!endMatch) return null;
+
+    let section = stdout.slice(0, endMatch.index);
+    const startIdx = section.indexOf(markers.start);
+    if (startIdx !== -1) {
+        section = section.slice(startIdx + markers.start.length);
+    }
+    return { output: section, status: parseInt(endMatch[1], 10) };
+}
+
+/**
+ * Turn raw runner output into the { success, output } result shape
+ */
+function formatRunnerResult(language, code, output, status) {
+    const timeoutSeconds = Math.floor(CONFIG.timeout / 1000);
+    // 124: killed by `timeout`; 137: SIGKILL (timeout -k or the memory limit)
+    if (status === 124) {
+        return { success: false, output: `Execution timed out (${timeoutSeconds} seconds)`, timedOut: true };
+    }
+    if (status === 137) {
+        return { success: false, output: `Execution killed (time or memory limit exceeded)`, timedOut: true };
+    }
+
+    const runner = RUNNER_LANGUAGES[language];
+    const text = runner.clean(output.replace(/\r/g, ''), code).replace(/^\n+|\n+$/g, '');
+    return {
+        success: status === 0 && !runner.isError(text),
+        output: text
+    };
+}
+
 /**
  * Start a warm container for a language
  */
@@ -293,13 +397,19 @@ async function startWarmContainer(language, options = {}) {
         '--cap-drop=ALL',
     ];
     
-    // APL-specific options
-    const dyalogPath = options.dyalogPath || findDyalogPath();
-    if (dyalogPath) {
-        dockerArgs.push('-v', `${dyalogPath}:/opt/dyalog:ro`);
-    }
     dockerArgs.push('--tmpfs', `/home/sandbox:rw,exec,uid=${uid},gid=${uid},size=16m`);
-    dockerArgs.push('--entrypoint', '/opt/dyalog/mapl');
+
+    if (language === 'apl') {
+        // APL-specific options
+        const dyalogPath = options.dyalogPath || findDyalogPath();
+        if (dyalogPath) {
+            dockerArgs.push('-v', `${dyalogPath}:/opt/dyalog:ro`);
+        }
+        dockerArgs.push('--entrypoint', '/opt/dyalog/mapl');
+    } else {
+        // Other languages use the image's runner.sh entrypoint, which enforces the timeout per run
+        dockerArgs.push('-e', `ARRAYBOX_TIMEOUT=${Math.floor(CONFIG.timeout / 1000)}`);
+    }
     dockerArgs.push(imageName);
     
     const container = spawn('docker', dockerArgs, {
@@ -314,10 +424,86 @@ async function startWarmContainer(language, options = {}) {
         busy: false,
         requestCount: 0,
         lastUsed: Date.now(),
-        language
+        language,
+        // APL session namespaces (#.ArrayboxSessions.S_<id>) living in this container
+        aplSessions: new Map(),        // id -> last used time
+        expiredAplSessions: []         // ids to expunge before the next request
     };
 }
 
+/**
+ * Check an APL session id (letters and digits, so it can be part of an APL name)
+ */
+function isValidSessionId(id) {
+    return typeof id === 'string' && /^[A-Za-z0-9]{1,64}$/.test(id);
+}
+
+/**
+ * Mark a session as used, dropping the least recently used ones over the limit
+ */
+function touchAplSession(container, id) {
+    container.aplSessions.delete(id);
+    container.aplSessions.set(id, Date.now());
+    while (container.aplSessions.size > CONFIG.maxAplSessions) {
+        const oldest = container.aplSessions.keys().next().value;
+        container.aplSessions.delete(oldest);
+        container.expiredAplSessions.push(oldest);
+    }
+}
+
+/**
+ * Forget an APL session; its namespace is expunged before the next APL request
+ */
+function resetAplSession(id) {
+    const container = warmPool.apl;
+    if (!container || !container.aplSessions.delete(id)) return false;
+    container.expiredAplSessions.push(id);
+    return true;
+}
+
+/**
+ * APL lines that create the session container namespace and expunge expired sessions
+ */
+function aplSessionSetup(container) {
+    let setup = `{}'ArrayboxSessions'#.⎕NS''\n`;
+    if (container.expiredAplSessions.length > 0) {
+        const names = container.expiredAplSessions.map(id => `'S_${id}'`).join(' ');
+        setup += `{}#.ArrayboxSessions.⎕EX ${container.expiredAplSessions.length === 1 ? names : `(${names})`}\n`;
+        container.expiredAplSessions = [];
+    }
+    return setup;
+}
+
+/**
+ * Whether a line the warm APL container printed is the program's output rather
+ * than an echoed command, a marker or a workspace message. Echoes are indented,
+ * but so are train trees and boxes, which are kept.
+ */
+function isAplOutputLine(line, markers) {
+    const trimmed = line.trim();
+    if (trimmed === '' || trimmed === 'clear ws' || trimmed === ')SIC') return false;
+    if (trimmed === markers.start || trimmed === markers.end || trimmed === markers.reset) return false;
+    const looksLikeEcho = trimmed.startsWith(']') || trimmed.startsWith('⎕←') || trimmed.startsWith(')') ||
+        trimmed.startsWith(':') || /^Safe3\.Exec/.test(trimmed);
+    const hasBoxDrawing = /[┌┐└┘─┼│├┤┬┴]/.test(line);
+    return !(line.startsWith(' ') && looksLikeEcho && !hasBoxDrawing);
+}
+
+/**
+ * Throw away a warm container whose state is unknown (a request that timed
+ * out or never finished resetting): it stays busy so it is never handed out
+ * again, and the next request starts a new one
+ */
+function retireWarmContainer(container) {
+    container.busy = true;
+    if (container.process && !container.process.killed) {
+        container.process.kill();
+    }
+    if (warmPool[container.language] === container) {
+        delete warmPool[container.language];
+    }
+}
+
 /**
  * Get or create a warm container for a language
  */
@@ -367,6 +553,10 @@ async function getWarmContainer(language, options = {}) {
             if (language === 'apl' && (initStdout.includes('Copyright') || initStderr.includes('Copyright'))) {
                 setTimeout(finish, 200);  // Extra delay to drain all banner output
             }
+            // Runner-based images announce themselves with a ready marker
+            if (language !== 'apl' && initStdout.includes(RUNNER_READY_MARKER)) {
+                finish();
+            }
         };
         
         const onStdout = (data) => {
@@ -465,20 +655,28 @@ async function executeWithWarmContainer(language, code, options = {}) {
     container.busy = true;
     container.requestCount++;
     container.lastUsed = Date.now();
-    
+
+    if (language !== 'apl') {
+        return executeWithRunner(container, language, code);
+    }
+
     return new Promise((resolve, reject) => {
         let output = '';
         let stderrOutput = '';
         let timeoutId;
         
-        const cleanup = () => {
-            container.busy = false;
+        const detach = () => {
             container.stdout.removeListener('data', onData);
             container.stderr.removeListener('data', onStderr);
             if (timeoutId) clearTimeout(timeoutId);
             if (errorCheckTimeout) clearTimeout(errorCheckTimeout);
         };
         
+        const cleanup = () => {
+            container.busy = false;
+            detach();
+        };
+        
         let resolved = false;
         let aplMarkers = null;
         let aplCodeLines = null;  // Lines to filter as echoed input (the wrapped code, not original)
@@ -518,11 +716,13 @@ async function executeWithWarmContainer(language, code, options = {}) {
             
             // Reset APL state - use a marker to know when cleanup is done
             let resetOutput = '';
+            let resetSeen = false;
             
             const onResetData = (data) => {
                 const str = data.toString();
                 resetOutput += str;
                 if (resetOutput.includes(APL_RESET_MARKER)) {
+                    resetSeen = true;
                     container.stdout.removeListener('data', onResetData);
                     container.stderr.removeListener('data', onResetStderr);
                     container.busy = false;  // Now safe for next request
@@ -532,6 +732,7 @@ async function executeWithWarmContainer(language, code, options = {}) {
                 const str = data.toString();
                 resetOutput += str; // Check stderr for marker too
                  if (resetOutput.includes(APL_RESET_MARKER)) {
+                    resetSeen = true;
                     container.stdout.removeListener('data', onResetData);
                     container.stderr.removeListener('data', onResetStderr);
                     container.busy = false;  // Now safe for next request
@@ -545,14 +746,17 @@ async function executeWithWarmContainer(language, code, options = {}) {
             // Use expression to clear vars instead of )CLEAR command to avoid pipe issues
             // We must escape the reset marker to prevent it from being interpreted if inside a string
             // Use )SIC to clear state indicator completely - CRITICAL for error recovery
+            // Safe3 and the session namespaces must survive the clear
             const resetMarker = aplMarkers ? aplMarkers.reset : '___ARRAYBOX_RESET___';
-            container.stdin.write(`\n)SIC\n⎕EX ⎕NL ¯1\n⎕←'${resetMarker}'\n`);
+            container.stdin.write(`\n)SIC\n⎕EX (⎕NL ¯1)~'Safe3' 'ArrayboxSessions'\n⎕←'${resetMarker}'\n`);
             
-            // Fallback timeout in case marker never arrives
+            // Fallback timeout in case marker never arrives: the workspace may still be
+            // suspended or running, so the container is not reused
             setTimeout(() => {
+                if (resetSeen) return;
                 container.stdout.removeListener('data', onResetData);
                 container.stderr.removeListener('data', onResetStderr);
-                container.busy = false;
+                retireWarmContainer(container);
             }, 2000);
         };
         
@@ -594,9 +798,25 @@ async function executeWithWarmContainer(language, code, options = {}) {
             }
         };
         
+        // Pass complete lines printed between the start and end markers to
+        // options.onOutput as they arrive (the result still has all of them)
+        let streamed = 0;  // Length of output already passed on
+        const streamAplOutput = () => {
+            const startIdx = output.indexOf(aplMarkers.start);
+            if (startIdx === -1) return;
+            const from = Math.max(streamed, startIdx + aplMarkers.start.length);
+            const endIdx = output.indexOf(aplMarkers.end, from);
+            const to = endIdx !== -1 ? endIdx : output.lastIndexOf('\n') + 1;
+            if (to <= from) return;
+            streamed = to;
+            const lines = output.slice(from, to).split('\n').filter(line => isAplOutputLine(line, aplMarkers));
+            if (lines.length) options.onOutput(lines.map(l => l.replace(/\r/g, '')).join('\n') + '\n');
+        };
+        
         onData = (data) => {
             output += data.toString();
             checkAplMarkers();
+            if (options.onOutput && aplMarkers && !resolved) streamAplOutput();
             
             if (language === 'apl' && isAplErrorOutput(output) && !errorCheckTimeout && !resolved) {
                 errorCheckTimeout = setTimeout(() => {
@@ -642,20 +862,7 @@ async function executeWithWarmContainer(language, code, options = {}) {
                     const codeLines = aplCodeLines || new Set(code.split('\n').map(l => l.trim()).filter(l => l));
                     
                     // First pass: filter echoed commands and system responses (keep indented train-tree/box-drawing)
-                    const looksLikeEcho = (trimmed) =>
-                        trimmed.startsWith(']') || trimmed.startsWith('⎕←') || trimmed.startsWith(')') ||
-                        trimmed.startsWith(':') || /^Safe3\.Exec/.test(trimmed);
-                    const hasBoxDrawing = (s) => /[┌┐└┘─┼│├┤┬┴]/.test(s);
-                    lines = lines.filter(line => {
-                        const trimmed = line.trim();
-                        if (trimmed === '') return false;
-                        if (trimmed === 'clear ws') return false;
-                        if (trimmed === ')SIC') return false;
-                        if (trimmed === aplMarkers.start || trimmed === aplMarkers.end || trimmed === aplMarkers.reset) return false;
-                        // Drop indented lines only if they look like echoed commands, not train-tree/box-drawing
-                        if (line.startsWith(' ') && looksLikeEcho(trimmed) && !hasBoxDrawing(line)) return false;
-                        return true;
-                    });
+                    lines = lines.filter(line => isAplOutputLine(line, aplMarkers));
                     
                     // Second pass: remove trailing lines that match user input (echoed)
                     while (lines.length > 0) {
@@ -704,20 +911,33 @@ async function executeWithWarmContainer(language, code, options = {}) {
             // Store for filtering
             aplCodeLines = new Set([`Safe3.Exec`]);
             
+            // Session mode: run in the session's own namespace so definitions persist
+            // (without a left argument Safe3.Exec uses a new empty namespace every time)
+            let setup = '';
+            let space = '';
+            if (options.session) {
+                touchAplSession(container, options.session);
+                space = `(#.ArrayboxSessions.(⍎'S_${options.session}'⎕NS''))`;
+            }
+            if (options.session || container.expiredAplSessions.length > 0) {
+                setup = aplSessionSetup(container);
+            }
+            
             // Build Safe3.Exec call wrapped in :Trap to catch security errors
             // Without :Trap, ⎕SIGNAL from Safe3 would prevent end markers from printing
-            input = `]boxing on -s=min -trains=tree\n)SIC\n⎕←'${aplMarkers.start}'\n:Trap 0 ⋄ ⎕←Safe3.Exec '${escapedCode}' ⋄ :Else ⋄ ⎕←⎕DMX.EM,': ',⎕DMX.Message ⋄ :EndTrap\n)SIC\n⎕←'${aplMarkers.end}'\n⎕←'${aplMarkers.reset}'\n`;
+            input = `${setup}]boxing on -s=min -trains=tree\n)SIC\n⎕←'${aplMarkers.start}'\n:Trap 0 ⋄ ⎕←${space}Safe3.Exec '${escapedCode}' ⋄ :Else ⋄ ⎕←⎕DMX.EM,': ',⎕DMX.Message ⋄ :EndTrap\n)SIC\n⎕←'${aplMarkers.end}'\n⎕←'${aplMarkers.reset}'\n`;
         }
         
         // Set timeout
         timeoutId = setTimeout(() => {
             if (resolved) return;
             resolved = true;
-            cleanup();
+            detach();
             
-            if (language === 'apl') {
-                // APL timeouts are handled by returning stderr/partial output below.
-            }
+            // The interpreter may still be running the code (and would print into the
+            // next request's output), so the container is thrown away rather than reused.
+            // The partial output or stderr is returned below.
+            retireWarmContainer(container);
             
             // Filter Dyalog banner from stderr
             let filteredStderr = '';
@@ -762,11 +982,62 @@ async function executeWithWarmContainer(language, code, options = {}) {
     });
 }
 
+/**
+ * Execute code in a warm runner container (every language except APL).
+ * The runner starts a fresh interpreter per request, so no reset step is needed.
+ */
+function executeWithRunner(container, language, code) {
+    return new Promise((resolve) => {
+        const { markers, input } = createRunnerRequest(code);
+        let stdout = '';
+        let timeoutId;
+
+        const detach = () => {
+            clearTimeout(timeoutId);
+            container.stdout.removeListener('data', onData);
+        };
+
+        const onData = (data) => {
+            stdout += data.toString();
+            const result = extractRunnerResult(stdout, markers);
+            if (!result) return;
+            detach();
+            container.busy = false;
+            resolve({ ...formatRunnerResult(language, code, result.output, result.status), warm: true });
+        };
+
+        container.stdout.on('data', onData);
+
+        // runner.sh enforces CONFIG.timeout itself; this only fires if the runner is wedged,
+        // in which case the container is thrown away rather than reused
+        timeoutId = setTimeout(() => {
+            detach();
+            retireWarmContainer(container);
+            resolve({
+                success: false,
+                output: `Execution timed out (${Math.floor(CONFIG.timeout / 1000)} seconds)`,
+                timedOut: true,
+                warm: true
+            });
+        }, CONFIG.timeout + 2000);
+
+        container.stdin.write(input);
+    });
+}
+
 /**
  * Execute code in a sandboxed Docker container
+ * options.session (APL only): session id whose namespace keeps definitions
+ * between requests; only honored by the warm container (result.warm)
+ * options.onOutput (APL only): (text) => void, called with lines the code
+ * prints as they are printed; also only by the warm container
  */
 function executeInSandbox(language, code, options = {}) {
     return new Promise(async (resolve, reject) => {
+        if (!CONFIG.images[language]) {
+            return reject(new Error('UNSUPPORTED_LANGUAGE'));
+        }
+
         // Try warm container first if enabled
         if (CONFIG.useWarmContainers) {
             try {
@@ -809,7 +1080,49 @@ function executeInSandbox(language, code, options = {}) {
             '--pids-limit=64',                   // Limit number of processes
             '--cap-drop=ALL',
         ];
-        
+
+        if (language !== 'apl') {
+            dockerArgs.push('--tmpfs', `/home/sandbox:rw,exec,uid=${uid},gid=${uid},size=16m`);
+            dockerArgs.push('-e', `ARRAYBOX_TIMEOUT=${Math.floor(timeout / 1000)}`);
+            dockerArgs.push(imageName);
+
+            const container = spawn('docker', dockerArgs, {
+                stdio: ['pipe', 'pipe', 'pipe']
+            });
+            const { markers, input } = createRunnerRequest(code);
+            let stdout = '';
+            let stderr = '';
+
+            container.stdout.on('data', d => stdout += d.toString());
+            container.stderr.on('data', d => stderr += d.toString());
+
+            // The runner exits on EOF; this is a backstop if the container hangs
+            const timeoutId = setTimeout(() => container.kill('SIGKILL'), timeout + 5000);
+
+            container.on('close', (exitCode) => {
+                clearTimeout(timeoutId);
+                const result = extractRunnerResult(stdout, markers);
+                if (!result) {
+                    resolve({
+                        success: false,
+                        output: stderr.trim() || `Execution timed out (${timeout / 1000} seconds)`,
+                        exitCode
+                    });
+                    return;
+                }
+                resolve({ ...formatRunnerResult(language, code, result.output, result.status), exitCode });
+            });
+
+            container.on('error', (err) => {
+                clearTimeout(timeoutId);
+                reject(new Error(`Container error: ${err.message}`));
+            });
+
+            container.stdin.write(input);
+            container.stdin.end();
+            return;
+        }
+
         // Mount Dyalog from host
         const dyalogPath = options.dyalogPath || findDyalogPath();
         if (dyalogPath) {
@@ -947,6 +1260,7 @@ function getStatus() {
         enabled: CONFIG.enabled,
         dockerAvailable: checkDocker(),
         imagesBuilt: { ...imagesBuilt },
+        languages: Object.keys(CONFIG.images),
         config: {
             memoryLimit: CONFIG.memoryLimit,
             cpuLimit: CONFIG.cpuLimit,
@@ -1000,7 +1314,7 @@ async function prewarmContainers() {
     }
     
     console.log('[Sandbox] Pre-warming containers...');
-    const languages = ['apl'];
+    const languages = CONFIG.prewarmLanguages;
     
     for (const lang of languages) {
         try {
@@ -1029,6 +1343,8 @@ setTimeout(() => {
 
 module.exports = {
     executeInSandbox,
+    isValidSessionId,
+    resetAplSession,
     isSandboxAvailable,
     setSandboxEnabled,
     getStatus,
@@ -136,6 +136,20 @@ const servers = {
         startTime: null,
         executable: null
     },
+    eval: {
+        name: 'Eval Server',
+        script: 'eval-server.cjs',
+        port: 8083,
+        color: ansi.green,
+        symbol: 'EVL',
+        process: null,
+        status: 'stopped',
+        requests: 0,
+        errors: 0,
+        lastRequest: null,
+        startTime: null,
+        executable: 'Docker (sandbox only)'
+    },
     permalink: {
         name: 'Permalink',
         script: 'permalink-server.cjs',
@@ -655,6 +669,29 @@ async function main() {
     
     // Kap is client-side only (Kotlin/JS) - no server to start
     
+    // Start the multi-language eval server (needs the Docker sandbox)
+    if (SANDBOX_MODE) {
+        const evalPort = servers.eval.port;
+        const evalScriptPath = path.join(__dirname, 'eval-server.cjs');
+        const evalProc = spawn('node', [evalScriptPath, String(evalPort)], {
+            stdio: ['ignore', 'pipe', 'pipe'],
+            cwd: __dirname
+        });
+        servers.eval.process = evalProc;
+        servers.eval.status = 'starting';
+        
+        evalProc.stdout.on('data', (data) => {
+            const text = data.toString();
+            if (text.includes('Server running on')) {
+                servers.eval.status = 'running';
+                servers.eval.startTime = Date.now();
+                renderDashboard();
+            }
+        });
+        evalProc.stderr.on('data', () => {});
+        evalProc.on('close', () => { servers.eval.status = 'stopped'; servers.eval.process = null; renderDashboard(); });
+    }
+    
     // Start Permalink server
     const permalinkPort = 8084;
     servers.permalink.port = permalinkPort;
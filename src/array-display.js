@@ -0,0 +1,152 @@
+/**
+ * Array Display
+ * Renders array results as HTML boxes from a structured value. Only TinyAPL
+ * and Kap hand one over (see runtimes.js); BQN, APL, J and Uiua results are
+ * formatted inside their interpreters and arrive as text, which is shown as is.
+ *
+ * Values share one model, built by each runtime from its interpreter's own:
+ *   { kind: 'number', text }          - text as the language prints it
+ *   { kind: 'char', text }            - one character
+ *   { kind: 'other', text }           - functions and anything else, as text
+ *   { kind: 'array', shape, items }   - items in ravel order; shape [] is an enclosure
+ *
+ * renderArrayHtml() returns null when boxes would add nothing over the text
+ * output (plain scalars and strings) or when the array is too large, and the
+ * text output is shown instead.
+ *
+ * Nested arrays are collapsible <details> cells headed by a shape badge; numbers
+ * are right-aligned and characters centred. Styles are the array-* classes in
+ * theme.css.
+ */
+
+// Above this many cells in total, the text output is shown instead
+export const MAX_CELLS = 2000;
+
+function escapeHtml(text) {
+    return text.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;');
+}
+
+function countCells(value) {
+    if (value.kind !== 'array') return 1;
+    return value.items.reduce((total, item) => total + countCells(item), 0) || 1;
+}
+
+function isString(value) {
+    return value.kind === 'array' && value.shape.length === 1 && value.items.length > 0
+        && value.items.every(item => item.kind === 'char');
+}
+
+// Shape badge text: 2×3 for a matrix, ⊂ for an enclosure
+function shapeLabel(value) {
+    return value.shape.length === 0 ? '⊂' : value.shape.join('×');
+}
+
+// Attribute locating an element for the inspector (src/inspector.js): its
+// index path, one multi-index per level of nesting
+function pathAttribute(path, inspect) {
+    return inspect ? ` data-path="${JSON.stringify(path)}"` : '';
+}
+
+// Multi-index of the nth item (ravel order) of an array of this shape
+export function indexOf(shape, n) {
+    const index = new Array(shape.length);
+    for (let axis = shape.length - 1; axis >= 0; axis--) {
+        index[axis] = n % shape[axis];
+        n = Math.floor(n / shape[axis]);
+    }
+    return index;
+}
+
+// An item's class and HTML, for a table cell or an enclosure
+function renderItem(item, path, inspect) {
+    if (isString(item)) {
+        return ['array-cell-string', escapeHtml(item.items.map(c => c.text).join(''))];
+    }
+    if (item.kind === 'array') return ['array-cell-nested', renderArray(item, true, path, inspect)];
+    return [`array-cell-${item.kind}`, escapeHtml(item.text)];
+}
+
+function renderCell(item, path, inspect) {
+    const [className, html] = renderItem(item, path, inspect);
+    return `<td class="${className}"${pathAttribute(path, inspect)}>${html}</td>`;
+}
+
+// Cells of a vector or matrix; start is the ravel index of the first
+function renderTable(value, start, rows, columns, path, inspect) {
+    let html = '<table class="array-table">';
+    for (let r = 0; r < rows; r++) {
+        html += '<tr>';
+        for (let c = 0; c < columns; c++) {
+            const n = start + r * columns + c;
+            html += renderCell(value.items[n], [...path, indexOf(value.shape, n)], inspect);
+        }
+        html += '</tr>';
+    }
+    return html + '</table>';
+}
+
+// The cells of an array: an enclosure's single item, a row for a vector, a
+// table for a matrix, and a stack of matrices for higher ranks
+function renderContents(value, path, inspect) {
+    const { shape, items } = value;
+    if (shape.length === 0) {
+        const [className, html] = renderItem(items[0], [...path, []], inspect);
+        return `<div class="array-enclosed ${className}"${pathAttribute([...path, []], inspect)}>${html}</div>`;
+    }
+    if (items.length === 0) return '<div class="array-empty"></div>';
+    if (shape.length === 1) return renderTable(value, 0, 1, shape[0], path, inspect);
+
+    const columns = shape[shape.length - 1];
+    const rows = shape[shape.length - 2];
+    const size = rows * columns;
+    if (shape.length === 2) return renderTable(value, 0, rows, columns, path, inspect);
+    let html = '<div class="array-slices">';
+    for (let start = 0; start < items.length; start += size) {
+        html += renderTable(value, start, rows, columns, path, inspect);
+    }
+    return html + '</div>';
+}
+
+function renderArray(value, collapsible, path = [], inspect = false) {
+    const badge = `<span class="array-shape">${shapeLabel(value)}</span>`;
+    if (collapsible) {
+        return `<details class="array-box" open><summary>${badge}</summary>${renderContents(value, path, inspect)}</details>`;
+    }
+    return `<div class="array-box"${pathAttribute(path, inspect)}>${badge}${renderContents(value, path, inspect)}</div>`;
+}
+
+/**
+ * Render a value as HTML boxes
+ * @param {object} value - Value in the model above
+ * @param {object} [options]
+ * @param {boolean} [options.inspect] - Draw scalars and strings too, and give
+ *   every cell a data-path for src/inspector.js
+ * @returns {string|null} - HTML, or null to show the text output instead
+ */
+export function renderArrayHtml(value, options = {}) {
+    const inspect = !!options.inspect;
+    if (!value || countCells(value) > MAX_CELLS) return null;
+    if (value.kind !== 'array') {
+        if (!inspect) return null;
+        return `<div class="array-display"><span class="array-scalar array-cell-${value.kind}"${pathAttribute([], true)}>${escapeHtml(value.text)}</span></div>`;
+    }
+    if (isString(value) && !inspect) return null;
+    return `<div class="array-display">${renderArray(value, false, [], inspect)}</div>`;
+}
+
+/**
+ * HTML for a runtime result: its own outputHtml, else boxes for its value
+ * @param {object} result - { success, output, value?, outputHtml? }
+ * @returns {string|null}
+ */
+export function resultToHtml(result) {
+    if (!result || !result.success) return null;
+    return result.outputHtml || renderArrayHtml(result.value) || null;
+}
+
+export default {
+    MAX_CELLS,
+    indexOf,
+    renderArrayHtml,
+    resultToHtml
+};
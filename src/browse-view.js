@@ -0,0 +1,175 @@
+/**
+ * Browse view: search the permalinks stored on the permalink server
+ * - Every term must appear in the code (case-insensitive); a single glyph such
+ *   as ⍤ is a term, and "quoted phrases" keep their spaces
+ * - Filter by language; results are newest first, with the line that matched
+ * - Enter opens the first result, clicking opens any of them
+ *
+ * The search itself is GET /p?q=&lang= (see servers/permalink-search.cjs).
+ */
+
+// Results fetched per page
+const PAGE_SIZE = 20;
+
+/**
+ * Create the browse view manager
+ * @param {object} elements - { screen, toolbar, input, lang, body, status } DOM elements
+ * @param {object} options
+ * @param {object} options.languages - Language configs by id ({ name, logo, fontClass })
+ * @param {Array<string>} options.languageOrder - Languages offered in the filter, in order
+ * @param {Function} options.search - async ({ q, lang, offset, limit }) => { total, results } or { error }
+ * @param {Function} options.onOpen - (code) => void, open a permalink
+ * @param {Function} options.onClose - called when the view closes
+ * @returns {object} - Manager API
+ */
+export function createBrowseView(elements, options) {
+    const { screen, toolbar, input, lang: langSelect, body, status } = elements;
+    let results = [];
+    let total = 0;
+    let searchId = 0;  // Latest search, so slow responses to older ones are dropped
+    let debounce = null;
+
+    const all = document.createElement('option');
+    all.value = '';
+    all.textContent = 'all languages';
+    langSelect.appendChild(all);
+    for (const lang of options.languageOrder) {
+        const option = document.createElement('option');
+        option.value = lang;
+        option.textContent = options.languages[lang].name;
+        langSelect.appendChild(option);
+    }
+
+    function isOpen() {
+        return screen.classList.contains('show');
+    }
+
+    /**
+     * Open the view
+     * @param {string} [lang] - Language to filter by (default: keep the last filter)
+     */
+    function open(lang) {
+        if (lang !== undefined) langSelect.value = lang;
+        screen.classList.add('show');
+        input.focus();
+        input.select();
+        search();
+    }
+
+    function close() {
+        screen.classList.remove('show');
+        if (options.onClose) options.onClose();
+    }
+
+    function make(tag, className, text) {
+        const element = document.createElement(tag);
+        if (className) element.className = className;
+        if (text !== undefined) element.textContent = text;
+        return element;
+    }
+
+    // "3 days ago", or the date for anything older than a month
+    function age(createdAt) {
+        if (!createdAt) return '';
+        const seconds = (Date.now() - Date.parse(createdAt)) / 1000;
+        if (seconds > 60 * 60 * 24 * 30) return createdAt.slice(0, 10);
+        for (const [size, unit] of [[60 * 60 * 24, 'day'], [60 * 60, 'hour'], [60, 'minute']]) {
+            if (seconds < size) continue;
+            const count = Math.floor(seconds / size);
+            return `${count} ${unit}${count === 1 ? '' : 's'} ago`;
+        }
+        return 'just now';
+    }
+
+    /**
+     * Run the search in the toolbar
+     * @param {boolean} [more=false] - Fetch the next page instead of starting over
+     */
+    async function search(more = false) {
+        const id = ++searchId;
+        const offset = more ? results.length : 0;
+        status.textContent = 'searching…';
+        const response = await options.search({
+            q: input.value.trim(),
+            lang: langSelect.value,
+            offset,
+            limit: PAGE_SIZE
+        });
+        if (id !== searchId) return;
+        if (response.error) {
+            status.textContent = response.error;
+            return;
+        }
+        results = more ? results.concat(response.results) : response.results;
+        total = response.total;
+        status.textContent = `${total} permalink${total === 1 ? '' : 's'}`;
+        render();
+    }
+
+    function render() {
+        body.innerHTML = '';
+        for (const result of results) {
+            const row = make('div', 'notebook-cell browse-result');
+            const bar = make('div', 'notebook-cell-bar');
+            for (const lang of result.langs) {
+                const config = options.languages[lang];
+                if (!config) continue;
+                const logo = make('img', 'multi-pane-logo');
+                logo.src = config.logo;
+                logo.alt = config.name;
+                logo.title = config.name;
+                bar.appendChild(logo);
+            }
+            bar.appendChild(make('span', 'multi-pane-name', `#${result.code}`));
+            if (result.version > 1) bar.appendChild(make('span', 'notebook-cell-type', `v${result.version}`));
+            if (result.kind !== 'code') bar.appendChild(make('span', 'notebook-cell-type', result.kind));
+            bar.appendChild(make('span', 'notebook-spacer'));
+            bar.appendChild(make('span', 'notebook-cell-type', age(result.createdAt)));
+            row.appendChild(bar);
+
+            const config = options.languages[result.langs[0]];
+            row.appendChild(make('div', `browse-preview ${config ? config.fontClass : ''}`, result.preview));
+            row.addEventListener('click', () => openResult(result));
+            body.appendChild(row);
+        }
+
+        if (results.length < total) {
+            const more = make('button', 'notebook-button', `more (${total - results.length} left)`);
+            more.addEventListener('click', () => search(true));
+            body.appendChild(more);
+        }
+    }
+
+    function openResult(result) {
+        close();
+        options.onOpen(result.code);
+    }
+
+    input.addEventListener('input', () => {
+        clearTimeout(debounce);
+        debounce = setTimeout(() => search(), 250);
+    });
+    input.addEventListener('keydown', (e) => {
+        if (e.key === 'Enter' && results.length) {
+            e.preventDefault();
+            openResult(results[0]);
+        }
+    });
+    langSelect.addEventListener('change', () => search());
+
+    toolbar.addEventListener('click', (e) => {
+        const action = e.target.closest('[data-action]')?.dataset.action;
+        if (action === 'close') close();
+    });
+
+    return {
+        open,
+        close,
+        isOpen,
+        search
+    };
+}
+
+export default {
+    createBrowseView
+};
@@ -0,0 +1,145 @@
+/**
+ * Collection view: step through the snippets of a shared collection
+ * - A collection is a title and an ordered list of snippets, each
+ *   { title?, lang, code, result? }, in any mix of languages
+ * - One snippet is shown at a time; ←/→ step, and a strip of every snippet
+ *   jumps straight to one
+ * - Enter (or "open") loads the current snippet into the main box
+ *
+ * Collections are created with POST /p { collection } (the multi-language
+ * view's "share" button makes one from its boxes).
+ */
+
+/**
+ * Create the collection view manager
+ * @param {object} elements - { screen, toolbar, body, title } DOM elements
+ * @param {object} options
+ * @param {object} options.languages - Language configs by id ({ name, logo, fontClass })
+ * @param {Function} options.highlightCode - (code, lang) => HTML
+ * @param {Function} options.onOpenSnippet - (snippet) => void, load a snippet into the main box
+ * @param {Function} options.onClose - called when the view closes
+ * @returns {object} - Manager API
+ */
+export function createCollectionView(elements, options) {
+    const { screen, toolbar, body, title } = elements;
+    let collection = null;
+    let index = 0;
+
+    function isOpen() {
+        return screen.classList.contains('show');
+    }
+
+    function close() {
+        screen.classList.remove('show');
+        if (options.onClose) options.onClose();
+    }
+
+    function make(tag, className, text) {
+        const element = document.createElement(tag);
+        if (className) element.className = className;
+        if (text !== undefined) element.textContent = text;
+        return element;
+    }
+
+    function languageName(lang) {
+        return options.languages[lang] ? options.languages[lang].name : lang;
+    }
+
+    /**
+     * Open a collection
+     * @param {object} newCollection - { title?, snippets: [{ title?, lang, code, result? }] }
+     * @param {number} [start=0] - Snippet to show first
+     */
+    function open(newCollection, start = 0) {
+        collection = newCollection;
+        index = Math.min(Math.max(0, start), collection.snippets.length - 1);
+        title.textContent = collection.title || 'collection';
+        screen.classList.add('show');
+        render();
+    }
+
+    function show(newIndex) {
+        if (!collection) return;
+        index = (newIndex + collection.snippets.length) % collection.snippets.length;
+        render();
+    }
+
+    function render() {
+        body.innerHTML = '';
+        const snippet = collection.snippets[index];
+        const config = options.languages[snippet.lang];
+
+        const cell = make('div', 'notebook-cell code collection-snippet');
+        const bar = make('div', 'notebook-cell-bar');
+        if (config) {
+            const logo = make('img', 'multi-pane-logo');
+            logo.src = config.logo;
+            logo.alt = config.name;
+            bar.appendChild(logo);
+        }
+        bar.appendChild(make('span', 'multi-pane-name', snippet.title || languageName(snippet.lang)));
+        if (snippet.title) bar.appendChild(make('span', 'notebook-cell-type', languageName(snippet.lang)));
+        bar.appendChild(make('span', 'notebook-spacer'));
+        bar.appendChild(make('span', 'notebook-cell-type', `${index + 1} / ${collection.snippets.length}`));
+        cell.appendChild(bar);
+
+        const code = make('div', `notebook-code-view ${config ? config.fontClass : ''}`);
+        code.innerHTML = options.highlightCode(snippet.code, snippet.lang);
+        cell.appendChild(code);
+
+        if (snippet.result) {
+            cell.appendChild(make('pre', `notebook-output ${config ? config.fontClass : ''}`, snippet.result));
+        }
+        body.appendChild(cell);
+
+        // Every snippet, for jumping around
+        const strip = make('div', 'collection-strip');
+        collection.snippets.forEach((entry, i) => {
+            const chip = make('button', 'notebook-button' + (i === index ? ' active' : ''),
+                entry.title || languageName(entry.lang));
+            chip.title = entry.code.split('\n')[0];
+            chip.addEventListener('click', () => show(i));
+            strip.appendChild(chip);
+        });
+        body.appendChild(strip);
+    }
+
+    function openSnippet() {
+        if (!collection) return;
+        const snippet = collection.snippets[index];
+        close();
+        options.onOpenSnippet(snippet);
+    }
+
+    /**
+     * Handle a key while the view is open
+     * @returns {boolean} - Whether the key was used
+     */
+    function handleKey(e) {
+        if (e.key === 'ArrowRight') show(index + 1);
+        else if (e.key === 'ArrowLeft') show(index - 1);
+        else if (e.key === 'Enter') openSnippet();
+        else return false;
+        return true;
+    }
+
+    toolbar.addEventListener('click', (e) => {
+        const action = e.target.closest('[data-action]')?.dataset.action;
+        if (action === 'prev') show(index - 1);
+        else if (action === 'next') show(index + 1);
+        else if (action === 'open') openSnippet();
+        else if (action === 'close') close();
+    });
+
+    return {
+        open,
+        close,
+        isOpen,
+        handleKey,
+        getIndex: () => index
+    };
+}
+
+export default {
+    createCollectionView
+};
@@ -0,0 +1,294 @@
+/**
+ * File tree: the project files of src/vfs.js next to the box
+ * - Files listed by directory; clicking one opens it in the panel's editor
+ * - new (a path like util/strings.bqn makes its directories), upload or drop
+ *   files from disk, rename and delete
+ * - Edits are saved to IndexedDB as they are typed
+ *
+ * The box's code is the program; it imports the files with its language's own
+ * mechanism (BQN •Import, J load, Kap use, APL ⎕FIX 'file://name').
+ */
+
+import { normalizePath, getFiles, writeFile, deleteFile, renameFile, replaceFiles } from './vfs.js';
+
+/**
+ * Language of a file by extension (null for data files)
+ */
+const EXTENSIONS = {
+    '.bqn': 'bqn',
+    '.ua': 'uiua',
+    '.ijs': 'j',
+    '.kap': 'kap',
+    '.tinyapl': 'tinyapl',
+    '.apl': 'apl',
+    '.aplf': 'apl',
+    '.apln': 'apl',
+    '.aplc': 'apl',
+    '.dyalog': 'apl'
+};
+
+/**
+ * Extension of new files by language
+ */
+const NEW_FILE_EXTENSIONS = {
+    bqn: '.bqn',
+    uiua: '.ua',
+    j: '.ijs',
+    kap: '.kap',
+    tinyapl: '.tinyapl',
+    apl: '.aplf'
+};
+
+export function languageOfPath(path) {
+    const dot = path.lastIndexOf('.');
+    return dot > path.lastIndexOf('/') ? EXTENSIONS[path.slice(dot).toLowerCase()] || null : null;
+}
+
+// Sort key putting each directory's subdirectories before its files
+function treeOrder(path) {
+    const parts = path.split('/');
+    return parts.map((part, i) => (i < parts.length - 1 ? '0' : '1') + part).join('/');
+}
+
+function sortedPaths(files) {
+    return Object.keys(files).sort((a, b) => treeOrder(a).localeCompare(treeOrder(b)));
+}
+
+/**
+ * Create the file tree manager
+ * @param {object} elements - { panel, list, path, editor, fileInput } DOM elements;
+ *   the panel's [data-action] buttons are new, upload, rename and delete
+ * @param {object} options
+ * @param {object} options.languages - Language configs by id ({ fontClass })
+ * @param {Function} options.getLanguage - () => the box's language (for new files)
+ * @param {Function} options.createKeyboardHandler - (element, lang) => cleanup, or null for no keymap
+ * @param {Function} options.onMessage - (text) => void, brief feedback
+ * @param {Function} options.onEscape - called on Escape in the editor
+ * @returns {object} - Manager API
+ */
+export function createFileTree(elements, options) {
+    const { panel, list, path: pathLabel, editor, fileInput } = elements;
+    let files = {};
+    let current = null;
+    let keyboardCleanup = null;
+    let saveTimer = null;
+
+    function isOpen() {
+        return panel.classList.contains('show');
+    }
+
+    function open() {
+        panel.classList.add('show');
+        render();
+    }
+
+    function close() {
+        flushSave();
+        panel.classList.remove('show');
+    }
+
+    function toggle() {
+        if (isOpen()) close();
+        else open();
+        return isOpen();
+    }
+
+    // All files as { path: text }
+    function getProjectFiles() {
+        return { ...files };
+    }
+
+    async function load() {
+        try {
+            files = await getFiles();
+        } catch (e) {
+            console.warn('Project files unavailable:', e.message);
+        }
+        select(sortedPaths(files)[0] || null);
+    }
+
+    // Replace the whole project (a project permalink) and show it
+    async function setFiles(newFiles) {
+        flushSave();
+        files = { ...newFiles };
+        try {
+            await replaceFiles(files);
+        } catch (e) {
+            console.warn('Could not save project files:', e.message);
+        }
+        select(sortedPaths(files)[0] || null);
+        open();
+    }
+
+    // Write files, keeping the tree usable when IndexedDB is not
+    function persist(operation) {
+        return operation.catch(e => console.warn('Could not save project files:', e.message));
+    }
+
+    function flushSave() {
+        if (!saveTimer) return;
+        clearTimeout(saveTimer);
+        saveTimer = null;
+        if (current !== null) persist(writeFile(current, files[current]));
+    }
+
+    function select(path) {
+        flushSave();
+        current = path;
+        if (keyboardCleanup) keyboardCleanup();
+        keyboardCleanup = null;
+
+        const lang = path === null ? null : languageOfPath(path);
+        editor.className = `file-tree-editor ${lang ? options.languages[lang].fontClass : ''}`;
+        editor.value = path === null ? '' : files[path];
+        editor.disabled = path === null;
+        pathLabel.textContent = path || 'no file open';
+        if (lang && options.createKeyboardHandler) keyboardCleanup = options.createKeyboardHandler(editor, lang);
+        if (isOpen()) render();
+    }
+
+    function render() {
+        list.innerHTML = '';
+        const paths = sortedPaths(files);
+        if (paths.length === 0) {
+            const empty = document.createElement('div');
+            empty.className = 'file-tree-empty';
+            empty.textContent = 'No files yet';
+            list.appendChild(empty);
+            return;
+        }
+
+        let shownDirs = [];
+        for (const path of paths) {
+            const parts = path.split('/');
+            const dirs = parts.slice(0, -1);
+            // A row for each directory not already shown above this file
+            let shared = 0;
+            while (shared < dirs.length && dirs[shared] === shownDirs[shared]) shared++;
+            for (let depth = shared; depth < dirs.length; depth++) {
+                list.appendChild(createRow(`${dirs[depth]}/`, depth, 'file-tree-dir'));
+            }
+            shownDirs = dirs;
+
+            const row = createRow(parts[parts.length - 1], dirs.length, 'file-tree-file');
+            row.title = path;
+            if (path === current) row.classList.add('active');
+            row.addEventListener('click', () => {
+                select(path);
+                editor.focus();
+            });
+            list.appendChild(row);
+        }
+    }
+
+    function createRow(text, depth, className) {
+        const row = document.createElement('div');
+        row.className = `file-tree-row ${className}`;
+        row.style.paddingLeft = `${depth * 14 + 6}px`;
+        row.textContent = text;
+        return row;
+    }
+
+    function askPath(message, initial) {
+        const answer = prompt(message, initial);
+        if (answer === null) return null;
+        const path = normalizePath(answer);
+        if (!path) options.onMessage(`Invalid file path: ${answer}`);
+        return path;
+    }
+
+    function newFile() {
+        const extension = NEW_FILE_EXTENSIONS[options.getLanguage()] || '.txt';
+        const path = askPath('New file (a path like lib.bqn or util/strings.ijs)', `lib${extension}`);
+        if (!path) return;
+        if (!Object.hasOwn(files, path)) {
+            files[path] = '';
+            persist(writeFile(path, ''));
+        }
+        select(path);
+        editor.focus();
+    }
+
+    async function addFromDisk(fileList) {
+        const added = [];
+        for (const file of fileList) {
+            const path = normalizePath(file.name);
+            if (!path) continue;
+            files[path] = await file.text();
+            await persist(writeFile(path, files[path]));
+            added.push(path);
+        }
+        if (added.length === 0) return;
+        select(added[0]);
+        options.onMessage(added.length === 1 ? `Added ${added[0]}` : `Added ${added.length} files`);
+    }
+
+    function renameCurrent() {
+        if (current === null) return;
+        const target = askPath(`Rename ${current} to`, current);
+        if (!target || target === current) return;
+        if (Object.hasOwn(files, target) && !confirm(`Replace ${target}?`)) return;
+        flushSave();
+        files[target] = files[current];
+        delete files[current];
+        persist(renameFile(current, target));
+        select(target);
+    }
+
+    function deleteCurrent() {
+        if (current === null || !confirm(`Delete ${current}?`)) return;
+        if (saveTimer) clearTimeout(saveTimer);
+        saveTimer = null;
+        delete files[current];
+        persist(deleteFile(current));
+        select(null);
+    }
+
+    editor.addEventListener('input', () => {
+        if (current === null) return;
+        files[current] = editor.value;
+        if (saveTimer) clearTimeout(saveTimer);
+        saveTimer = setTimeout(flushSave, 300);
+    });
+    editor.addEventListener('keydown', (e) => {
+        if (e.key === 'Escape' && options.onEscape) options.onEscape();
+    });
+
+    panel.addEventListener('click', (e) => {
+        const button = e.target.closest('[data-action]');
+        if (!button) return;
+        switch (button.dataset.action) {
+            case 'new': newFile(); break;
+            case 'upload': fileInput.click(); break;
+            case 'rename': renameCurrent(); break;
+            case 'delete': deleteCurrent(); break;
+        }
+    });
+    fileInput.addEventListener('change', () => {
+        addFromDisk(Array.from(fileInput.files));
+        fileInput.value = '';
+    });
+    list.addEventListener('dragover', (e) => {
+        if (e.dataTransfer.types.includes('Files')) e.preventDefault();
+    });
+    list.addEventListener('drop', (e) => {
+        if (!e.dataTransfer.files.length) return;
+        e.preventDefault();
+        addFromDisk(Array.from(e.dataTransfer.files));
+    });
+
+    return {
+        open,
+        close,
+        toggle,
+        isOpen,
+        load,
+        getFiles: getProjectFiles,
+        setFiles
+    };
+}
+
+export default {
+    createFileTree,
+    languageOfPath
+};
@@ -0,0 +1,81 @@
+/**
+ * Fuzzy matching for search boxes (Ctrl+Space primitives, idiom search)
+ */
+
+/**
+ * Match a query against a piece of text
+ * Substring matches score highest (more at the start of the text); otherwise
+ * every query character must appear in order, with bonuses for consecutive
+ * characters and for matches at word starts.
+ * @param {string} text - Text to search (e.g. a primitive name)
+ * @param {string} query - What the user typed
+ * @param {Function} [escape] - Escapes text pieces for HTML (for text that may contain markup characters)
+ * @returns {{score: number, highlighted: string}|null} - Score and the text with
+ *   matched characters wrapped in <em>, or null when the query doesn't match
+ */
+export function fuzzyMatch(text, query, escape = (piece) => piece) {
+    if (!query) return { score: 1, highlighted: escape(text) };
+    
+    const textLower = text.toLowerCase();
+    const queryLower = query.toLowerCase();
+    
+    // Check for exact substring match first (highest priority)
+    const substringIndex = textLower.indexOf(queryLower);
+    if (substringIndex !== -1) {
+        const before = text.slice(0, substringIndex);
+        const match = text.slice(substringIndex, substringIndex + query.length);
+        const after = text.slice(substringIndex + query.length);
+        return {
+            score: 100 + (substringIndex === 0 ? 50 : 0) + (query.length / text.length * 20),
+            highlighted: `${escape(before)}<em>${escape(match)}</em>${escape(after)}`
+        };
+    }
+    
+    // Fuzzy matching - find all query chars in order
+    let textIdx = 0;
+    let queryIdx = 0;
+    let score = 0;
+    let highlighted = '';
+    let lastMatchIdx = -1;
+    let consecutiveMatches = 0;
+    
+    while (textIdx < text.length && queryIdx < queryLower.length) {
+        if (textLower[textIdx] === queryLower[queryIdx]) {
+            highlighted += `<em>${escape(text[textIdx])}</em>`;
+            score += 10;
+            
+            // Bonus for consecutive matches
+            if (lastMatchIdx === textIdx - 1) {
+                consecutiveMatches++;
+                score += consecutiveMatches * 5;
+            } else {
+                consecutiveMatches = 0;
+            }
+            
+            // Bonus for matching at word boundaries
+            if (textIdx === 0 || text[textIdx - 1] === ' ' || text[textIdx - 1] === '/') {
+                score += 15;
+            }
+            
+            lastMatchIdx = textIdx;
+            queryIdx++;
+        } else {
+            highlighted += escape(text[textIdx]);
+        }
+        textIdx++;
+    }
+    
+    // Add remaining text
+    highlighted += escape(text.slice(textIdx));
+    
+    // Return null if not all query chars were found
+    if (queryIdx < queryLower.length) {
+        return null;
+    }
+    
+    return { score, highlighted };
+}
+
+export default {
+    fuzzyMatch
+};
@@ -0,0 +1,216 @@
+/**
+ * LeetGolf: problems, judging and scoring
+ * - Problem format: a statement plus test cases for each language
+ * - Judge: runs a submission through an evaluator against every case
+ * - Scoring: characters and bytes, with APL and BQN counted in their
+ *   single-byte code pages
+ *
+ * Problem: {
+ *   id, title, statement (markdown),
+ *   compare: 'exact' (default, trailing whitespace ignored) | 'values' (as the
+ *            multi-language view compares: numbers, words and characters only),
+ *   tests: { <lang>: [{ input?, expected, hidden? }] }
+ * }
+ *
+ * Hidden cases are judged like the others, but publicProblem() and
+ * publicVerdict() leave out everything about them except whether they passed,
+ * so a problem shared by permalink can be solved without seeing them.
+ *
+ * A submission is a function. Each case applies it to the case's input,
+ * written in that language: `input: "1‿2‿3"` runs `(code) 1‿2‿3`, and
+ * `input: ["2", "1‿2‿3"]` runs `2 (code) 1‿2‿3` (Uiua: `(code) 2 1‿2‿3`,
+ * since its first argument is the top of the stack). A case without an input
+ * runs the submission as a whole program.
+ *
+ * Works in the browser and under Node (bin/arraybox golf, servers/eval-server.cjs).
+ */
+
+import { aplGlyphDocs } from './apl-docs.js';
+import { bqnGlyphDocs } from './bqn-docs.js';
+import { normalizeOutput } from './multi-lang.js';
+
+export const golfLanguages = ['apl', 'bqn', 'uiua', 'j', 'kap', 'tinyapl'];
+
+// Glyphs in each language's single-byte code page (ASCII is always one byte)
+const SBCS = {
+    apl: new Set([...Object.keys(aplGlyphDocs), ...'⍫⊇⍢']),
+    bqn: new Set([...Object.keys(bqnGlyphDocs), ...'𝕗𝕘𝕤𝕎𝕏'])
+};
+
+/**
+ * Count a submission's length
+ * @param {string} code - Submission (trailing whitespace is not counted)
+ * @param {string} lang - Language id
+ * @returns {{chars: number, bytes: number}} - Code points, and bytes in the
+ *   language's SBCS (APL, BQN) or UTF-8 (the rest, or any code using
+ *   characters outside the SBCS)
+ */
+export function countCode(code, lang) {
+    const chars = [...code.replace(/\s+$/, '')];
+    const sbcs = SBCS[lang];
+    const utf8 = new TextEncoder().encode(chars.join('')).length;
+    if (sbcs && chars.every(ch => ch.charCodeAt(0) < 128 || sbcs.has(ch))) {
+        return { chars: chars.length, bytes: chars.length };
+    }
+    return { chars: chars.length, bytes: utf8 };
+}
+
+/**
+ * Validate a problem and keep only known fields
+ * @param {object} problem - Problem to check
+ * @param {object} [options]
+ * @param {boolean} [options.requireId=true] - Problems shared by permalink have no id of their own
+ * @returns {{problem: object}|{error: string}}
+ */
+export function normalizeProblem(problem, options = {}) {
+    const { requireId = true } = options;
+    if (!problem || typeof problem !== 'object') return { error: 'Problem must be an object' };
+    if ((requireId || problem.id !== undefined) &&
+        (typeof problem.id !== 'string' || !/^[\w-]{1,64}$/.test(problem.id))) {
+        return { error: 'Problem id must be 1-64 letters, digits, - or _' };
+    }
+    if (typeof problem.title !== 'string' || !problem.title.trim()) return { error: 'Problem needs a title' };
+    if (problem.compare !== undefined && !['exact', 'values'].includes(problem.compare)) {
+        return { error: `Unknown compare mode: ${problem.compare}` };
+    }
+    if (!problem.tests || typeof problem.tests !== 'object') return { error: 'Problem needs tests' };
+
+    const tests = {};
+    for (const [lang, cases] of Object.entries(problem.tests)) {
+        if (!golfLanguages.includes(lang)) return { error: `Unknown language: ${lang}` };
+        if (!Array.isArray(cases) || cases.length === 0) return { error: `No test cases for ${lang}` };
+        tests[lang] = [];
+        for (const testCase of cases) {
+            const input = testCase && testCase.input;
+            const validInput = input === undefined || typeof input === 'string' ||
+                (Array.isArray(input) && input.length === 2 && input.every(arg => typeof arg === 'string'));
+            if (!validInput || typeof testCase.expected !== 'string') {
+                return { error: `Invalid test case for ${lang}` };
+            }
+            const clean = input === undefined ? { expected: testCase.expected } : { input, expected: testCase.expected };
+            if (testCase.hidden === true) clean.hidden = true;
+            tests[lang].push(clean);
+        }
+    }
+    if (Object.keys(tests).length === 0) return { error: 'Problem needs tests for at least one language' };
+
+    return {
+        problem: {
+            ...(problem.id !== undefined ? { id: problem.id } : {}),
+            title: problem.title.trim(),
+            statement: typeof problem.statement === 'string' ? problem.statement : '',
+            compare: problem.compare || 'exact',
+            tests
+        }
+    };
+}
+
+/**
+ * The program a test case runs
+ * @param {string} code - Submission
+ * @param {string} lang - Language id
+ * @param {object} testCase - { input?, expected }
+ */
+export function buildProgram(code, lang, testCase) {
+    const fn = code.replace(/\s+$/, '');
+    if (testCase.input === undefined) return fn;
+    if (!Array.isArray(testCase.input)) return `(${fn}) ${testCase.input}`;
+    const [left, right] = testCase.input;
+    return lang === 'uiua' ? `(${fn}) ${left} ${right}` : `${left} (${fn}) ${right}`;
+}
+
+/**
+ * Whether an output matches what a case expects
+ * @param {string} output - Interpreter output
+ * @param {string} expected - Expected output
+ * @param {string} [mode='exact'] - 'exact' or 'values'
+ */
+export function outputsMatch(output, expected, mode = 'exact') {
+    if (mode === 'values') {
+        return normalizeOutput(output).join(' ') === normalizeOutput(expected).join(' ');
+    }
+    const clean = (text) => (text || '').split('\n').map(line => line.replace(/\s+$/, '')).join('\n').trim();
+    return clean(output) === clean(expected);
+}
+
+/**
+ * Run a submission against every test case for its language
+ * @param {object} problem - Normalized problem
+ * @param {string} lang - Language of the submission
+ * @param {string} code - Submission
+ * @param {Function} evaluate - async (lang, code) => { success, output }
+ * @returns {Promise<{passed: boolean, chars: number, bytes: number,
+ *   cases: Array<{input, expected, output, success, passed}>, error?: string}>}
+ */
+export async function judge(problem, lang, code, evaluate) {
+    const score = countCode(code, lang);
+    const cases = problem.tests[lang];
+    if (!cases) {
+        return { passed: false, ...score, cases: [], error: `${problem.title} has no tests for ${lang}` };
+    }
+    if (!code.trim()) {
+        return { passed: false, ...score, cases: [], error: 'Empty submission' };
+    }
+
+    const results = [];
+    for (const testCase of cases) {
+        let result;
+        try {
+            result = await evaluate(lang, buildProgram(code, lang, testCase));
+        } catch (error) {
+            result = { success: false, output: error.message || String(error) };
+        }
+        const output = result.output || '';
+        results.push({
+            input: testCase.input,
+            expected: testCase.expected,
+            ...(testCase.hidden ? { hidden: true } : {}),
+            output,
+            success: result.success,
+            passed: result.success && outputsMatch(output, testCase.expected, problem.compare)
+        });
+    }
+    return { passed: results.every(result => result.passed), ...score, cases: results };
+}
+
+/**
+ * A problem as solvers see it: hidden cases are reduced to { hidden: true }
+ */
+export function publicProblem(problem) {
+    const tests = {};
+    for (const [lang, cases] of Object.entries(problem.tests)) {
+        tests[lang] = cases.map(testCase => testCase.hidden ? { hidden: true } : { ...testCase });
+    }
+    return { ...problem, tests };
+}
+
+/**
+ * A judge verdict as solvers see it: hidden cases only say whether they passed
+ */
+export function publicVerdict(verdict) {
+    return {
+        ...verdict,
+        cases: verdict.cases.map(testCase => testCase.hidden ? { hidden: true, passed: testCase.passed } : testCase)
+    };
+}
+
+/**
+ * Sort leaderboard entries: fewest bytes, then fewest characters, then earliest
+ * @param {Array<{bytes, chars, submittedAt}>} entries
+ */
+export function rankEntries(entries) {
+    return [...entries].sort((a, b) =>
+        a.bytes - b.bytes || a.chars - b.chars || String(a.submittedAt).localeCompare(String(b.submittedAt)));
+}
+
+export default {
+    golfLanguages,
+    countCode,
+    normalizeProblem,
+    buildProgram,
+    outputsMatch,
+    judge,
+    publicProblem,
+    publicVerdict,
+    rankEntries
+};
@@ -0,0 +1,31 @@
+/**
+ * Imported Idioms
+ *
+ * Auto-generated by scripts/import-idioms.cjs
+ * Sources: (none imported yet)
+ * Generated: (never)
+ *
+ * Run `node scripts/import-idioms.cjs` to update from upstream.
+ *
+ * ATTRIBUTION:
+ * APLcart (https://aplcart.info) is maintained by Adám Brudzewsky,
+ * repository https://github.com/abrudz/aplcart.
+ * BQNcrate (https://mlochbaum.github.io/bqncrate/) is maintained by
+ * Marshall Lochbaum, repository https://github.com/mlochbaum/bqncrate.
+ * Check each project's license before redistributing imported idioms.
+ */
+
+/**
+ * Metadata about the import
+ */
+export const idiomDataMeta = {
+    "sources": [],
+    "importedAt": null
+};
+
+/**
+ * Imported idioms: { description, code, language, tags, source }
+ */
+export const importedIdioms = [];
+
+export default importedIdioms;
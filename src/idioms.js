@@ -0,0 +1,208 @@
+/**
+ * Idiom library (APLcart / BQNcrate style)
+ * - A small curated set of idioms for every language, plus whatever
+ *   scripts/import-idioms.cjs imported from APLcart and BQNcrate (idiom-data.js)
+ * - Fuzzy search over descriptions, tags and code
+ * - Translation of an idiom into another language via primitive-translate.js
+ *
+ * Each idiom: { description, code, language, tags, source }
+ * Code is the idiom itself, usually a function to apply to an argument
+ * ("+´÷≠" rather than "(+´÷≠) 1‿2‿3").
+ */
+
+import { fuzzyMatch } from './fuzzy.js';
+import { escapeHtml } from './syntax.js';
+import { translatePrimitives, translateArrayLiterals } from './primitive-translate.js';
+import { importedIdioms } from './idiom-data.js';
+
+function curated(language, entries) {
+    return entries.map(([description, code, tags]) => ({ description, code, language, tags, source: 'arraybox' }));
+}
+
+/**
+ * Hand-picked idioms, checked against each interpreter
+ */
+export const curatedIdioms = [
+    ...curated('apl', [
+        ['Mean (average)', '+/÷≢', ['statistics', 'mean', 'average']],
+        ['Sum', '+/', ['reduce', 'total']],
+        ['Product', '×/', ['reduce']],
+        ['Maximum', '⌈/', ['reduce', 'largest']],
+        ['Minimum', '⌊/', ['reduce', 'smallest']],
+        ['Sort ascending', '{⍵[⍋⍵]}', ['sort', 'order']],
+        ['Sort descending', '{⍵[⍒⍵]}', ['sort', 'order']],
+        ['Remove duplicates (unique elements)', '∪', ['unique', 'nub', 'distinct']],
+        ['Is it a palindrome?', '⊢≡⌽', ['string', 'test', 'reverse']],
+        ['Indices of ones (where true)', '⍸', ['boolean', 'mask', 'indices']],
+        ['Running sum (cumulative sum)', '+\\', ['scan', 'prefix']],
+        ['Is it sorted?', '{⍵≡⍵[⍋⍵]}', ['sort', 'test']],
+        ['Multiplication table for 1..n', '∘.×⍨⍳', ['outer product', 'table']],
+        ['Last element', '⊃⌽', ['last', 'tail']],
+        ['Flatten (enlist)', '∊', ['ravel', 'nested']],
+        ['Split a string on spaces', "' '(≠⊆⊢)", ['string', 'words', 'partition']],
+        ['Digits of a number', '⍎¨⍕', ['number', 'digits']],
+        ['Remove vowels', "~∘'aeiou'", ['string', 'filter', 'without']],
+        ['Count occurrences of each unique element', '{⍺,≢⍵}⌸', ['frequency', 'histogram', 'key']]
+    ]),
+    ...curated('bqn', [
+        ['Mean (average)', '+´÷≠', ['statistics', 'mean', 'average']],
+        ['Sum', '+´', ['fold', 'total']],
+        ['Product', '×´', ['fold']],
+        ['Maximum', '⌈´', ['fold', 'largest']],
+        ['Minimum', '⌊´', ['fold', 'smallest']],
+        ['Sort ascending', '∧', ['sort', 'order']],
+        ['Sort descending', '∨', ['sort', 'order']],
+        ['Remove duplicates (unique elements)', '⍷', ['unique', 'deduplicate', 'distinct']],
+        ['Is it a palindrome?', '≡⟜⌽', ['string', 'test', 'reverse']],
+        ['Indices of ones (where true)', '/', ['boolean', 'mask', 'indices']],
+        ['Running sum (cumulative sum)', '+`', ['scan', 'prefix']],
+        ['Is it sorted?', '∧≡⊢', ['sort', 'test']],
+        ['Multiplication table for 1..n', '×⌜˜1+↕', ['table', 'outer product']],
+        ['Last element', '⊑⌽', ['last', 'tail']],
+        ['Join a list of lists', '∾', ['flatten', 'concatenate', 'nested']],
+        ['Range 1..n', '1+↕', ['range', 'iota']],
+        ['Split a string on a separator', '((⊢-˜+`×¬)∘=⊔⊢)', ['string', 'words', 'split', 'group']],
+        ['Digits of a number', "'0'-˜•Fmt", ['number', 'digits']],
+        ['Remove vowels', '(¬∊⟜"aeiou")⊸/', ['string', 'filter', 'without']],
+        ['Count occurrences of each unique element', '≠¨⊐⊸⊔', ['frequency', 'histogram', 'group']]
+    ]),
+    ...curated('uiua', [
+        ['Mean (average)', '÷⊃⧻/+', ['statistics', 'mean', 'average']],
+        ['Sum', '/+', ['reduce', 'total']],
+        ['Product', '/×', ['reduce']],
+        ['Maximum', '/↥', ['reduce', 'largest']],
+        ['Minimum', '/↧', ['reduce', 'smallest']],
+        ['Sort ascending', '⍆', ['sort', 'order']],
+        ['Sort descending', '⇌⍆', ['sort', 'order']],
+        ['Remove duplicates (unique elements)', '◴', ['unique', 'deduplicate', 'distinct']],
+        ['Is it a palindrome?', '≍⊸⇌', ['string', 'test', 'reverse']],
+        ['Indices of ones (where true)', '⊚', ['boolean', 'mask', 'indices']],
+        ['Running sum (cumulative sum)', '\\+', ['scan', 'prefix']],
+        ['Is it sorted?', '≍⊸⍆', ['sort', 'test']],
+        ['Multiplication table for 1..n', '⊞×.+1⇡', ['table', 'outer product']],
+        ['Last element', '⊣', ['last', 'tail']],
+        ['Range 1..n', '+1⇡', ['range', 'iota']],
+        ['Split a string on spaces', '⊜□⊸≠@ ', ['string', 'words', 'partition']],
+        ['Digits of a number', '-@0°⋕', ['number', 'digits']],
+        ['Remove vowels', '▽⊸(¬∈"aeiou")', ['string', 'filter', 'without']],
+        ['Counts of each index (inverse where)', '°⊚', ['frequency', 'histogram', 'count']]
+    ]),
+    ...curated('j', [
+        ['Mean (average)', '+/ % #', ['statistics', 'mean', 'average']],
+        ['Sum', '+/', ['insert', 'total']],
+        ['Product', '*/', ['insert']],
+        ['Maximum', '>./', ['insert', 'largest']],
+        ['Minimum', '<./', ['insert', 'smallest']],
+        ['Sort ascending', '/:~', ['sort', 'order']],
+        ['Sort descending', '\\:~', ['sort', 'order']],
+        ['Remove duplicates (unique elements)', '~.', ['unique', 'nub', 'distinct']],
+        ['Is it a palindrome?', '-: |.', ['string', 'test', 'reverse']],
+        ['Indices of ones (where true)', 'I.', ['boolean', 'mask', 'indices']],
+        ['Running sum (cumulative sum)', '+/\\', ['scan', 'prefix']],
+        ['Is it sorted?', '-: /:~', ['sort', 'test']],
+        ['Multiplication table for 1..n', '*/~ >: i.', ['table', 'outer product']],
+        ['Last element', '{:', ['last', 'tail']],
+        ['Range 1..n', '>: i.', ['range', 'integers']],
+        ['Split a string on spaces', "<;._1 ' ',", ['string', 'words', 'cut']],
+        ['Digits of a number', '"."0 ":', ['number', 'digits']],
+        ['Remove vowels', "-.&'aeiou'", ['string', 'filter', 'without']],
+        ['Count occurrences of each unique element', '#/.~', ['frequency', 'histogram', 'key']]
+    ]),
+    ...curated('kap', [
+        ['Mean (average)', '(+/«÷»≢)', ['statistics', 'mean', 'average']],
+        ['Sum', '+/', ['reduce', 'total']],
+        ['Product', '×/', ['reduce']],
+        ['Maximum', '⌈/', ['reduce', 'largest']],
+        ['Minimum', '⌊/', ['reduce', 'smallest']],
+        ['Sort ascending', '∧', ['sort', 'order']],
+        ['Sort descending', '∨', ['sort', 'order']],
+        ['Remove duplicates (unique elements)', '∪', ['unique', 'nub', 'distinct']],
+        ['Is it a palindrome?', '(⊢«≡»⌽)', ['string', 'test', 'reverse']],
+        ['Indices of ones (where true)', '⍸', ['boolean', 'mask', 'indices']],
+        ['Running sum (cumulative sum)', '+\\', ['scan', 'prefix']],
+        ['Is it sorted?', '(⊢«≡»∧)', ['sort', 'test']],
+        ['Multiplication table for 1..n', '×⌻⍨ 1+⍳', ['table', 'outer product']],
+        ['Last element', '↑⌽', ['last', 'tail']],
+        ['Split a string on spaces', '{⍵⊂⍨⍵≠@\\s}', ['string', 'words', 'partition']],
+        ['Remove vowels', '{(~⍵∊"aeiou")/⍵}', ['string', 'filter', 'without']]
+    ]),
+    ...curated('tinyapl', [
+        ['Mean (average)', '⦅+/÷≢⦆', ['statistics', 'mean', 'average']],
+        ['Sum', '+/', ['reduce', 'total']],
+        ['Product', '×/', ['reduce']],
+        ['Maximum', '⌈/', ['reduce', 'largest']],
+        ['Minimum', '⌊/', ['reduce', 'smallest']],
+        ['Sort ascending', '⊴', ['sort', 'order']],
+        ['Sort descending', '⊵', ['sort', 'order']],
+        ['Remove duplicates (unique elements)', '∪', ['unique', 'nub', 'distinct']],
+        ['Is it a palindrome?', '⦅⊢≡⌽⦆', ['string', 'test', 'reverse']],
+        ['Indices of ones (where true)', '⍸', ['boolean', 'mask', 'indices']],
+        ['Is it sorted?', '⦅⊢≡⊴⦆', ['sort', 'test']],
+        ['Multiplication table for 1..n', '×⊞⍨ 1+⍳', ['table', 'outer product']],
+        ['Last element', '⊃⌽', ['last', 'tail']]
+    ])
+];
+
+/**
+ * Every idiom: curated first, then imported ones
+ */
+export const idioms = [...curatedIdioms, ...importedIdioms];
+
+/**
+ * Search idioms by description, tags or code
+ * @param {string} query - What the user typed (a description fragment, a tag, or glyphs)
+ * @param {object} [options]
+ * @param {string} [options.language] - Rank this language's idioms first
+ * @param {boolean} [options.onlyLanguage=false] - Only return this language's idioms
+ * @param {number} [options.limit=50] - Maximum number of results
+ * @returns {Array<{idiom, score: number, highlighted: string}>} - Best matches
+ *   first; highlighted is the HTML-escaped description with matches in <em>
+ */
+export function searchIdioms(query, options = {}) {
+    const { language = null, onlyLanguage = false, limit = 50 } = options;
+    const queryLower = query.trim().toLowerCase();
+    const results = [];
+
+    for (const idiom of idioms) {
+        if (onlyLanguage && idiom.language !== language) continue;
+
+        let best = null;
+        if (!queryLower) {
+            best = { score: 1, highlighted: escapeHtml(idiom.description) };
+        } else {
+            best = fuzzyMatch(idiom.description, queryLower, escapeHtml);
+            if (idiom.tags.some(tag => tag.toLowerCase().includes(queryLower))) {
+                if (!best || best.score < 120) best = { score: 120, highlighted: escapeHtml(idiom.description) };
+            }
+            // Glyphs typed into the search find idioms that use them
+            if (idiom.code.includes(query.trim())) {
+                best = { score: 200, highlighted: best ? best.highlighted : escapeHtml(idiom.description) };
+            }
+        }
+        if (!best) continue;
+
+        const score = best.score + (idiom.language === language ? 30 : 0) + (idiom.source === 'arraybox' ? 5 : 0);
+        results.push({ idiom, score, highlighted: best.highlighted });
+    }
+
+    results.sort((a, b) => b.score - a.score);
+    return results.slice(0, limit);
+}
+
+/**
+ * An idiom's code in another language (primitives and array literals translated)
+ * @param {object} idiom - Idiom to translate
+ * @param {string} toLang - Target language
+ * @returns {string} - Translated code (unchanged for the idiom's own language)
+ */
+export function translateIdiom(idiom, toLang) {
+    if (idiom.language === toLang) return idiom.code;
+    return translateArrayLiterals(translatePrimitives(idiom.code, idiom.language, toLang), idiom.language, toLang);
+}
+
+export default {
+    curatedIdioms,
+    idioms,
+    searchIdioms,
+    translateIdiom
+};
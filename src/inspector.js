@@ -0,0 +1,388 @@
+/**
+ * Output inspector
+ * - Draws a result as boxes (src/array-display.js) whose cells describe
+ *   themselves on hover: index path, shape, rank, depth and element type
+ * - Clicking a cell copies the expression that selects it in the result's
+ *   language (1‿2⊑ in BQN, (⊂2 3)⊃ in APL, ...), to put in front of the code
+ *
+ * TinyAPL and Kap results carry a structured value. For BQN, APL, J and Uiua
+ * the value is read back from the text output when it is one simple numeric
+ * array or string as the language prints it; anything else stays text.
+ */
+
+import { renderArrayHtml } from './array-display.js';
+
+// Index origin by language (APL is Dyalog with ⎕IO←1)
+const INDEX_ORIGIN = { apl: 1 };
+
+// ============================================================================
+// Values
+// ============================================================================
+
+/**
+ * The part of a value at an index path
+ * @param {object} value - Value in the array-display.js model
+ * @param {Array<number[]>} path - One multi-index per level
+ * @returns {object}
+ */
+export function valueAt(value, path) {
+    for (const index of path) {
+        let n = 0;
+        index.forEach((i, axis) => { n = n * value.shape[axis] + i; });
+        value = value.items[n];
+    }
+    return value;
+}
+
+// Depth as APL's ≡ counts it: 0 for a simple scalar, 1 for a simple array
+function depthOf(value) {
+    if (value.kind !== 'array') return 0;
+    return 1 + value.items.reduce((max, item) => Math.max(max, depthOf(item)), 0);
+}
+
+function scalarType(value) {
+    if (value.kind === 'char') return 'character';
+    if (value.kind !== 'number') return 'other';
+    if (/.[jJᴊ]/.test(value.text)) return 'complex';
+    if (/\//.test(value.text)) return 'rational';
+    return /[.eE∞]/.test(value.text) ? 'float' : 'integer';
+}
+
+function arrayType(value) {
+    const { items } = value;
+    if (items.length === 0) return 'empty';
+    if (items.some(item => item.kind === 'array')) return value.shape.length === 0 ? 'enclosure' : 'nested';
+    if (items.every(item => item.kind === 'char')) return 'character';
+    if (items.every(item => item.kind === 'number')) return 'numeric';
+    return 'mixed';
+}
+
+/**
+ * Shape, rank, depth and type of a value
+ * @param {object} value
+ * @returns {{ shape: number[], rank: number, depth: number, type: string }}
+ */
+export function describeValue(value) {
+    const shape = value.kind === 'array' ? value.shape : [];
+    return {
+        shape,
+        rank: shape.length,
+        depth: depthOf(value),
+        type: value.kind === 'array' ? arrayType(value) : scalarType(value)
+    };
+}
+
+// ============================================================================
+// Selection expressions
+// ============================================================================
+
+// Whether the selection at inner[i] is made in a boxed item, picked by the
+// next (outer) level from an array, which must be unboxed first
+function boxed(inner, i) {
+    return i < inner.length - 1 && inner[i + 1].length > 0;
+}
+
+/**
+ * An expression selecting the element at a path, written to go in front of
+ * the code that produced the result (functions applied right to left)
+ * @param {string} lang - Language id
+ * @param {Array<number[]>} path - One multi-index per level ([] for an enclosure)
+ * @returns {string} - Empty for the whole result
+ */
+export function selectExpression(lang, path) {
+    if (path.length === 0) return '';
+    const origin = INDEX_ORIGIN[lang] || 0;
+    const levels = path.map(index => index.map(i => i + origin));
+    // Selections innermost first, as they are written
+    const inner = [...levels].reverse();
+
+    switch (lang) {
+        case 'bqn':
+            return inner.map(index => index.length ? `${index.join('‿')}⊑` : '⊑').join('');
+        case 'tinyapl':
+            return inner.map(index => index.length ? `⊃${index.join('‿')}⌷` : '⊃').join('');
+        case 'j':
+            // Nested arrays are boxes: open each one before selecting in it
+            return inner.map((index, i) => {
+                const select = index.length === 0 ? '>'
+                    : index.length === 1 ? `${index[0]}{` : `(<${index.join(' ')}){`;
+                return boxed(inner, i) ? `${select}>` : select;
+            }).join('');
+        case 'uiua':
+            return inner.map((index, i) => {
+                const select = index.length === 0 ? '°□' : `⊡${index.join('_')}`;
+                return boxed(inner, i) ? `${select}°□` : select;
+            }).join('');
+        case 'apl':
+        case 'kap': {
+            // Pick: one item of the left argument per level
+            if (levels.length === 1) {
+                const [index] = levels;
+                if (index.length === 1) return `${index[0]}⊃`;
+                return `(⊂${index.length ? index.join(' ') : '⍬'})⊃`;
+            }
+            const items = levels.map(index =>
+                index.length === 0 ? '⍬' : index.length === 1 ? `${index[0]}` : `(${index.join(' ')})`);
+            return `(${items.join(' ')})⊃`;
+        }
+        default:
+            return '';
+    }
+}
+
+// ============================================================================
+// Values read from text output
+// ============================================================================
+
+// A number as each language prints it (¯ for negatives, J uses _)
+const NUMBER = {
+    apl: /^¯?(\d+\.?\d*|\.\d+)(E¯?\d+)?$/,
+    bqn: /^¯?(∞|(\d+\.?\d*|\.\d+)(e¯?\d+)?)$/,
+    uiua: /^¯?(∞|(\d+\.?\d*|\.\d+)(e¯?\d+)?)$/,
+    j: /^_?(_|(\d+\.?\d*|\.\d+)(e_?\d+)?)$/
+};
+
+function numbers(lang, text) {
+    const tokens = text.trim().split(/\s+/);
+    if (!tokens.every(token => NUMBER[lang].test(token))) return null;
+    return tokens.map(token => ({ kind: 'number', text: token }));
+}
+
+function array(shape, items) {
+    return { kind: 'array', shape, items };
+}
+
+function vectorOrScalar(lang, line) {
+    const items = numbers(lang, line);
+    if (!items) return null;
+    return items.length === 1 ? items[0] : array([items.length], items);
+}
+
+// Rows of numbers, with a blank line between the matrices of a rank 3 array
+function numericRows(lang, lines) {
+    const slices = [[]];
+    for (const line of lines) {
+        if (line.trim()) {
+            slices[slices.length - 1].push(line);
+        } else if (slices[slices.length - 1].length) {
+            slices.push([]);
+        } else {
+            return null;  // Two blank lines: rank 4 or more
+        }
+    }
+    const rows = slices.flat().map(line => numbers(lang, line));
+    if (rows.some(row => !row || row.length !== rows[0].length)) return null;
+    if (slices.some(slice => slice.length !== slices[0].length)) return null;
+
+    const shape = slices.length > 1
+        ? [slices.length, slices[0].length, rows[0].length]
+        : [rows.length, rows[0].length];
+    return array(shape, rows.flat());
+}
+
+function quotedString(text, quote) {
+    if (text.length < 2 || text[0] !== quote || text[text.length - 1] !== quote) return null;
+    const body = text.slice(1, -1);
+    // A string printed with escapes or doubled quotes is left as text
+    if (body.includes(quote) || body.includes('\\') || body.includes('\n')) return null;
+    return array([[...body].length], [...body].map(c => ({ kind: 'char', text: c })));
+}
+
+// BQN's ⟨ … ⟩ list notation, with nested lists, numbers, 'c' and "strings"
+function bqnList(text) {
+    let pos = 0;
+    const skip = () => { while (text[pos] === ' ') pos++; };
+    function item() {
+        skip();
+        if (text[pos] === '⟨') {
+            pos++;
+            const items = [];
+            for (skip(); text[pos] !== '⟩'; skip()) {
+                if (pos >= text.length) throw new Error('Unclosed list');
+                items.push(item());
+            }
+            pos++;
+            return array([items.length], items);
+        }
+        if (text[pos] === '"' || text[pos] === '\'') {
+            const end = text.indexOf(text[pos], pos + 1);
+            const value = end > pos && text[end + 1] !== text[pos] && quotedString(text.slice(pos, end + 1), text[pos]);
+            if (!value) throw new Error('Unreadable string');
+            pos = end + 1;
+            return text[end] === '\'' && value.items.length === 1 ? value.items[0] : value;
+        }
+        const token = /^[^\s⟨⟩]+/.exec(text.slice(pos));
+        if (!token || !NUMBER.bqn.test(token[0])) throw new Error('Unreadable item');
+        pos += token[0].length;
+        return { kind: 'number', text: token[0] };
+    }
+    try {
+        const value = item();
+        skip();
+        return pos === text.length ? value : null;
+    } catch (e) {
+        return null;
+    }
+}
+
+// A box of numeric rows: ┌─ / ╵ rows / ┘ for BQN, ╭─ / ╷ rows / ╯ for Uiua
+function boxedRows(lang, lines, corners) {
+    const [top, left, bottom] = corners;
+    if (lines.length < 3 || lines[0].trim() !== top || lines[lines.length - 1].trim() !== bottom) return null;
+    const body = lines.slice(1, -1);
+    // BQN marks rank 3 with ╎; Uiua prints rank 3 side by side (left as text)
+    const mark = body[0][0];
+    if (mark !== left && !(lang === 'bqn' && mark === '╎')) return null;
+    if (body.slice(1).some(line => line[0] !== ' ')) return null;
+    const value = numericRows(lang, body.map(line => line.slice(1)));
+    if (!value) return null;
+    const rank3 = mark === '╎';
+    return value.shape.length === (rank3 ? 3 : 2) ? value : null;
+}
+
+/**
+ * Read a value back from a language's text output
+ * @param {string} lang - Language id
+ * @param {string} output - Text output of a successful evaluation
+ * @returns {object|null} - Value in the array-display.js model, or null
+ */
+export function textValue(lang, output) {
+    const text = (output || '').replace(/\s+$/, '');
+    const lines = text.split('\n');
+    if (!text) return null;
+
+    switch (lang) {
+        case 'bqn':
+            if (lines.length === 1) return quotedString(text, '"') || bqnList(text);
+            return boxedRows(lang, lines, ['┌─', '╵', '┘']);
+        case 'uiua':
+            if (lines.length === 1) {
+                if (text.startsWith('[') && text.endsWith(']')) {
+                    const items = text.length > 2 ? numbers(lang, text.slice(1, -1)) : [];
+                    return items && array([items.length], items);
+                }
+                return quotedString(text, '"') || vectorOrScalar(lang, text);
+            }
+            return boxedRows(lang, lines, ['╭─', '╷', '╯']);
+        case 'apl':
+            return lines.length === 1 ? vectorOrScalar(lang, text) : numericRows(lang, lines);
+        case 'j':
+            // J results arrive without their blank lines, so a rank 3 array
+            // can't be told from a matrix: only single lines are read
+            return lines.length === 1 ? vectorOrScalar(lang, text) : null;
+        default:
+            return null;
+    }
+}
+
+// ============================================================================
+// Inspector
+// ============================================================================
+
+/**
+ * Create the inspector
+ * Outputs drawn with show() describe their cells on hover; one tooltip is
+ * shared by all of them.
+ * @param {object} options
+ * @param {Function} options.onCopy - (expression) => void, after a cell's
+ *   selection expression is copied
+ * @returns {object} - { show, hide }
+ */
+export function createInspector(options) {
+    const inspected = new WeakMap();  // Output element -> { lang, value }
+    const tooltip = document.createElement('div');
+    tooltip.className = 'inspector-tooltip';
+    document.body.appendChild(tooltip);
+
+    function target(event) {
+        const cell = event.target.closest('[data-path]');
+        const display = cell && cell.closest('.array-display');
+        const owner = display && inspected.get(display.parentElement);
+        return owner ? { cell, owner, path: JSON.parse(cell.dataset.path) } : null;
+    }
+
+    function describe({ owner, path }) {
+        const { shape, rank, depth, type } = describeValue(valueAt(owner.value, path));
+        const expression = selectExpression(owner.lang, path);
+        const rows = [
+            ['index', path.length ? path.map(index => index.length ? index.join(' ') : '⊂').join(' → ') : 'whole result'],
+            ['shape', rank ? shape.join(' ') : 'scalar'],
+            ['rank', rank],
+            ['depth', depth],
+            ['type', type]
+        ];
+        if (expression) rows.push(['select', expression]);
+        tooltip.innerHTML = '';
+        for (const [label, text] of rows) {
+            const row = document.createElement('div');
+            const name = document.createElement('span');
+            name.className = 'inspector-label';
+            name.textContent = label;
+            row.append(name, String(text));
+            tooltip.appendChild(row);
+        }
+        if (expression) {
+            const hint = document.createElement('div');
+            hint.className = 'inspector-hint';
+            hint.textContent = 'click to copy';
+            tooltip.appendChild(hint);
+        }
+    }
+
+    let hovered = null;
+    document.addEventListener('mouseover', (event) => {
+        const found = target(event);
+        if (hovered) hovered.classList.remove('inspected');
+        hovered = found ? found.cell : null;
+        if (!found) {
+            tooltip.classList.remove('show');
+            return;
+        }
+        hovered.classList.add('inspected');
+        describe(found);
+        const rect = hovered.getBoundingClientRect();
+        tooltip.style.left = `${Math.max(8, rect.left)}px`;
+        tooltip.style.top = `${rect.bottom + 6}px`;
+        tooltip.classList.add('show');
+    });
+
+    document.addEventListener('click', (event) => {
+        // Clicking a nested cell's badge collapses it instead
+        if (event.target.closest('summary')) return;
+        const found = target(event);
+        if (!found) return;
+        const expression = selectExpression(found.owner.lang, found.path);
+        if (!expression) return;
+        navigator.clipboard.writeText(expression).then(() => options.onCopy(expression));
+    });
+
+    /**
+     * Draw a result in an output element for inspection
+     * @param {HTMLElement} element
+     * @param {string} lang - Language id
+     * @param {object} result - { success, output, value? }
+     * @returns {boolean} - False (and nothing drawn) when the result has no value
+     */
+    function show(element, lang, result) {
+        const value = result.success ? result.value || textValue(lang, result.output) : null;
+        const html = value && renderArrayHtml(value, { inspect: true });
+        if (!html) return false;
+        element.innerHTML = html;
+        inspected.set(element, { lang, value });
+        return true;
+    }
+
+    function hide() {
+        tooltip.classList.remove('show');
+    }
+
+    return { show, hide };
+}
+
+export default {
+    valueAt,
+    describeValue,
+    selectExpression,
+    textValue,
+    createInspector
+};
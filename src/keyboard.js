@@ -963,8 +963,8 @@ const defaultStyles = `
 }
 
 .array-keyboard-overlay {
-    background: #1f2937;
-    border: 3px solid #4b5563;
+    background: var(--input-bg);
+    border: 3px solid var(--border-color);
     border-radius: 21px;
     padding: 28px;
     box-shadow: 0 14px 56px rgba(0, 0, 0, 0.4);
@@ -979,14 +979,14 @@ const defaultStyles = `
     align-items: center;
     margin-bottom: 21px;
     padding-bottom: 14px;
-    border-bottom: 2px solid #4b5563;
+    border-bottom: 2px solid var(--border-color);
 }
 
 .array-keyboard-title {
     font-family: 'JetBrains Mono', monospace;
     font-size: 24px;
     font-variant-ligatures: none;
-    color: #e5e7eb;
+    color: var(--text-color);
     opacity: 0.8;
     display: flex;
     align-items: center;
@@ -1018,7 +1018,7 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 21px;
     font-variant-ligatures: none;
-    color: #6b7280;
+    color: var(--text-muted);
 }
 
 .array-keyboard-row {
@@ -1032,8 +1032,8 @@ const defaultStyles = `
 .array-keyboard-key {
     width: 84px;
     height: 84px;
-    background: #1f2937;
-    border: 2px solid #4b5563;
+    background: var(--input-bg);
+    border: 2px solid var(--border-color);
     border-radius: 11px;
     display: flex;
     flex-direction: column;
@@ -1044,8 +1044,8 @@ const defaultStyles = `
 }
 
 .array-keyboard-key:hover {
-    background: #374151;
-    border-color: #6b7280;
+    background: var(--dropdown-hover);
+    border-color: var(--border-hover);
 }
 
 .array-keyboard-key.search-match {
@@ -1094,7 +1094,7 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 16px;
     font-variant-ligatures: none;
-    color: #6b7280;
+    color: var(--text-muted);
     position: absolute;
     bottom: 5px;
     right: 7px;
@@ -1182,7 +1182,7 @@ const defaultStyles = `
     right: 50%;
     transform: translateX(50%);
     font-size: 12px;
-    color: #6b7280;
+    color: var(--text-muted);
     opacity: 0.7;
 }
 
@@ -1272,7 +1272,7 @@ const defaultStyles = `
     right: 50%;
     transform: translateX(50%);
     font-size: 11px;
-    color: #6b7280;
+    color: var(--text-muted);
     opacity: 0.7;
 }
 
@@ -1356,7 +1356,7 @@ const defaultStyles = `
     font-variant-ligatures: none;
     margin-bottom: 14px;
     padding-bottom: 7px;
-    border-bottom: 2px solid #4b5563;
+    border-bottom: 2px solid var(--border-color);
 }
 
 .array-keyboard-category-title.syntax-function         { color: var(--syntax-function); }
@@ -1364,7 +1364,7 @@ const defaultStyles = `
 .array-keyboard-category-title.syntax-modifier-dyadic  { color: var(--syntax-modifier-dyadic); }
 .array-keyboard-category-title.syntax-number           { color: var(--syntax-number); }
 .array-keyboard-category-title.syntax-comment          { color: var(--syntax-comment); }
-.array-keyboard-category-title.syntax-default          { color: #9CA3AF; }
+.array-keyboard-category-title.syntax-default          { color: var(--text-secondary); }
 .array-keyboard-category-title.syntax-stack            { color: var(--syntax-default); }
 .array-keyboard-category-title.syntax-uiua-function-monadic { color: var(--syntax-uiua-function-monadic); }
 .array-keyboard-category-title.syntax-uiua-function-dyadic  { color: var(--syntax-uiua-function-dyadic); }
@@ -1395,7 +1395,7 @@ const defaultStyles = `
     justify-content: center;
     gap: 21px;
     padding-top: 14px;
-    border-top: 2px solid #4b5563;
+    border-top: 2px solid var(--border-color);
 }
 
 .array-keyboard-legend-item {
@@ -1416,7 +1416,7 @@ const defaultStyles = `
 .array-keyboard-legend-dot.syntax-modifier-dyadic  { background-color: var(--syntax-modifier-dyadic); }
 .array-keyboard-legend-dot.syntax-number           { background-color: var(--syntax-number); }
 .array-keyboard-legend-dot.syntax-comment          { background-color: var(--syntax-comment); }
-.array-keyboard-legend-dot.syntax-default          { background-color: #9CA3AF; }
+.array-keyboard-legend-dot.syntax-default          { background-color: var(--text-secondary); }
 .array-keyboard-legend-dot.syntax-stack            { background-color: var(--syntax-default); }
 .array-keyboard-legend-dot.syntax-uiua-function-monadic { background-color: var(--syntax-uiua-function-monadic); }
 .array-keyboard-legend-dot.syntax-uiua-function-dyadic  { background-color: var(--syntax-uiua-function-dyadic); }
@@ -1427,14 +1427,14 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 18px;
     font-variant-ligatures: none;
-    color: #9CA3AF;
+    color: var(--text-secondary);
 }
 
 .array-keyboard-glyph {
     width: 67px;
     height: 56px;
-    background: #1f2937;
-    border: 2px solid #4b5563;
+    background: var(--input-bg);
+    border: 2px solid var(--border-color);
     border-radius: 7px;
     display: flex;
     align-items: center;
@@ -1450,8 +1450,8 @@ const defaultStyles = `
 }
 
 .array-keyboard-glyph:hover {
-    background: #374151;
-    border-color: #6b7280;
+    background: var(--dropdown-hover);
+    border-color: var(--border-hover);
 }
 
 .array-keyboard-glyph.nav-selected {
@@ -1473,7 +1473,7 @@ const defaultStyles = `
 
 /* Solid border with filled background for array functions */
 .array-keyboard-glyph.array-glyph {
-    background: #374151;
+    background: var(--dropdown-hover);
 }
 
 /* Leader lines overlay for glyph names */
@@ -1499,7 +1499,7 @@ const defaultStyles = `
 }
 
 .array-keyboard-leader-line {
-    stroke: #6b7280;
+    stroke: var(--border-hover);
     stroke-width: 2;  // Scaled 1.75x from 1 (rounded to 2 for better visibility)
     stroke-linecap: round;
     stroke-linejoin: round;
@@ -1514,8 +1514,8 @@ const defaultStyles = `
     font-variant-ligatures: none;
     padding: 4px 11px;
     border-radius: 5px;
-    background: #374151;
-    border: 2px solid #4b5563;
+    background: var(--dropdown-hover);
+    border: 2px solid var(--border-color);
     white-space: nowrap;
     pointer-events: none;
 }
@@ -1525,7 +1525,7 @@ const defaultStyles = `
 .array-keyboard-name-label.syntax-modifier-dyadic  { color: var(--syntax-modifier-dyadic); border-color: color-mix(in srgb, var(--syntax-modifier-dyadic) 25%, transparent); }
 .array-keyboard-name-label.syntax-number           { color: var(--syntax-number); border-color: color-mix(in srgb, var(--syntax-number) 25%, transparent); }
 .array-keyboard-name-label.syntax-comment          { color: var(--syntax-comment); border-color: color-mix(in srgb, var(--syntax-comment) 25%, transparent); }
-.array-keyboard-name-label.syntax-default          { color: #e5e7eb; border-color: color-mix(in srgb, #e5e7eb 25%, transparent); }
+.array-keyboard-name-label.syntax-default          { color: var(--text-color); border-color: color-mix(in srgb, var(--text-color) 25%, transparent); }
 .array-keyboard-name-label.syntax-stack            { color: var(--syntax-default); border-color: color-mix(in srgb, var(--syntax-default) 25%, transparent); }
 .array-keyboard-name-label.syntax-uiua-function-monadic { color: var(--syntax-uiua-function-monadic); border-color: color-mix(in srgb, var(--syntax-uiua-function-monadic) 25%, transparent); }
 .array-keyboard-name-label.syntax-uiua-function-dyadic  { color: var(--syntax-uiua-function-dyadic); border-color: color-mix(in srgb, var(--syntax-uiua-function-dyadic) 25%, transparent); }
@@ -1537,13 +1537,13 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 19px;
     font-variant-ligatures: none;
-    color: #9CA3AF;
+    color: var(--text-secondary);
     margin-left: 21px;
 }
 
 .array-keyboard-names-hint kbd {
-    background: #374151;
-    border: 2px solid #4b5563;
+    background: var(--dropdown-hover);
+    border: 2px solid var(--border-color);
     border-radius: 5px;
     padding: 2px 7px;
     font-size: 18px;
@@ -1568,10 +1568,10 @@ const defaultStyles = `
     font-size: 24px;
     font-variant-ligatures: none;
     padding: 14px 28px;
-    border: 3px solid #4b5563;
+    border: 3px solid var(--border-color);
     border-radius: 14px;
-    background: #1f2937;
-    color: #e5e7eb;
+    background: var(--input-bg);
+    color: var(--text-color);
     width: 525px;
     outline: none;
     transition: border-color 0.2s;
@@ -1582,14 +1582,14 @@ const defaultStyles = `
 }
 
 .array-keyboard-search-input::placeholder {
-    color: #6b7280;
+    color: var(--text-muted);
 }
 
 .array-keyboard-search-hint {
     font-family: 'JetBrains Mono', monospace;
     font-size: 19px;
     font-variant-ligatures: none;
-    color: #6b7280;
+    color: var(--text-muted);
     text-align: center;
     margin-top: 7px;
 }
@@ -1598,7 +1598,7 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 18px;
     font-variant-ligatures: none;
-    color: #6b7280;
+    color: var(--text-muted);
     opacity: 0.5;
     position: absolute;
     right: 0;
@@ -1619,14 +1619,14 @@ const defaultStyles = `
 }
 
 .array-keyboard-doc-links .separator {
-    color: #4b5563;
+    color: var(--border-color);
     margin: 0 11px;
 }
 
 /* Hover tooltip styles */
 .array-keyboard-tooltip {
-    background: #1f2937;
-    border: 2px solid #4b5563;
+    background: var(--input-bg);
+    border: 2px solid var(--border-color);
     border-radius: 14px;
     padding: 21px 24px;
     box-shadow: 0 7px 35px rgba(0, 0, 0, 0.5);
@@ -1658,7 +1658,7 @@ const defaultStyles = `
     gap: 18px;
     margin-bottom: 14px;
     padding-bottom: 11px;
-    border-bottom: 2px solid #374151;
+    border-bottom: 2px solid var(--divider-color);
 }
 
 .array-keyboard-tooltip-glyph {
@@ -1671,18 +1671,18 @@ const defaultStyles = `
     font-size: 24px;
     font-weight: 600;
     font-variant-ligatures: none;
-    color: #e5e7eb;
+    color: var(--text-color);
 }
 
 .array-keyboard-tooltip-type {
     font-family: 'JetBrains Mono', monospace;
     font-size: 16px;
     font-variant-ligatures: none;
-    color: #9ca3af;
+    color: var(--text-secondary);
     text-transform: uppercase;
     letter-spacing: 0.9px;
     margin-left: auto;
-    background: #374151;
+    background: var(--dropdown-hover);
     padding: 4px 11px;
     border-radius: 5px;
 }
@@ -1713,7 +1713,7 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 21px;
     font-variant-ligatures: none;
-    color: #d1d5db;
+    color: var(--text-soft);
     line-height: 1.6;
     margin-bottom: 18px;
 }
@@ -1723,14 +1723,14 @@ const defaultStyles = `
     font-size: 23px;
     font-weight: 600;
     font-variant-ligatures: none;
-    color: #e5e7eb;
+    color: var(--text-color);
     margin-bottom: 11px;
 }
 
 .array-keyboard-tooltip-section {
     margin-bottom: 18px;
     padding-bottom: 14px;
-    border-bottom: 2px solid #374151;
+    border-bottom: 2px solid var(--divider-color);
 }
 
 .array-keyboard-tooltip-section:last-of-type {
@@ -1752,14 +1752,14 @@ const defaultStyles = `
     font-family: 'JetBrains Mono', monospace;
     font-size: 19px;
     font-variant-ligatures: none;
-    color: #d1d5db;
+    color: var(--text-soft);
     line-height: 1.5;
 }
 
 .array-keyboard-tooltip-example {
     font-size: 21px;
-    color: #e5e7eb;
-    background: #111827;
+    color: var(--text-color);
+    background: var(--bg-color);
     padding: 18px;
     border-radius: 7px;
     white-space: pre-wrap;
@@ -0,0 +1,326 @@
+/**
+ * Multi-language solve view
+ * - One box per language, seeded from the box being edited by translating
+ *   primitives and array literals (src/primitive-translate.js)
+ * - Run every box together and show the outputs side by side
+ * - Check whether the outputs agree
+ * - Share the boxes as a collection permalink (see src/collection-view.js)
+ *
+ * Each language prints arrays its own way (⟨ 1 2 ⟩, [1 2], boxed tables, _1
+ * for ¯1, ...), so outputs are compared by value: numbers, words and
+ * characters in reading order, ignoring brackets, box drawing, quotes and
+ * separators. Numbers are compared to 6 significant digits, since the
+ * languages print floats at different precisions.
+ */
+
+import { translatePrimitives, translateArrayLiterals } from './primitive-translate.js';
+
+/**
+ * Translate code from one language into each of the others
+ * @param {string} code - Source code
+ * @param {string} fromLang - Language the code is written in
+ * @param {Array<string>} langs - Languages to translate to
+ * @returns {Object<string, string>} - Code by language (the source language keeps its code)
+ */
+export function seedTranslations(code, fromLang, langs) {
+    const seeded = {};
+    for (const lang of langs) {
+        seeded[lang] = lang === fromLang
+            ? code
+            : translateArrayLiterals(translatePrimitives(code, fromLang, lang), fromLang, lang);
+    }
+    return seeded;
+}
+
+// Box drawing, brackets, quotes and separators carry no value
+const DECORATION = /[─-╿()[\]{}⟨⟩"'`‿_,;:│|+\-↓→∙·⋄]/g;
+// Numbers with any of the languages' negative signs (¯ APL/BQN/Uiua/TinyAPL, _ J, - Kap)
+const NUMBER = /(^|[^\w.¯])([¯_-]?)(\d+(?:\.\d+)?(?:[eE][¯_-]?\d+)?)(?![\w.])/g;
+
+function canonicalNumber(sign, digits) {
+    const value = Number(digits.replace(/[¯_]/g, '-'));
+    const rounded = Number(value.toPrecision(6));
+    return String(sign ? -rounded : rounded);
+}
+
+/**
+ * Reduce an output to the values it shows, for comparing across languages
+ * @param {string} output - Interpreter output
+ * @returns {Array<string>} - Numbers, words and characters in reading order
+ */
+export function normalizeOutput(output) {
+    const tokens = [];
+    const withoutNumbers = (output || '').replace(NUMBER, (match, before, sign, digits) => {
+        tokens.push({ at: tokens.length, value: canonicalNumber(sign, digits) });
+        return `${before} \u0000${tokens.length - 1}\u0000 `;
+    });
+
+    const values = [];
+    for (const part of withoutNumbers.replace(DECORATION, ' ').split(/\s+/)) {
+        if (!part) continue;
+        const number = part.match(/^\u0000(\d+)\u0000$/);
+        values.push(number ? tokens[Number(number[1])].value : part);
+    }
+    return values;
+}
+
+/**
+ * Compare the outputs of several languages
+ * @param {Object<string, {success: boolean, output: string}>} results - Results by language
+ * @returns {{agree: boolean, groups: Array<Array<string>>}} - Whether every result
+ *   succeeded with the same values, and the languages grouped by matching values
+ *   (largest group first)
+ */
+export function compareOutputs(results) {
+    const groups = new Map();
+    for (const [lang, result] of Object.entries(results)) {
+        const key = result.success ? normalizeOutput(result.output).join(' ') : `\u0000error:${lang}`;
+        if (!groups.has(key)) groups.set(key, []);
+        groups.get(key).push(lang);
+    }
+    const sorted = [...groups.values()].sort((a, b) => b.length - a.length);
+    const allSucceeded = Object.values(results).every(result => result.success);
+    return { agree: allSucceeded && sorted.length === 1, groups: sorted };
+}
+
+/**
+ * Create the multi-language view manager
+ * @param {object} elements - { screen, panes, toolbar, status } DOM elements
+ * @param {object} options
+ * @param {object} options.languages - Language configs by id ({ name, logo, fontClass })
+ * @param {Array<string>} options.languageOrder - Languages shown, in order
+ * @param {Function} options.evaluate - async (lang, code) => { success, output, formatted?, outputHtml? }
+ * @param {Function} options.createKeyboardHandler - (element, lang) => cleanup, or null for no keymap
+ * @param {Function} options.renderOutput - (element, lang, result) fills an output element
+ * @param {Function} options.share - async (snippets) => { id } or { error }, publish the
+ *   non-empty boxes as a collection ({ lang, code, result? } each)
+ * @param {Function} options.onClose - (sourceLang, code) called when the view closes
+ * @returns {object} - Manager API
+ */
+export function createMultiLangView(elements, options) {
+    const { screen, panes: panesElement, toolbar, status } = elements;
+    const panes = {};
+    let sourceLang = options.languageOrder[0];
+    let running = false;
+    let lastResults = {};  // Results of the last run, by language, until the box changes
+
+    for (const lang of options.languageOrder) {
+        panes[lang] = createPane(lang);
+        panesElement.appendChild(panes[lang].element);
+    }
+
+    function createPane(lang) {
+        const config = options.languages[lang];
+        const element = document.createElement('div');
+        element.className = 'multi-pane';
+        element.dataset.lang = lang;
+
+        const header = document.createElement('div');
+        header.className = 'multi-pane-header';
+        const logo = document.createElement('img');
+        logo.src = config.logo;
+        logo.alt = config.name;
+        logo.className = 'multi-pane-logo';
+        const name = document.createElement('span');
+        name.className = 'multi-pane-name';
+        name.textContent = config.name;
+        const spacer = document.createElement('span');
+        spacer.className = 'notebook-spacer';
+        const seed = document.createElement('button');
+        seed.className = 'notebook-button';
+        seed.textContent = '⇉';
+        seed.title = 'Translate this box into the others';
+        seed.addEventListener('click', () => seedFrom(lang));
+        header.append(logo, name, spacer, seed);
+
+        const editor = document.createElement('textarea');
+        editor.className = `multi-pane-editor ${config.fontClass}`;
+        editor.spellcheck = false;
+        editor.addEventListener('focus', () => setSource(lang));
+        editor.addEventListener('input', () => {
+            delete element.dataset.agreement;
+            delete lastResults[lang];
+            autosize(editor);
+        });
+        editor.addEventListener('keydown', (e) => {
+            // Enter runs every box (Shift+Enter inserts a newline, as in the main box)
+            if (e.key === 'Enter' && !e.shiftKey) {
+                e.preventDefault();
+                e.stopPropagation();
+                runAll();
+            }
+        });
+        if (options.createKeyboardHandler) options.createKeyboardHandler(editor, lang);
+
+        const output = document.createElement('pre');
+        output.className = `multi-pane-output ${config.fontClass}`;
+
+        element.append(header, editor, output);
+        return { element, editor, output };
+    }
+
+    function autosize(editor) {
+        editor.rows = Math.max(2, editor.value.split('\n').length);
+    }
+
+    function setSource(lang) {
+        sourceLang = lang;
+        for (const [paneLang, pane] of Object.entries(panes)) {
+            pane.element.classList.toggle('source', paneLang === lang);
+        }
+    }
+
+    function setCode(lang, code) {
+        panes[lang].editor.value = code;
+        autosize(panes[lang].editor);
+    }
+
+    function clearResults() {
+        for (const pane of Object.values(panes)) {
+            pane.output.textContent = '';
+            pane.output.classList.remove('error');
+            delete pane.element.dataset.agreement;
+        }
+        lastResults = {};
+        status.textContent = '';
+        status.className = 'multi-status';
+    }
+
+    // Translate one box into every other box
+    function seedFrom(lang) {
+        const seeded = seedTranslations(panes[lang].editor.value, lang, options.languageOrder);
+        for (const [targetLang, code] of Object.entries(seeded)) setCode(targetLang, code);
+        setSource(lang);
+        clearResults();
+    }
+
+    async function runAll() {
+        if (running) return;
+        running = true;
+        status.textContent = 'running…';
+        status.className = 'multi-status';
+
+        const results = {};
+        try {
+            await Promise.all(options.languageOrder.map(async (lang) => {
+                const pane = panes[lang];
+                const code = pane.editor.value.trim();
+                if (!code) return;
+                pane.element.classList.add('running');
+                let result;
+                try {
+                    result = await options.evaluate(lang, code);
+                } catch (error) {
+                    result = { success: false, output: error.message || String(error) };
+                }
+                pane.element.classList.remove('running');
+
+                // Uiua formats code as it evaluates
+                if (result.formatted && result.formatted.trim() !== code) setCode(lang, result.formatted.trim());
+                pane.output.className = `multi-pane-output ${options.languages[lang].fontClass} ${result.success ? '' : 'error'}`;
+                options.renderOutput(pane.output, lang, result);
+                results[lang] = result;
+            }));
+        } finally {
+            running = false;
+        }
+
+        lastResults = results;
+        showAgreement(results);
+        return results;
+    }
+
+    // Mark panes that match the largest group of agreeing outputs
+    function showAgreement(results) {
+        const langs = Object.keys(results);
+        if (langs.length < 2) {
+            status.textContent = langs.length ? 'only one box to compare' : '';
+            status.className = 'multi-status';
+            return;
+        }
+
+        const { agree, groups } = compareOutputs(results);
+        const majority = results[groups[0][0]].success && groups[0].length > 1 ? groups[0] : [];
+        for (const lang of options.languageOrder) {
+            if (!results[lang]) delete panes[lang].element.dataset.agreement;
+            else panes[lang].element.dataset.agreement = majority.includes(lang) ? 'agree' : 'differ';
+        }
+
+        if (agree) {
+            status.textContent = `✓ all ${langs.length} outputs agree`;
+            status.className = 'multi-status agree';
+        } else {
+            const names = langs.filter(lang => !majority.includes(lang)).map(lang => options.languages[lang].name);
+            status.textContent = majority.length
+                ? `✗ ${names.join(', ')} differ${names.length === 1 ? 's' : ''}`
+                : '✗ no two outputs agree';
+            status.className = 'multi-status differ';
+        }
+    }
+
+    // Publish the non-empty boxes (with the outputs of the last run that
+    // succeeded) as a collection
+    async function share() {
+        if (!options.share) return;
+        const snippets = [];
+        for (const lang of options.languageOrder) {
+            const code = panes[lang].editor.value.trim();
+            if (!code) continue;
+            const snippet = { lang, code };
+            if (lastResults[lang] && lastResults[lang].success) snippet.result = lastResults[lang].output;
+            snippets.push(snippet);
+        }
+        if (snippets.length === 0) return;
+
+        status.textContent = 'sharing…';
+        status.className = 'multi-status';
+        const { id, error } = await options.share(snippets);
+        status.textContent = id ? `shared #${id} (link copied)` : error;
+        status.className = `multi-status ${id ? 'agree' : 'differ'}`;
+    }
+
+    function isOpen() {
+        return screen.classList.contains('show');
+    }
+
+    /**
+     * Open the view seeded from one language's code
+     * @param {string} lang - Language of the code being edited
+     * @param {string} code - Code to translate into every box
+     */
+    function open(lang, code) {
+        setCode(lang, code);
+        screen.classList.add('show');
+        seedFrom(lang);
+        panes[lang].editor.focus();
+    }
+
+    function close() {
+        screen.classList.remove('show');
+        if (options.onClose) options.onClose(sourceLang, panes[sourceLang].editor.value);
+    }
+
+    toolbar.addEventListener('click', (e) => {
+        const action = e.target.closest('[data-action]')?.dataset.action;
+        if (action === 'run-all') runAll();
+        else if (action === 'seed') seedFrom(sourceLang);
+        else if (action === 'share') share();
+        else if (action === 'close') close();
+    });
+
+    return {
+        open,
+        close,
+        isOpen,
+        runAll,
+        seedFrom,
+        share
+    };
+}
+
+export default {
+    seedTranslations,
+    normalizeOutput,
+    compareOutputs,
+    createMultiLangView
+};
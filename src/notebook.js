@@ -0,0 +1,491 @@
+/**
+ * Notebook mode
+ * - Ordered code cells (each with its own language) and markdown cells
+ * - Run a single cell or the whole notebook top to bottom
+ * - Markdown rendering and export to a .md file with outputs embedded
+ *
+ * The view is a thin layer over the same pieces the box uses: the caller
+ * passes in its evaluate function, highlightCode and keyboard handlers.
+ */
+
+// Languages a code cell can be in; cells in any other fall back to the first
+export const notebookLanguages = ['apl', 'bqn', 'uiua', 'j', 'kap', 'tinyapl'];
+
+/**
+ * Create a notebook object
+ * @param {Array} cells - Initial cells ({type: 'code', lang, code} or {type: 'markdown', text})
+ */
+export function createNotebook(cells = []) {
+    return { cells: cells.map(normalizeCell).filter(Boolean) };
+}
+
+/**
+ * Validate and copy a cell (drops unknown fields, e.g. from a permalink)
+ */
+export function normalizeCell(cell) {
+    if (!cell || typeof cell !== 'object') return null;
+    if (cell.type === 'markdown') {
+        return { type: 'markdown', text: String(cell.text || '') };
+    }
+    if (cell.type === 'code' && typeof cell.lang === 'string') {
+        const known = notebookLanguages.includes(cell.lang);
+        const normalized = { type: 'code', lang: known ? cell.lang : notebookLanguages[0], code: String(cell.code || '') };
+        // Output of another language's code would be misleading
+        if (known && typeof cell.output === 'string') {
+            normalized.output = cell.output;
+            normalized.success = cell.success !== false;
+        }
+        return normalized;
+    }
+    return null;
+}
+
+function escapeHtml(text) {
+    return text
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;')
+        .replace(/'/g, '&#039;');
+}
+
+/**
+ * Inline markdown: `code`, **bold**, *italic*, [links](https://...)
+ * Input is raw text; everything is escaped before markup is added.
+ */
+function renderInline(text) {
+    const codeSpans = [];
+    let html = escapeHtml(text).replace(/`([^`]+)`/g, (match, code) => {
+        codeSpans.push(`<code>${code}</code>`);
+        return `\u0000${codeSpans.length - 1}\u0000`;
+    });
+
+    html = html
+        .replace(/\*\*([^*]+)\*\*/g, '<strong>$1</strong>')
+        .replace(/(^|[^*])\*([^*\s][^*]*)\*/g, '$1<em>$2</em>')
+        .replace(/\[([^\]]+)\]\((https?:\/\/[^\s)]+)\)/g, '<a href="$2" target="_blank" rel="noopener">$1</a>');
+
+    return html.replace(/\u0000(\d+)\u0000/g, (match, index) => codeSpans[Number(index)]);
+}
+
+/**
+ * Render a markdown cell to HTML.
+ * Supports headings, paragraphs, lists, block quotes, rules, fenced code
+ * (highlighted when the fence names a language) and inline markup.
+ * @param {string} text - Markdown source
+ * @param {Function} [highlight] - highlightCode(code, lang), used for fenced code
+ * @returns {string} - Safe HTML
+ */
+export function renderMarkdown(text, highlight = null) {
+    const lines = text.replace(/\r/g, '').split('\n');
+    const blocks = [];
+    let paragraph = [];
+    let list = null;
+
+    const flushParagraph = () => {
+        if (paragraph.length) blocks.push(`<p>${renderInline(paragraph.join(' '))}</p>`);
+        paragraph = [];
+    };
+    const flushList = () => {
+        if (list) blocks.push(`<${list.tag}>${list.items.map(item => `<li>${renderInline(item)}</li>`).join('')}</${list.tag}>`);
+        list = null;
+    };
+
+    for (let i = 0; i < lines.length; i++) {
+        const line = lines[i];
+        let match;
+
+        if ((match = line.match(/^\s*(`{3,})\s*(\S*)\s*$/))) {
+            flushParagraph();
+            flushList();
+            const fence = match[1];
+            const lang = match[2];
+            const code = [];
+            for (i++; i < lines.length && !lines[i].trim().startsWith(fence); i++) {
+                code.push(lines[i]);
+            }
+            const source = code.join('\n');
+            const body = highlight && lang ? highlight(source, lang) : escapeHtml(source);
+            blocks.push(`<pre class="notebook-md-code ${escapeHtml(lang)}">${body}</pre>`);
+        } else if ((match = line.match(/^(#{1,6})\s+(.*)$/))) {
+            flushParagraph();
+            flushList();
+            blocks.push(`<h${match[1].length}>${renderInline(match[2])}</h${match[1].length}>`);
+        } else if (/^\s*([-*_])(\s*\1){2,}\s*$/.test(line)) {
+            flushParagraph();
+            flushList();
+            blocks.push('<hr>');
+        } else if ((match = line.match(/^\s*>\s?(.*)$/))) {
+            flushParagraph();
+            flushList();
+            blocks.push(`<blockquote>${renderInline(match[1])}</blockquote>`);
+        } else if ((match = line.match(/^\s*(?:([-*+])|(\d+)[.)])\s+(.*)$/))) {
+            flushParagraph();
+            const tag = match[1] ? 'ul' : 'ol';
+            if (list && list.tag !== tag) flushList();
+            if (!list) list = { tag, items: [] };
+            list.items.push(match[3]);
+        } else if (!line.trim()) {
+            flushParagraph();
+            flushList();
+        } else {
+            flushList();
+            paragraph.push(line.trim());
+        }
+    }
+    flushParagraph();
+    flushList();
+
+    return blocks.join('\n');
+}
+
+// Fence long enough not to clash with backticks inside the content
+function fenceFor(text) {
+    const longest = Math.max(2, ...(text.match(/`+/g) || []).map(run => run.length));
+    return '`'.repeat(longest + 1);
+}
+
+/**
+ * Export a notebook as markdown: markdown cells verbatim, code cells as
+ * fenced blocks tagged with their language, followed by their output.
+ * @param {object} notebook - Notebook from createNotebook
+ * @param {object} [options] - { title }
+ * @returns {string} - Markdown document
+ */
+export function notebookToMarkdown(notebook, options = {}) {
+    const parts = [];
+    if (options.title) parts.push(`# ${options.title}`);
+
+    for (const cell of notebook.cells) {
+        if (cell.type === 'markdown') {
+            if (cell.text.trim()) parts.push(cell.text.trim());
+            continue;
+        }
+        const codeFence = fenceFor(cell.code);
+        parts.push(`${codeFence}${cell.lang}\n${cell.code}\n${codeFence}`);
+        if (cell.output) {
+            const outputFence = fenceFor(cell.output);
+            parts.push(`${outputFence}${cell.success === false ? 'error' : 'output'}\n${cell.output}\n${outputFence}`);
+        }
+    }
+
+    return parts.join('\n\n') + '\n';
+}
+
+/**
+ * Create the notebook view manager
+ * @param {object} elements - { screen, cells, toolbar } DOM elements
+ * @param {object} options
+ * @param {object} options.languages - Language configs by id ({ name, fontClass })
+ * @param {Array<string>} options.languageOrder - Languages offered for code cells
+ * @param {Function} options.evaluate - async (lang, code) => { success, output, formatted?, outputHtml? }
+ * @param {Function} options.resetSessions - async (langs) called before running all cells
+ * @param {Function} options.highlightCode - (code, lang) => HTML
+ * @param {Function} options.createKeyboardHandler - (element, lang) => cleanup, or null for no keymap
+ * @param {Function} options.renderOutput - (element, lang, result) fills an output element
+ * @param {Function} options.onSave - (notebook) save as permalink
+ * @param {Function} options.onClose - called when the view closes
+ * @returns {object} - Manager API
+ */
+export function createNotebookView(elements, options) {
+    const { screen, cells: cellsElement, toolbar } = elements;
+    let notebook = createNotebook();
+    let running = false;
+    // Per-cell keyboard handler cleanups, keyed by the cell object
+    const keyboardCleanups = new Map();
+
+    function isOpen() {
+        return screen.classList.contains('show');
+    }
+
+    function open(newNotebook) {
+        if (newNotebook) notebook = createNotebook(newNotebook.cells);
+        screen.classList.add('show');
+        render();
+        focusCell(0);
+    }
+
+    function close() {
+        screen.classList.remove('show');
+        if (options.onClose) options.onClose();
+    }
+
+    function getNotebook() {
+        return notebook;
+    }
+
+    function addCell(type, index = notebook.cells.length, lang = null) {
+        const previousCode = [...notebook.cells.slice(0, index)].reverse().find(c => c.type === 'code');
+        const cell = type === 'markdown'
+            ? { type: 'markdown', text: '', editing: true }
+            : { type: 'code', lang: lang || (previousCode ? previousCode.lang : options.languageOrder[0]), code: '' };
+        notebook.cells.splice(index, 0, cell);
+        render();
+        focusCell(index);
+        return cell;
+    }
+
+    function removeCell(index) {
+        notebook.cells.splice(index, 1);
+        render();
+    }
+
+    function moveCell(index, direction) {
+        const target = index + direction;
+        if (target < 0 || target >= notebook.cells.length) return;
+        const [cell] = notebook.cells.splice(index, 1);
+        notebook.cells.splice(target, 0, cell);
+        render();
+        focusCell(target);
+    }
+
+    function focusCell(index) {
+        const cellElement = cellsElement.children[index];
+        const editor = cellElement && cellElement.querySelector('textarea');
+        if (!editor) return;
+        // Code editors are hidden behind the highlighted view until edited
+        cellElement.classList.add('editing');
+        editor.focus();
+    }
+
+    async function runCell(index) {
+        const cell = notebook.cells[index];
+        if (!cell) return;
+        if (cell.type === 'markdown') {
+            cell.editing = false;
+            render();
+            return;
+        }
+        if (!cell.code.trim()) {
+            delete cell.output;
+            render();
+            return;
+        }
+
+        const cellElement = cellsElement.children[index];
+        if (cellElement) cellElement.classList.add('running');
+
+        let result;
+        try {
+            result = await options.evaluate(cell.lang, cell.code.trim());
+        } catch (error) {
+            result = { success: false, output: error.message || String(error) };
+        }
+
+        // Uiua formats code as it evaluates
+        if (result.formatted && result.formatted.trim() !== cell.code.trim()) {
+            cell.code = result.formatted.trim();
+        }
+        cell.output = result.output || '';
+        cell.success = result.success;
+        cell.result = result;
+        render();
+    }
+
+    async function runAll() {
+        if (running) return;
+        running = true;
+        try {
+            // Start every language used from a clean session so the notebook is reproducible
+            const langs = [...new Set(notebook.cells.filter(c => c.type === 'code').map(c => c.lang))];
+            await options.resetSessions(langs);
+            for (let i = 0; i < notebook.cells.length; i++) {
+                await runCell(i);
+            }
+        } finally {
+            running = false;
+        }
+    }
+
+    // Cells as stored in permalinks/exports (no view state)
+    function serialize() {
+        return { cells: notebook.cells.map(normalizeCell).filter(Boolean) };
+    }
+
+    function createButton(label, title, onClick) {
+        const button = document.createElement('button');
+        button.className = 'notebook-button';
+        button.textContent = label;
+        button.title = title;
+        button.addEventListener('click', onClick);
+        return button;
+    }
+
+    function renderCellBar(cell, index) {
+        const bar = document.createElement('div');
+        bar.className = 'notebook-cell-bar';
+
+        if (cell.type === 'code') {
+            const select = document.createElement('select');
+            select.className = 'notebook-lang-select';
+            for (const lang of options.languageOrder) {
+                const option = document.createElement('option');
+                option.value = lang;
+                option.textContent = options.languages[lang].name;
+                option.selected = lang === cell.lang;
+                select.appendChild(option);
+            }
+            select.addEventListener('change', () => {
+                cell.lang = select.value;
+                delete cell.output;
+                render();
+            });
+            bar.appendChild(select);
+            bar.appendChild(createButton('▶', 'Run cell (shift+enter)', () => runCell(index)));
+        } else {
+            const label = document.createElement('span');
+            label.className = 'notebook-cell-type';
+            label.textContent = 'markdown';
+            bar.appendChild(label);
+            bar.appendChild(createButton(cell.editing ? '✓' : '✎', cell.editing ? 'Render (shift+enter)' : 'Edit', () => {
+                cell.editing = !cell.editing;
+                render();
+                if (cell.editing) focusCell(index);
+            }));
+        }
+
+        const spacer = document.createElement('span');
+        spacer.className = 'notebook-spacer';
+        bar.appendChild(spacer);
+        bar.appendChild(createButton('↑', 'Move up', () => moveCell(index, -1)));
+        bar.appendChild(createButton('↓', 'Move down', () => moveCell(index, 1)));
+        bar.appendChild(createButton('+', 'Insert code cell below', () => addCell('code', index + 1)));
+        bar.appendChild(createButton('×', 'Delete cell', () => removeCell(index)));
+        return bar;
+    }
+
+    function fontClass(lang) {
+        const config = options.languages[lang];
+        return config ? config.fontClass : '';
+    }
+
+    function renderEditor(cell, index) {
+        const editor = document.createElement('textarea');
+        editor.className = `notebook-editor ${cell.type === 'code' ? fontClass(cell.lang) : ''}`;
+        editor.value = cell.type === 'code' ? cell.code : cell.text;
+        editor.spellcheck = false;
+        editor.rows = Math.max(1, editor.value.split('\n').length);
+
+        editor.addEventListener('input', () => {
+            if (cell.type === 'code') cell.code = editor.value;
+            else cell.text = editor.value;
+            editor.rows = Math.max(1, editor.value.split('\n').length);
+        });
+
+        editor.addEventListener('keydown', (e) => {
+            // Shift+Enter: run (or render) and move to the next cell; Ctrl+Enter: run in place
+            if (e.key === 'Enter' && (e.shiftKey || e.ctrlKey)) {
+                e.preventDefault();
+                e.stopPropagation();
+                const next = index + 1;
+                runCell(index).then(() => {
+                    if (!e.shiftKey) focusCell(index);
+                    else if (next >= notebook.cells.length) addCell('code', next);
+                    else focusCell(next);
+                });
+            }
+        });
+
+        if (cell.type === 'code' && options.createKeyboardHandler) {
+            const cleanup = options.createKeyboardHandler(editor, cell.lang);
+            if (cleanup) keyboardCleanups.set(cell, cleanup);
+        }
+        return editor;
+    }
+
+    function renderCell(cell, index) {
+        const cellElement = document.createElement('div');
+        cellElement.className = `notebook-cell ${cell.type}`;
+        cellElement.appendChild(renderCellBar(cell, index));
+
+        if (cell.type === 'markdown' && !cell.editing) {
+            const view = document.createElement('div');
+            view.className = 'notebook-markdown';
+            view.innerHTML = cell.text.trim()
+                ? renderMarkdown(cell.text, options.highlightCode)
+                : '<p class="notebook-placeholder">empty markdown cell</p>';
+            view.addEventListener('dblclick', () => {
+                cell.editing = true;
+                render();
+                focusCell(index);
+            });
+            cellElement.appendChild(view);
+            return cellElement;
+        }
+
+        const editor = renderEditor(cell, index);
+        cellElement.appendChild(editor);
+
+        if (cell.type === 'code') {
+            // Highlighted view while the editor isn't focused
+            const view = document.createElement('pre');
+            view.className = `notebook-code-view ${fontClass(cell.lang)}`;
+            view.innerHTML = cell.code ? options.highlightCode(cell.code, cell.lang) : '<span class="notebook-placeholder">code</span>';
+            view.addEventListener('click', () => focusCell(index));
+            editor.addEventListener('blur', () => {
+                cellElement.classList.remove('editing');
+                view.innerHTML = cell.code ? options.highlightCode(cell.code, cell.lang) : '<span class="notebook-placeholder">code</span>';
+            });
+            cellElement.appendChild(view);
+
+            if (cell.output !== undefined) {
+                const outputElement = document.createElement('pre');
+                outputElement.className = `notebook-output ${fontClass(cell.lang)} ${cell.success === false ? 'error' : ''}`;
+                options.renderOutput(outputElement, cell.lang, cell.result || { success: cell.success, output: cell.output });
+                cellElement.appendChild(outputElement);
+            }
+        } else {
+            cellElement.classList.add('editing');
+        }
+
+        return cellElement;
+    }
+
+    function render() {
+        for (const cleanup of keyboardCleanups.values()) cleanup();
+        keyboardCleanups.clear();
+
+        cellsElement.innerHTML = '';
+        notebook.cells.forEach((cell, index) => cellsElement.appendChild(renderCell(cell, index)));
+    }
+
+    toolbar.addEventListener('click', (e) => {
+        const action = e.target.closest('[data-action]')?.dataset.action;
+        if (action === 'add-code') addCell('code');
+        else if (action === 'add-markdown') addCell('markdown');
+        else if (action === 'run-all') runAll();
+        else if (action === 'save') options.onSave(serialize());
+        else if (action === 'export') exportMarkdown();
+        else if (action === 'close') close();
+    });
+
+    function exportMarkdown() {
+        const blob = new Blob([notebookToMarkdown(serialize())], { type: 'text/markdown' });
+        const link = document.createElement('a');
+        link.href = URL.createObjectURL(blob);
+        link.download = 'notebook.md';
+        link.click();
+        setTimeout(() => URL.revokeObjectURL(link.href), 1000);
+    }
+
+    return {
+        open,
+        close,
+        isOpen,
+        getNotebook,
+        serialize,
+        addCell,
+        runCell,
+        runAll,
+        exportMarkdown
+    };
+}
+
+export default {
+    notebookLanguages,
+    createNotebook,
+    normalizeCell,
+    renderMarkdown,
+    notebookToMarkdown,
+    createNotebookView
+};
@@ -0,0 +1,349 @@
+/**
+ * Plots
+ * Draws numeric results as pictures next to their text output:
+ *   line, bar - a vector
+ *   heatmap   - a matrix, coloured from its smallest to its largest value
+ *   image     - a matrix of greyscale values, or a rank 3 array whose last axis
+ *               holds grey+alpha, RGB or RGBA (values 0-1, or 0-255)
+ * autoPlotMode() picks one from the value's shape; any applicable mode can be
+ * asked for instead (plotModes()).
+ *
+ * Also draws the media Uiua results carry (see runtimes.js): images, GIFs as
+ * looping animations and audio as a player.
+ *
+ * Values are in the array-display.js model. Charts are SVG; images are PNG
+ * data URLs drawn on a canvas, so this module needs a DOM. Styles are the
+ * plot-* classes in theme.css.
+ */
+
+export const PLOT_MODES = ['line', 'bar', 'heatmap', 'image'];
+
+// Larger arrays are not plotted
+export const MAX_POINTS = 100000;
+
+const CHART_WIDTH = 480;
+const CHART_HEIGHT = 200;
+const CHART_MARGIN = { top: 10, right: 10, bottom: 20, left: 50 };
+
+// Images are scaled up by whole pixels to about this size
+const IMAGE_SIZE = 256;
+
+// Heatmap colours, from the smallest value to the largest
+const HEATMAP_STOPS = [
+    [68, 1, 84], [59, 82, 139], [33, 145, 140], [94, 201, 98], [253, 231, 37]
+];
+
+// ============================================================================
+// Numeric arrays
+// ============================================================================
+
+// A number as the languages print it: ¯ or J's _ for negatives, ∞, and
+// rationals written 1r3 (J) or 1/3; null for anything else (complex numbers)
+function parseNumber(text) {
+    const t = text.replace(/¯/g, '-');
+    if (/^_+$/.test(t)) return t === '_' ? Infinity : -Infinity;
+    const normalized = t.replace(/_/g, '-').replace(/∞/g, 'Infinity');
+    const rational = /^(-?\d+)[r/](\d+)$/.exec(normalized);
+    if (rational) return Number(rational[1]) / Number(rational[2]);
+    const number = Number(normalized);
+    return normalized !== '' && !Number.isNaN(number) ? number : null;
+}
+
+/**
+ * A simple numeric array: { shape, data } with data in ravel order
+ * @param {object} value - Value in the array-display.js model
+ * @returns {object|null} - Null for scalars and anything not all numbers
+ */
+export function numericArray(value) {
+    if (!value || value.kind !== 'array' || value.shape.length === 0) return null;
+    if (value.items.length > MAX_POINTS) return null;
+    const data = [];
+    for (const item of value.items) {
+        const number = item.kind === 'number' ? parseNumber(item.text) : null;
+        if (number === null) return null;
+        data.push(number);
+    }
+    return { shape: value.shape, data };
+}
+
+// A numeric array from nested JS arrays (Uiua's media)
+function nestedArray(nested) {
+    const shape = [];
+    for (let level = nested; Array.isArray(level); level = level[0]) shape.push(level.length);
+    const data = (Array.isArray(nested) ? nested.flat(Infinity) : [nested]).map(Number);
+    return { shape, data };
+}
+
+function range(data) {
+    let min = Infinity;
+    let max = -Infinity;
+    for (const x of data) {
+        if (!Number.isFinite(x)) continue;
+        if (x < min) min = x;
+        if (x > max) max = x;
+    }
+    return min <= max ? { min, max } : { min: 0, max: 0 };
+}
+
+// ============================================================================
+// Modes
+// ============================================================================
+
+// Whether an array can be drawn as an image: a matrix, or channels on the last axis
+function isImage({ shape, data }) {
+    const { min, max } = range(data);
+    if (min < 0 || max > 255) return false;
+    return shape.length === 2 || (shape.length === 3 && shape[2] >= 2 && shape[2] <= 4);
+}
+
+/**
+ * Modes a value can be plotted in
+ * @param {object} value - Value in the array-display.js model
+ * @returns {string[]} - Some of PLOT_MODES, best first
+ */
+export function plotModes(value) {
+    const array = numericArray(value);
+    if (!array || array.data.length === 0) return [];
+    const { shape } = array;
+    if (shape.length === 1) return array.data.length > 1 ? ['line', 'bar'] : [];
+    const modes = [];
+    if (isImage(array)) modes.push('image');
+    if (shape.length === 2) modes.push('heatmap');
+    // Matrices are greyscale images only when they look like one (0-1 values)
+    if (shape.length === 2 && range(array.data).max > 1) modes.reverse();
+    return modes;
+}
+
+/**
+ * The mode a value is plotted in when none is asked for
+ * @returns {string|null}
+ */
+export function autoPlotMode(value) {
+    return plotModes(value)[0] || null;
+}
+
+// ============================================================================
+// Charts
+// ============================================================================
+
+function formatTick(x) {
+    return Number.isInteger(x) ? String(x) : x.toPrecision(3).replace(/\.?0+$/, '');
+}
+
+// Axes with the range of the data on the left, the length underneath
+function chartFrame(min, max, length, y) {
+    const { top, right, bottom, left } = CHART_MARGIN;
+    const x0 = left;
+    const x1 = CHART_WIDTH - right;
+    const y1 = CHART_HEIGHT - bottom;
+    let svg = `<line class="plot-axis" x1="${x0}" y1="${top}" x2="${x0}" y2="${y1}"/>`;
+    svg += `<line class="plot-axis" x1="${x0}" y1="${y1}" x2="${x1}" y2="${y1}"/>`;
+    if (min < 0 && max > 0) svg += `<line class="plot-zero" x1="${x0}" y1="${y(0)}" x2="${x1}" y2="${y(0)}"/>`;
+    svg += `<text class="plot-label" x="${x0 - 4}" y="${top + 4}" text-anchor="end">${formatTick(max)}</text>`;
+    svg += `<text class="plot-label" x="${x0 - 4}" y="${y1}" text-anchor="end">${formatTick(min)}</text>`;
+    svg += `<text class="plot-label" x="${x0}" y="${CHART_HEIGHT - 4}">0</text>`;
+    svg += `<text class="plot-label" x="${x1}" y="${CHART_HEIGHT - 4}" text-anchor="end">${length - 1}</text>`;
+    return svg;
+}
+
+function chartSvg(data, mode) {
+    const { top, right, bottom, left } = CHART_MARGIN;
+    let { min, max } = range(data);
+    // Bars grow from zero
+    if (mode === 'bar') {
+        min = Math.min(min, 0);
+        max = Math.max(max, 0);
+    }
+    if (min === max) {
+        min -= 1;
+        max += 1;
+    }
+    const width = CHART_WIDTH - left - right;
+    const height = CHART_HEIGHT - top - bottom;
+    const clamp = (v) => Math.min(max, Math.max(min, v));
+    const y = (v) => +(top + (max - clamp(v)) / (max - min) * height).toFixed(2);
+
+    let marks = '';
+    if (mode === 'line') {
+        const step = width / (data.length - 1);
+        const points = data.map((v, i) => `${+(left + i * step).toFixed(2)},${y(v)}`);
+        marks = `<polyline class="plot-line" points="${points.join(' ')}"/>`;
+    } else {
+        const step = width / data.length;
+        const gap = step > 4 ? 1 : 0;
+        data.forEach((v, i) => {
+            const top0 = Math.min(y(v), y(0));
+            const barHeight = Math.abs(y(v) - y(0));
+            marks += `<rect class="plot-bar" x="${+(left + i * step).toFixed(2)}" y="${top0}" width="${+(step - gap).toFixed(2)}" height="${+barHeight.toFixed(2)}"/>`;
+        });
+    }
+    return `<svg class="plot-chart" viewBox="0 0 ${CHART_WIDTH} ${CHART_HEIGHT}" width="${CHART_WIDTH}" height="${CHART_HEIGHT}">`
+        + chartFrame(min, max, data.length, y) + marks + '</svg>';
+}
+
+// ============================================================================
+// Images
+// ============================================================================
+
+// RGBA bytes for an image array (greyscale, grey+alpha, RGB or RGBA)
+function imagePixels({ shape, data }) {
+    const [height, width] = shape;
+    const channels = shape[2] || 1;
+    const unit = range(data).max > 1 ? 255 : 1;
+    const byte = (v) => Math.round(Math.min(1, Math.max(0, v / unit)) * 255);
+    const pixels = new Uint8ClampedArray(width * height * 4);
+    for (let p = 0; p < width * height; p++) {
+        const c = data.slice(p * channels, (p + 1) * channels).map(byte);
+        const [r, g, b] = channels >= 3 ? c : [c[0], c[0], c[0]];
+        const a = channels === 2 ? c[1] : channels === 4 ? c[3] : 255;
+        pixels.set([r, g, b, a], p * 4);
+    }
+    return pixels;
+}
+
+function heatmapPixels({ shape, data }) {
+    const { min, max } = range(data);
+    const pixels = new Uint8ClampedArray(data.length * 4);
+    data.forEach((v, p) => {
+        const t = max > min ? (Math.min(max, Math.max(min, v)) - min) / (max - min) : 0.5;
+        const at = t * (HEATMAP_STOPS.length - 1);
+        const i = Math.min(HEATMAP_STOPS.length - 2, Math.floor(at));
+        const f = at - i;
+        const color = HEATMAP_STOPS[i].map((c, k) => Math.round(c + (HEATMAP_STOPS[i + 1][k] - c) * f));
+        pixels.set([...color, 255], p * 4);
+    });
+    return pixels;
+}
+
+function pngUrl(width, height, pixels) {
+    const canvas = document.createElement('canvas');
+    canvas.width = width;
+    canvas.height = height;
+    canvas.getContext('2d').putImageData(new ImageData(pixels, width, height), 0, 0);
+    return canvas.toDataURL('image/png');
+}
+
+// Display size: scaled up by a whole factor, never down
+function displaySize(width, height) {
+    const factor = Math.max(1, Math.floor(IMAGE_SIZE / Math.max(width, height)));
+    return [width * factor, height * factor];
+}
+
+function imageHtml(array, pixels) {
+    const [height, width] = array.shape;
+    if (!width || !height) return '';
+    const [w, h] = displaySize(width, height);
+    return `<img class="plot-image" src="${pngUrl(width, height, pixels)}" width="${w}" height="${h}" alt="${height}×${width} image">`;
+}
+
+// Frames shown one after another, looping, as an SVG animation
+function animationHtml(frames, fps) {
+    const [height, width] = frames[0].shape;
+    if (!width || !height) return '';
+    const [w, h] = displaySize(width, height);
+    const duration = frames.length / (fps > 0 ? fps : 1);
+    const images = frames.map((frame, i) => {
+        const href = pngUrl(width, height, imagePixels(frame));
+        const start = i / frames.length;
+        const end = (i + 1) / frames.length;
+        const [values, keyTimes] = i === 0 ? ['visible;hidden', `0;${end}`] : ['hidden;visible;hidden', `0;${start};${end}`];
+        const animate = frames.length > 1
+            ? `<animate attributeName="visibility" values="${values}" keyTimes="${keyTimes}" calcMode="discrete" dur="${duration}s" repeatCount="indefinite"/>`
+            : '';
+        return `<image href="${href}" width="${width}" height="${height}" style="image-rendering: pixelated">${animate}</image>`;
+    });
+    return `<svg class="plot-image" viewBox="0 0 ${width} ${height}" width="${w}" height="${h}">${images.join('')}</svg>`;
+}
+
+// ============================================================================
+// Audio
+// ============================================================================
+
+// 16-bit PCM WAV data URL; a rank 2 array holds a channel per row, or per
+// column when it has more rows than columns
+function wavUrl(array, sampleRate) {
+    const [rows, columns] = array.shape;
+    const byColumn = array.shape.length === 2 && rows > columns;
+    const channels = array.shape.length === 2 ? Math.min(rows, columns) : 1;
+    const length = array.shape.length === 2 ? Math.max(rows, columns) : rows;
+    const sample = (c, s) => array.data[byColumn ? s * channels + c : c * length + s];
+    const bytes = new DataView(new ArrayBuffer(44 + length * channels * 2));
+    const text = (offset, s) => [...s].forEach((c, i) => bytes.setUint8(offset + i, c.charCodeAt(0)));
+    text(0, 'RIFF');
+    bytes.setUint32(4, 36 + length * channels * 2, true);
+    text(8, 'WAVEfmt ');
+    bytes.setUint32(16, 16, true);
+    bytes.setUint16(20, 1, true);
+    bytes.setUint16(22, channels, true);
+    bytes.setUint32(24, sampleRate, true);
+    bytes.setUint32(28, sampleRate * channels * 2, true);
+    bytes.setUint16(32, channels * 2, true);
+    bytes.setUint16(34, 16, true);
+    text(36, 'data');
+    bytes.setUint32(40, length * channels * 2, true);
+    for (let s = 0; s < length; s++) {
+        for (let c = 0; c < channels; c++) {
+            const v = Math.max(-1, Math.min(1, sample(c, s) || 0));
+            bytes.setInt16(44 + (s * channels + c) * 2, Math.round(v * 32767), true);
+        }
+    }
+    let binary = '';
+    const raw = new Uint8Array(bytes.buffer);
+    for (let i = 0; i < raw.length; i += 0x8000) {
+        binary += String.fromCharCode(...raw.subarray(i, i + 0x8000));
+    }
+    return `data:audio/wav;base64,${btoa(binary)}`;
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/**
+ * Draw a value
+ * @param {object} value - Value in the array-display.js model
+ * @param {string} [mode] - One of PLOT_MODES; the automatic mode when missing
+ *   or not applicable to the value
+ * @returns {string|null} - HTML, or null when the value can't be plotted
+ */
+export function renderPlotHtml(value, mode) {
+    const modes = plotModes(value);
+    if (!modes.length) return null;
+    if (!modes.includes(mode)) mode = modes[0];
+    const array = numericArray(value);
+    let html;
+    if (mode === 'line' || mode === 'bar') html = chartSvg(array.data, mode);
+    else if (mode === 'heatmap') html = imageHtml(array, heatmapPixels(array));
+    else html = imageHtml(array, imagePixels(array));
+    return `<div class="plot" data-mode="${mode}">${html}</div>`;
+}
+
+/**
+ * Draw the media of a Uiua result
+ * @param {object[]} media - The result's `media` (see runtimes.js)
+ * @returns {string} - HTML, empty when there are none
+ */
+export function renderMediaHtml(media) {
+    const parts = (media || []).map(item => {
+        if (item.kind === 'audio') {
+            return `<audio class="plot-audio" controls src="${wavUrl(nestedArray(item.samples), item.sampleRate)}"></audio>`;
+        }
+        if (item.kind === 'gif') {
+            const frames = (item.frames || []).map(nestedArray).filter(isImage);
+            return frames.length ? animationHtml(frames, item.fps) : '';
+        }
+        const array = nestedArray(item.data);
+        return isImage(array) ? imageHtml(array, imagePixels(array)) : '';
+    }).filter(Boolean);
+    return parts.length ? `<div class="plot plot-media">${parts.join('')}</div>` : '';
+}
+
+export default {
+    PLOT_MODES,
+    MAX_POINTS,
+    numericArray,
+    plotModes,
+    autoPlotMode,
+    renderPlotHtml,
+    renderMediaHtml
+};
@@ -0,0 +1,260 @@
+/**
+ * Cross-language primitive index
+ *
+ * Joins the glyph docs of every language (src/*-docs.js) into one index of
+ * primitives keyed by what they do, so a name like "reverse" or "grade up"
+ * finds ⌽ in APL/BQN/Kap/TinyAPL, ⇌ in Uiua and |. in J.
+ *
+ * Primitives are joined when their documented names match (after lowercasing
+ * and dropping punctuation), and when primitiveGroups in primitive-translate.js
+ * says they correspond even though the names differ (APL "Index Generator",
+ * BQN "Range", J "Integers").
+ */
+
+import { aplGlyphDocs } from './apl-docs.js';
+import { bqnGlyphDocs } from './bqn-docs.js';
+import { jGlyphDocs } from './j-docs.js';
+import { kapGlyphDocs } from './kap-docs.js';
+import { tinyaplGlyphDocs } from './tinyapl-docs.js';
+import { uiuaGlyphDocs } from './uiua-docs.js';
+import { primitiveGroups } from './primitive-translate.js';
+
+export const indexLanguages = ['apl', 'bqn', 'uiua', 'j', 'kap', 'tinyapl'];
+
+const glyphDocs = {
+    apl: aplGlyphDocs,
+    bqn: bqnGlyphDocs,
+    uiua: uiuaGlyphDocs,
+    j: jGlyphDocs,
+    kap: kapGlyphDocs,
+    tinyapl: tinyaplGlyphDocs
+};
+
+// primitiveGroups pair glyphs by their monadic meaning, except these
+const dyadicGroups = new Set(['multiply']);
+
+/**
+ * Lowercase a name and drop punctuation, for matching names across languages
+ */
+export function normalizeName(name) {
+    return name.toLowerCase().replace(/[^a-z0-9]+/g, ' ').trim();
+}
+
+// "gradeUp" -> "grade up"
+function humanizeKey(key) {
+    return key.replace(/([a-z])([A-Z])/g, '$1 $2').toLowerCase();
+}
+
+// Entries that file their one meaning under monad, though nothing applies them
+const ARGUMENTLESS_TYPES = new Set(['syntax', 'noun']);
+
+/**
+ * Arity label for one documented meaning of a glyph
+ *
+ * A documented monad or dyad says the arity whatever the entry's type (J's
+ * docs list /: as an "adverb"); the type is the label only for entries with
+ * neither, and for syntax and nouns.
+ * @param {object} entry - Glyph docs entry
+ * @param {string|null} valence - 'monad', 'dyad', or null for single-meaning entries
+ * @param {string} language - Language id
+ */
+function arityOf(entry, valence, language) {
+    if (!ARGUMENTLESS_TYPES.has(entry.type)) {
+        if (valence === 'monad') return 'monadic';
+        if (valence === 'dyad') return 'dyadic';
+    }
+    if (language === 'uiua') {
+        if (entry.type === 'modifier') return 'modifier';
+        const args = (entry.signature || '').match(/^(\d+)\s*→/);
+        if (!args) return entry.type;
+        return ['niladic', 'monadic', 'dyadic'][Number(args[1])] || `${args[1]} arguments`;
+    }
+    return entry.type;
+}
+
+/**
+ * Every documented meaning of every glyph in one language
+ * @returns {Array<{language, glyph, name, valence, arity}>}
+ */
+function languageRecords(language) {
+    const records = [];
+    for (const [glyph, entry] of Object.entries(glyphDocs[language])) {
+        const meanings = entry.overloads
+            ? entry.overloads.map(o => ({ name: o.name, valence: o.valence || null }))
+            : [
+                entry.monad && { name: entry.monad.name, valence: 'monad' },
+                entry.dyad && { name: entry.dyad.name, valence: 'dyad' },
+                entry.name && { name: entry.name, valence: null }
+            ].filter(Boolean);
+
+        for (const { name, valence } of meanings) {
+            if (!name) continue;
+            records.push({ language, glyph, name, valence, arity: arityOf(entry, valence, language) });
+        }
+    }
+    return records;
+}
+
+// Records belonging to a primitiveGroups entry (the right valence of each glyph)
+function groupRecords(groupKey, group, recordsByGlyph) {
+    const members = [];
+    for (const language of indexLanguages) {
+        const glyph = group[language];
+        if (!glyph) continue;
+        const records = recordsByGlyph.get(`${language} ${glyph}`) || [];
+        const valence = dyadicGroups.has(groupKey) ? 'dyad' : 'monad';
+        const matching = records.filter(r => r.valence === null || r.valence === valence);
+        // Glyphs with no docs entry still belong to the group
+        members.push(...(matching.length ? matching : [{
+            language, glyph, name: humanizeKey(groupKey), valence: null, arity: ''
+        }]));
+    }
+    return members;
+}
+
+/**
+ * Build the index
+ * @returns {Array<{key: string, name: string, names: Array<string>,
+ *   glyphs: Object<string, Array<{glyph, name, arity}>>}>} - One entry per
+ *   primitive meaning, with the glyphs for it in each language
+ */
+export function buildPrimitiveIndex() {
+    const records = indexLanguages.flatMap(languageRecords);
+    const recordsByGlyph = new Map();
+    for (const record of records) {
+        const key = `${record.language} ${record.glyph}`;
+        if (!recordsByGlyph.has(key)) recordsByGlyph.set(key, []);
+        recordsByGlyph.get(key).push(record);
+    }
+
+    const entries = new Map();       // concept key -> entry
+    const conceptByName = new Map(); // normalized name -> concept key
+    const placed = new Set();        // records already in a concept
+
+    function add(conceptKey, record) {
+        if (!entries.has(conceptKey)) entries.set(conceptKey, { key: conceptKey, names: new Set(), records: [] });
+        const entry = entries.get(conceptKey);
+        entry.records.push(record);
+        entry.names.add(normalizeName(record.name));
+        placed.add(record);
+    }
+
+    // Groups first, so differently named equivalents share one entry
+    for (const [groupKey, group] of Object.entries(primitiveGroups)) {
+        const conceptKey = `group:${groupKey}`;
+        for (const record of groupRecords(groupKey, group, recordsByGlyph)) add(conceptKey, record);
+        entries.get(conceptKey)?.names.add(humanizeKey(groupKey));
+        for (const name of entries.get(conceptKey)?.names || []) {
+            if (!conceptByName.has(name)) conceptByName.set(name, conceptKey);
+        }
+    }
+
+    for (const record of records) {
+        if (placed.has(record)) continue;
+        const name = normalizeName(record.name);
+        if (!name) continue;
+        // A name shared with a group only joins it if the group has no other
+        // glyph in this language (APL ⍪ "Table" is not the outer product ∘.)
+        let conceptKey = conceptByName.get(name) || `name:${name}`;
+        const grouped = entries.get(conceptKey);
+        if (conceptKey.startsWith('group:') &&
+            grouped.records.some(r => r.language === record.language && r.glyph !== record.glyph)) {
+            conceptKey = `name:${name}`;
+        } else {
+            conceptByName.set(name, conceptKey);
+        }
+        add(conceptKey, record);
+    }
+
+    return [...entries.values()].map(({ key, names, records: members }) => {
+        const glyphs = {};
+        for (const { language, glyph, name, arity } of members) {
+            glyphs[language] = glyphs[language] || [];
+            const existing = glyphs[language].find(g => g.glyph === glyph);
+            if (existing) {
+                if (arity && !existing.arity.split(' / ').includes(arity)) {
+                    existing.arity = existing.arity ? `${existing.arity} / ${arity}` : arity;
+                }
+            } else {
+                glyphs[language].push({ glyph, name, arity });
+            }
+        }
+        const name = key.startsWith('group:') ? humanizeKey(key.slice('group:'.length)) : commonName(members);
+        return { key, name, names: [...names], glyphs };
+    });
+}
+
+// The name most languages use for a concept
+function commonName(records) {
+    const counts = new Map();
+    for (const record of records) {
+        const name = normalizeName(record.name);
+        counts.set(name, (counts.get(name) || 0) + 1);
+    }
+    return [...counts.entries()].sort((a, b) => b[1] - a[1])[0][0];
+}
+
+let cachedIndex = null;
+
+/**
+ * The index, built on first use
+ */
+export function getPrimitiveIndex() {
+    if (!cachedIndex) cachedIndex = buildPrimitiveIndex();
+    return cachedIndex;
+}
+
+// How well a name matches a query (0 = no match)
+function nameScore(name, query) {
+    if (name === query) return 300;
+    if (name.startsWith(query)) return 200;
+    if (name.includes(` ${query}`)) return 150;
+    if (name.includes(query)) return 100;
+    return 0;
+}
+
+/**
+ * Search the index by name
+ * @param {string} query - Name or part of a name ("reverse", "grade up")
+ * @param {object} [options]
+ * @param {number} [options.limit=20] - Maximum number of results
+ * @returns {Array<{key, name, matchedName, score, glyphs}>} - Best matches first
+ */
+export function searchPrimitives(query, options = {}) {
+    const { limit = 20 } = options;
+    const normalized = normalizeName(query);
+    if (!normalized) return [];
+
+    const results = [];
+    for (const entry of getPrimitiveIndex()) {
+        let best = { score: 0, name: null };
+        for (const name of entry.names) {
+            const score = nameScore(name, normalized);
+            if (score > best.score) best = { score, name };
+        }
+        if (best.score === 0) continue;
+        // Prefer primitives more languages share
+        const score = best.score + Object.keys(entry.glyphs).length;
+        results.push({ ...entry, matchedName: best.name, score });
+    }
+    results.sort((a, b) => b.score - a.score || a.name.localeCompare(b.name));
+    return results.slice(0, limit);
+}
+
+/**
+ * Entries that contain a glyph in a language, for finding its equivalents
+ * @param {string} language - Language id
+ * @param {string} glyph - Glyph in that language
+ */
+export function findPrimitive(language, glyph) {
+    return getPrimitiveIndex().filter(entry => (entry.glyphs[language] || []).some(g => g.glyph === glyph));
+}
+
+export default {
+    indexLanguages,
+    normalizeName,
+    buildPrimitiveIndex,
+    getPrimitiveIndex,
+    searchPrimitives,
+    findPrimitive
+};
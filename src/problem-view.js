@@ -0,0 +1,411 @@
+/**
+ * Problem view: write and solve golf problems shared by permalink
+ * - Author mode: title, markdown statement and test cases (each case can be
+ *   hidden), published with POST /p { problem }
+ * - Solve mode: the statement, the visible cases as examples, and an editor
+ *   whose submissions are judged by the permalink server against every case
+ *
+ * Hidden cases never reach the browser (the server strips them, see
+ * publicProblem in src/golf.js), so solve mode only shows whether they passed.
+ */
+
+import { countCode, publicProblem } from './golf.js';
+import { renderMarkdown } from './notebook.js';
+
+/**
+ * Create the problem view manager
+ * @param {object} elements - { screen, toolbar, body, title } DOM elements
+ * @param {object} options
+ * @param {object} options.languages - Language configs by id ({ name, fontClass })
+ * @param {Array<string>} options.languageOrder - Languages offered, in order
+ * @param {Function} options.highlightCode - (code, lang) => HTML
+ * @param {Function} options.createKeyboardHandler - (element, lang) => cleanup, or null for no keymap
+ * @param {Function} options.submit - async (permalinkId, lang, code) => verdict ({ passed, chars, bytes, cases } or { error })
+ * @param {Function} options.publish - async (problem) => { id } or { error }
+ * @param {Function} options.getLanguage - () => language being edited in the box
+ * @param {Function} options.onClose - called when the view closes
+ * @returns {object} - Manager API
+ */
+export function createProblemView(elements, options) {
+    const { screen, toolbar, body, title } = elements;
+    const keyboardCleanups = [];
+    let mode = 'solve';
+    let problemId = null;
+    let problem = null;
+    let solveLang = null;
+    let drafts = {};
+    let submitting = false;
+    // Author mode state
+    let draft = null;
+
+    function isOpen() {
+        return screen.classList.contains('show');
+    }
+
+    function close() {
+        screen.classList.remove('show');
+        if (options.onClose) options.onClose();
+    }
+
+    function show(newMode) {
+        mode = newMode;
+        screen.dataset.mode = newMode;
+        title.textContent = newMode === 'author' ? 'new problem' : 'problem';
+        screen.classList.add('show');
+    }
+
+    function clearKeyboards() {
+        while (keyboardCleanups.length) keyboardCleanups.pop()();
+    }
+
+    function attachKeyboard(element, lang) {
+        if (!options.createKeyboardHandler) return;
+        const cleanup = options.createKeyboardHandler(element, lang);
+        if (cleanup) keyboardCleanups.push(cleanup);
+    }
+
+    function make(tag, className, text) {
+        const element = document.createElement(tag);
+        if (className) element.className = className;
+        if (text !== undefined) element.textContent = text;
+        return element;
+    }
+
+    function languageSelect(value, langs, onChange) {
+        const select = make('select', 'notebook-lang-select');
+        for (const lang of langs) {
+            const option = make('option', '', options.languages[lang].name);
+            option.value = lang;
+            select.appendChild(option);
+        }
+        select.value = value;
+        select.addEventListener('change', () => onChange(select.value));
+        return select;
+    }
+
+    function autosize(textarea) {
+        textarea.rows = Math.max(1, textarea.value.split('\n').length);
+    }
+
+    function formatInput(input) {
+        if (input === undefined) return '(whole program)';
+        return Array.isArray(input) ? input.join('\n') : input;
+    }
+
+    // ========================================
+    // Solve mode
+    // ========================================
+
+    /**
+     * Open a problem for solving
+     * @param {string} id - Permalink id submissions go to
+     * @param {object} newProblem - Problem as the server returns it (hidden cases reduced to { hidden: true })
+     */
+    function openSolve(id, newProblem) {
+        problemId = id;
+        problem = newProblem;
+        drafts = {};
+        const langs = problemLanguages();
+        const preferred = options.getLanguage();
+        solveLang = langs.includes(preferred) ? preferred : langs[0];
+        show('solve');
+        renderSolve();
+        body.querySelector('.problem-editor')?.focus();
+    }
+
+    function problemLanguages() {
+        return options.languageOrder.filter(lang => problem.tests[lang]);
+    }
+
+    function renderSolve(verdict = null) {
+        clearKeyboards();
+        body.innerHTML = '';
+        const config = options.languages[solveLang];
+        const cases = problem.tests[solveLang];
+
+        body.appendChild(make('h2', 'problem-heading', problem.title));
+        if (problem.statement.trim()) {
+            const statement = make('div', 'notebook-markdown problem-statement');
+            statement.innerHTML = renderMarkdown(problem.statement, options.highlightCode);
+            body.appendChild(statement);
+        }
+
+        const bar = make('div', 'notebook-cell-bar');
+        bar.appendChild(languageSelect(solveLang, problemLanguages(), (lang) => {
+            solveLang = lang;
+            renderSolve();
+            body.querySelector('.problem-editor').focus();
+        }));
+        bar.appendChild(make('span', 'notebook-spacer'));
+        const count = make('span', 'problem-count');
+        bar.appendChild(count);
+
+        const editor = make('textarea', `notebook-editor problem-editor ${config.fontClass}`);
+        editor.spellcheck = false;
+        editor.placeholder = 'solution';
+        editor.value = drafts[solveLang] || '';
+        autosize(editor);
+        const updateCount = () => {
+            const { chars, bytes } = countCode(editor.value, solveLang);
+            count.textContent = `${bytes} byte${bytes === 1 ? '' : 's'} · ${chars} char${chars === 1 ? '' : 's'}`;
+        };
+        editor.addEventListener('input', () => {
+            drafts[solveLang] = editor.value;
+            autosize(editor);
+            updateCount();
+        });
+        editor.addEventListener('keydown', (e) => {
+            // Enter submits (Shift+Enter inserts a newline, as in the main box)
+            if (e.key === 'Enter' && !e.shiftKey) {
+                e.preventDefault();
+                e.stopPropagation();
+                submit();
+            }
+        });
+        attachKeyboard(editor, solveLang);
+        updateCount();
+
+        const cell = make('div', 'notebook-cell code editing');
+        cell.append(bar, editor);
+        body.appendChild(cell);
+
+        // Examples: the visible cases
+        const examples = make('div', 'problem-cases');
+        cases.forEach((testCase, index) => {
+            const result = verdict && verdict.cases ? verdict.cases[index] : null;
+            const row = make('div', 'problem-case');
+            if (result) row.classList.add(result.passed ? 'passed' : 'failed');
+            const mark = make('span', 'problem-case-mark', result ? (result.passed ? '✓' : '✗') : '·');
+            row.appendChild(mark);
+
+            if (testCase.hidden) {
+                row.appendChild(make('span', 'problem-case-hidden', `case ${index + 1} (hidden)`));
+            } else {
+                const detail = make('div', 'problem-case-detail');
+                detail.appendChild(labelled('input', formatInput(testCase.input), config.fontClass));
+                detail.appendChild(labelled('expected', testCase.expected, config.fontClass));
+                if (result && !result.passed) detail.appendChild(labelled('got', result.output, config.fontClass));
+                row.appendChild(detail);
+            }
+            examples.appendChild(row);
+        });
+        body.appendChild(examples);
+
+        const status = make('div', 'multi-status problem-status');
+        if (submitting) {
+            status.textContent = 'judging…';
+        } else if (verdict && verdict.error) {
+            status.textContent = `✗ ${verdict.error}`;
+            status.classList.add('differ');
+        } else if (verdict) {
+            const passed = verdict.cases.filter(c => c.passed).length;
+            status.textContent = verdict.passed
+                ? `✓ all ${passed} cases passed in ${verdict.bytes} bytes`
+                : `✗ ${passed}/${verdict.cases.length} cases passed`;
+            status.classList.add(verdict.passed ? 'agree' : 'differ');
+        } else {
+            const hidden = cases.filter(c => c.hidden).length;
+            status.textContent = hidden ? `${hidden} of ${cases.length} cases are hidden` : '';
+        }
+        body.appendChild(status);
+    }
+
+    function labelled(label, text, fontClass) {
+        const line = make('div', 'problem-case-line');
+        line.appendChild(make('span', 'problem-case-label', label));
+        line.appendChild(make('pre', `problem-case-value ${fontClass}`, text));
+        return line;
+    }
+
+    async function submit() {
+        if (mode !== 'solve' || submitting) return;
+        const code = drafts[solveLang] || '';
+        if (!code.trim()) return;
+        submitting = true;
+        renderSolve();
+        let verdict;
+        try {
+            verdict = await options.submit(problemId, solveLang, code);
+        } catch (error) {
+            verdict = { error: error.message || String(error) };
+        }
+        submitting = false;
+        renderSolve(verdict);
+        body.querySelector('.problem-editor').focus();
+    }
+
+    // ========================================
+    // Author mode
+    // ========================================
+
+    /**
+     * Open the problem being written (a new one if there is none)
+     * @param {string} lang - Language of the first test case of a new problem
+     * @param {boolean} [fresh=false] - Discard the unpublished problem and start over
+     */
+    function openAuthor(lang, fresh = false) {
+        if (!draft || fresh) {
+            draft = {
+                title: '',
+                statement: '',
+                compare: 'exact',
+                cases: [{ lang, input: '', expected: '', hidden: false }]
+            };
+        }
+        show('author');
+        renderAuthor();
+        body.querySelector('.problem-title-input').focus();
+    }
+
+    function renderAuthor(message = null) {
+        clearKeyboards();
+        body.innerHTML = '';
+
+        const titleInput = make('input', 'problem-title-input');
+        titleInput.placeholder = 'Title';
+        titleInput.value = draft.title;
+        titleInput.addEventListener('input', () => { draft.title = titleInput.value; });
+        body.appendChild(titleInput);
+
+        const statement = make('textarea', 'notebook-editor problem-statement-input');
+        statement.placeholder = 'Statement (markdown)';
+        statement.value = draft.statement;
+        statement.rows = Math.max(4, draft.statement.split('\n').length);
+        statement.addEventListener('input', () => {
+            draft.statement = statement.value;
+            statement.rows = Math.max(4, statement.value.split('\n').length);
+        });
+        body.appendChild(statement);
+
+        const compareBar = make('div', 'notebook-cell-bar');
+        compareBar.appendChild(make('span', 'notebook-cell-type', 'compare outputs'));
+        const compare = make('select', 'notebook-lang-select');
+        for (const [value, label] of [['exact', 'exactly'], ['values', 'by value']]) {
+            const option = make('option', '', label);
+            option.value = value;
+            compare.appendChild(option);
+        }
+        compare.value = draft.compare;
+        compare.addEventListener('change', () => { draft.compare = compare.value; });
+        compareBar.appendChild(compare);
+        body.appendChild(compareBar);
+
+        const help = make('div', 'notebook-markdown problem-help');
+        help.innerHTML = renderMarkdown('Solutions are functions: a case with input `x` runs `(solution) x`. ' +
+            'An input on two lines is two arguments: `2` and `1‿2‿3` run `2 (solution) 1‿2‿3`. ' +
+            'Hidden cases are judged but never shown to solvers.');
+        body.appendChild(help);
+
+        const cases = make('div', 'problem-cases');
+        draft.cases.forEach((testCase, index) => cases.appendChild(renderAuthorCase(testCase, index)));
+        body.appendChild(cases);
+
+        const add = make('button', 'notebook-button', '+ case');
+        add.title = 'Add a test case';
+        add.addEventListener('click', () => {
+            const last = draft.cases[draft.cases.length - 1];
+            draft.cases.push({ lang: last ? last.lang : options.getLanguage(), input: '', expected: '', hidden: true });
+            renderAuthor();
+            body.querySelectorAll('.problem-case-input')[draft.cases.length - 1].focus();
+        });
+        body.appendChild(add);
+
+        if (message) {
+            const status = make('div', `multi-status problem-status ${message.error ? 'differ' : 'agree'}`, message.text);
+            body.appendChild(status);
+        }
+    }
+
+    function renderAuthorCase(testCase, index) {
+        const config = options.languages[testCase.lang];
+        const row = make('div', 'notebook-cell code editing problem-author-case');
+
+        const bar = make('div', 'notebook-cell-bar');
+        bar.appendChild(languageSelect(testCase.lang, options.languageOrder, (lang) => {
+            testCase.lang = lang;
+            renderAuthor();
+        }));
+        const hiddenLabel = make('label', 'problem-hidden-toggle');
+        const hidden = make('input');
+        hidden.type = 'checkbox';
+        hidden.checked = testCase.hidden;
+        hidden.addEventListener('change', () => { testCase.hidden = hidden.checked; });
+        hiddenLabel.append(hidden, ' hidden');
+        bar.appendChild(hiddenLabel);
+        bar.appendChild(make('span', 'notebook-spacer'));
+        const remove = make('button', 'notebook-button', '×');
+        remove.title = 'Delete case';
+        remove.addEventListener('click', () => {
+            draft.cases.splice(index, 1);
+            renderAuthor();
+        });
+        bar.appendChild(remove);
+        row.appendChild(bar);
+
+        for (const field of ['input', 'expected']) {
+            const editor = make('textarea', `notebook-editor problem-case-${field} ${config.fontClass}`);
+            editor.placeholder = field === 'input' ? 'input (empty: run the solution as a program)' : 'expected output';
+            editor.spellcheck = false;
+            editor.value = testCase[field];
+            autosize(editor);
+            editor.addEventListener('input', () => {
+                testCase[field] = editor.value;
+                autosize(editor);
+            });
+            attachKeyboard(editor, testCase.lang);
+            row.appendChild(editor);
+        }
+        return row;
+    }
+
+    /**
+     * The problem being written, in the format src/golf.js expects
+     */
+    function serialize() {
+        const tests = {};
+        for (const testCase of draft.cases) {
+            if (!testCase.expected.trim() && !testCase.input.trim()) continue;
+            const input = testCase.input.trim();
+            const args = input.split('\n').map(arg => arg.trim());
+            const clean = { expected: testCase.expected.replace(/\s+$/, '') };
+            if (input) clean.input = args.length === 2 ? args : input;
+            if (testCase.hidden) clean.hidden = true;
+            (tests[testCase.lang] = tests[testCase.lang] || []).push(clean);
+        }
+        return { title: draft.title.trim(), statement: draft.statement, compare: draft.compare, tests };
+    }
+
+    async function publish() {
+        if (mode !== 'author') return;
+        const written = serialize();
+        const result = await options.publish(written);
+        if (result.error) {
+            renderAuthor({ error: true, text: `✗ ${result.error}` });
+            return;
+        }
+        // Show the problem the way solvers will see it
+        draft = null;
+        openSolve(result.id, publicProblem(written));
+    }
+
+    toolbar.addEventListener('click', (e) => {
+        const action = e.target.closest('[data-action]')?.dataset.action;
+        if (action === 'new') openAuthor(options.getLanguage(), true);
+        else if (action === 'publish') publish();
+        else if (action === 'submit') submit();
+        else if (action === 'close') close();
+    });
+
+    return {
+        openSolve,
+        openAuthor,
+        close,
+        isOpen,
+        publish,
+        getMode: () => mode
+    };
+}
+
+export default {
+    createProblemView
+};
@@ -0,0 +1,66 @@
+/**
+ * Runtime Worker
+ * Web Worker running one language's interpreter from runtimes.js, for
+ * src/worker-runtimes.js.
+ *
+ * Requests:  { id, action: 'load' | 'eval' | 'reset' | 'format', lang, code, input,
+ *            files }
+ * Responses: { id, value }, after any { id, output } messages carrying text the
+ *            evaluation printed
+ *
+ * Evaluations run one after another, even when a second one arrives while
+ * the first is still running (TinyAPL's are async), so each output message
+ * carries the id of the evaluation that printed it.
+ */
+
+import { runtimes, setOutputListener } from './runtimes.js';
+
+// Request whose evaluation is printing, and the last evaluation queued
+let evaluating = null;
+let evaluations = Promise.resolve();
+
+setOutputListener((text) => {
+    if (evaluating !== null) self.postMessage({ id: evaluating, output: text });
+});
+
+async function handle({ id, action, lang, code, input, files }) {
+    const runtime = runtimes[lang];
+    switch (action) {
+        case 'load': {
+            const loaded = await runtime.load();
+            return {
+                loaded,
+                error: loaded ? null : runtime.getError()?.message || 'Unknown error',
+                version: loaded && runtime.version ? runtime.version() : null
+            };
+        }
+        case 'eval': {
+            const evaluation = evaluations.then(async () => {
+                evaluating = id;
+                try {
+                    return await runtime.eval(code, { input, files });
+                } finally {
+                    evaluating = null;
+                }
+            });
+            evaluations = evaluation.catch(() => {});
+            return evaluation;
+        }
+        case 'reset':
+            return runtime.reset();
+        case 'format':
+            return runtime.format(code);
+        default:
+            throw new Error(`Unknown action ${action}`);
+    }
+}
+
+self.onmessage = async ({ data }) => {
+    let value;
+    try {
+        value = await handle(data);
+    } catch (error) {
+        value = { success: false, output: error.message || String(error) };
+    }
+    self.postMessage({ id: data.id, value });
+};
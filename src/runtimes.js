@@ -0,0 +1,1151 @@
+/**
+ * Array Language Runtimes
+ *
+ * Loads the in-browser interpreters shipped in wasm/ and evaluates code with them:
+ *   BQN:     CBQN compiled to WASM with Emscripten (wasm/bqn/BQN.js)
+ *   Uiua:    wasm-bindgen build (wasm/uiua_wasm.js)
+ *   TinyAPL: GHC WASM build (wasm/tinyapl/tinyapl.js)
+ *   J:       Emscripten build (wasm/j/emj.js)
+ *   Kap:     Kotlin/JS build (wasm/kap/standalonejs.js)
+ *
+ * The same module runs in the browser (index.html) and under Node (bin/arraybox),
+ * so scripts and CI evaluate code with exactly the interpreters the site uses.
+ * In the browser the Emscripten and Kap builds are isolated in hidden iframes;
+ * under Node and in Web Workers (src/runtime-worker.js) they are evaluated in a
+ * function scope with the few globals they expect.
+ *
+ * Every runtime exposes { load, isReady, getError, eval, reset } and eval resolves to
+ * { success, output } (Uiua's eval is synchronous, matching its WASM API).
+ * TinyAPL and Kap results also carry `value`, the result as a structured array
+ * for src/array-display.js (null when it can't be converted).
+ * Uiua results carry `media`, what &ims, &gifs and &ap would have shown, for
+ * src/plot.js: { kind: 'image', data }, { kind: 'gif', fps, frames } and
+ * { kind: 'audio', samples, sampleRate }, with the arrays as nested JS arrays.
+ * reset() discards everything defined by earlier evaluations (session mode).
+ * setOutputListener() receives BQN and TinyAPL output as it is printed, before
+ * the evaluation finishes.
+ *
+ * eval(code, { input }) gives the program input data, read the language's own
+ * way: stdin in BQN (•GetLine, •FLines "/dev/stdin") and J (1!:1 ]3), also the
+ * file input.txt in J, ⍞ in TinyAPL, &sc in Uiua (the next line each time it
+ * runs) and the variable `input` in Kap.
+ *
+ * eval(code, { files }) gives the program project files ({ path: text }, see
+ * src/vfs.js) to import: they are the working directory of BQN (•Import
+ * "lib.bqn") and J (load 'lib.ijs'), and Kap's use("lib.kap") reads them (in
+ * Web Workers and under Node).
+ *
+ * Pages with no wasm/ directory next to them (standalone HTML exports, see
+ * src/standalone-export.js) hand over the files as blob: URLs with setWasmFiles().
+ */
+
+const isNode = typeof window === 'undefined' && typeof process !== 'undefined' && !!process.versions?.node;
+const isWorker = typeof WorkerGlobalScope !== 'undefined' && self instanceof WorkerGlobalScope;
+
+// Directory holding the interpreter builds (overridable for unusual layouts);
+// null when this module itself was loaded from a blob: URL
+let wasmBaseUrl = defaultWasmBaseUrl();
+
+// Interpreter files by path under wasm/ (e.g. 'bqn/BQN.wasm'), when set
+let wasmFiles = null;
+
+function defaultWasmBaseUrl() {
+    try {
+        return new URL('../wasm/', import.meta.url);
+    } catch (e) {
+        return null;
+    }
+}
+
+// Loader progress/errors go here; bin/arraybox silences it to keep stdout clean
+let logger = console;
+
+/**
+ * Point the runtimes at a different wasm/ directory (URL or absolute URL string)
+ */
+export function setWasmBaseUrl(url) {
+    wasmBaseUrl = new URL(url);
+}
+
+/**
+ * Load interpreter files from the given URLs instead of the wasm/ directory
+ * @param {Object<string, string>} files - URL by path under wasm/ ('bqn/BQN.js', 'kap/standard-lib/io.kap', ...)
+ */
+export function setWasmFiles(files) {
+    wasmFiles = files;
+}
+
+// Browser URL of a file under wasm/
+function wasmUrl(path) {
+    if (!wasmFiles) return new URL(path, wasmBaseUrl).href;
+    if (!wasmFiles[path]) throw new Error(`${path} is not included in this page`);
+    return wasmFiles[path];
+}
+
+// Import an ES module given as source, resolving its relative imports among
+// the files from setWasmFiles (their sources are fetched and rewritten too)
+async function importFromFiles(path, rewrite = (source) => source) {
+    return import(await moduleUrlFromFiles(path, rewrite));
+}
+
+async function moduleUrlFromFiles(path, rewrite = (source) => source) {
+    const dir = path.slice(0, path.lastIndexOf('/') + 1);
+    let source = rewrite(await (await fetch(wasmUrl(path))).text());
+    const specifiers = new Set();
+    for (const match of source.matchAll(/\b(?:from|import)\s*['"](\.{1,2}\/[^'"]+)['"]/g)) specifiers.add(match[1]);
+    for (const specifier of specifiers) {
+        const resolved = new URL(specifier, `https://files/${dir}`).pathname.slice(1);
+        const url = await moduleUrlFromFiles(resolved);
+        source = source.split(`'${specifier}'`).join(`'${url}'`).split(`"${specifier}"`).join(`"${url}"`);
+    }
+    return URL.createObjectURL(new Blob([source], { type: 'text/javascript' }));
+}
+
+/**
+ * Replace the logger used for loader messages (null silences them)
+ */
+export function setLogger(newLogger) {
+    const noop = () => {};
+    logger = newLogger || { log: noop, warn: noop, error: noop };
+}
+
+// Output printed during an evaluation goes here as well as into the result
+let outputListener = null;
+
+/**
+ * Receive output as it is printed (null to stop)
+ * @param {Function|null} listener - (text) => void, text ending in a newline
+ */
+export function setOutputListener(listener) {
+    outputListener = listener;
+}
+
+function streamOutput(text) {
+    if (outputListener) outputListener(text);
+}
+
+// Input data of the evaluation running now, as UTF-8 ending in a line break
+// like a text file, and how much of it the program has read
+let programInput = new Uint8Array(0);
+let programInputRead = 0;
+let inputLineEnded = false;
+
+function setProgramInput(text = '') {
+    programInput = new TextEncoder().encode(text && !text.endsWith('\n') ? text + '\n' : text);
+    programInputRead = 0;
+    inputLineEnded = false;
+}
+
+// Emscripten stdin device: the next byte of input. Every read stops after a
+// line so C's stdin buffers no input past what the program asked for, and the
+// end is "try again" (undefined) rather than end of file (null), which C's
+// stdin would keep reporting to later evaluations.
+function readInputByte() {
+    if (inputLineEnded || programInputRead >= programInput.length) {
+        inputLineEnded = false;
+        return undefined;
+    }
+    const byte = programInput[programInputRead++];
+    inputLineEnded = byte === 10;
+    return byte;
+}
+
+// The next line of input without its line break, null at the end
+function readInputLine() {
+    if (programInputRead >= programInput.length) return null;
+    let end = programInput.indexOf(10, programInputRead);
+    if (end < 0) end = programInput.length;
+    const line = new TextDecoder().decode(programInput.subarray(programInputRead, end));
+    programInputRead = end + 1;
+    return line.replace(/\r$/, '');
+}
+
+// Project files are written under this directory of the Emscripten file
+// systems, which is made the working directory so relative paths find them
+const PROJECT_DIR = '/project';
+
+// Replace the files under PROJECT_DIR with `files` ({ path: text })
+function writeProjectFiles(FS, files) {
+    FS.mkdirTree(PROJECT_DIR);
+    // The working directory can't be removed, only emptied
+    for (const name of FS.readdir(PROJECT_DIR)) {
+        if (name !== '.' && name !== '..') removeTree(FS, `${PROJECT_DIR}/${name}`);
+    }
+    for (const [path, text] of Object.entries(files)) {
+        const fullPath = `${PROJECT_DIR}/${path}`;
+        FS.mkdirTree(fullPath.slice(0, fullPath.lastIndexOf('/')));
+        FS.writeFile(fullPath, text);
+    }
+    FS.chdir(PROJECT_DIR);
+}
+
+function removeTree(FS, path) {
+    if (!FS.isDir(FS.stat(path).mode)) {
+        FS.unlink(path);
+        return;
+    }
+    for (const name of FS.readdir(path)) {
+        if (name !== '.' && name !== '..') removeTree(FS, `${path}/${name}`);
+    }
+    FS.rmdir(path);
+}
+
+const consoleHint = isNode ? '' : '\n\nCheck browser console (F12) for details.';
+
+/**
+ * Wrap a loader so it only runs once and records readiness/errors.
+ * load() resolves to true when ready and false if loading failed.
+ */
+function createLoader(label, loadFn) {
+    const state = { ready: false, error: null, promise: null };
+
+    state.load = () => {
+        if (state.ready) return Promise.resolve(true);
+        if (state.error) return Promise.resolve(false);
+        if (!state.promise) {
+            logger.log(`[${label}] Starting to load...`);
+            state.promise = loadFn()
+                .then(() => {
+                    state.ready = true;
+                    logger.log(`[${label}] Loaded successfully!`);
+                    return true;
+                })
+                .catch((error) => {
+                    logger.error(`[${label}] Loading failed:`, error);
+                    state.error = error instanceof Error ? error : new Error(String(error));
+                    return false;
+                });
+        }
+        return state.promise;
+    };
+
+    // Start over with a freshly loaded interpreter (clears all interpreter state)
+    state.reload = () => {
+        state.ready = false;
+        state.error = null;
+        state.promise = null;
+        return state.load();
+    };
+
+    return state;
+}
+
+/**
+ * Wait (up to maxWait ms) for a runtime to finish loading.
+ * Returns null when ready, or a failed result to hand back to the caller.
+ */
+async function waitForLoad(state, label, maxWait, loadingMessage) {
+    if (state.ready) return null;
+
+    let timer;
+    const timedOut = new Promise(resolve => { timer = setTimeout(() => resolve('timeout'), maxWait); });
+    const outcome = await Promise.race([state.load(), timedOut]);
+    clearTimeout(timer);
+
+    if (state.ready) return null;
+    if (state.error || outcome === false) {
+        return {
+            success: false,
+            output: `${label} failed to load: ${state.error ? state.error.message : 'Unknown error'}${consoleHint}`
+        };
+    }
+    return { success: false, output: loadingMessage || `${label} is still loading...` };
+}
+
+// Results with more cells than this get no `value` (src/array-display.js shows
+// arrays that large as text anyway)
+const VALUE_CELL_LIMIT = 2000;
+
+/**
+ * Convert an interpreter's result to the array display model (src/array-display.js)
+ * convert(value, budget) takes cells from budget.cells and throws when it runs
+ * out or meets something the model can't hold; the result is then null
+ */
+function structuredValue(convert, value) {
+    try {
+        return convert(value, { cells: VALUE_CELL_LIMIT });
+    } catch (e) {
+        return null;
+    }
+}
+
+function takeCells(budget, count) {
+    budget.cells -= count;
+    if (budget.cells < 0) throw new Error('Too many cells to convert');
+}
+
+// ============================================================================
+// Node helpers (only imported when running under Node)
+// ============================================================================
+
+async function nodeModules() {
+    const [fs, module, url] = await Promise.all([
+        import('node:fs'),
+        import('node:module'),
+        import('node:url')
+    ]);
+    return { fs, createRequire: module.createRequire, fileURLToPath: url.fileURLToPath };
+}
+
+// Console passed to interpreter code under Node so its chatter goes to the logger
+function interpreterConsole() {
+    return {
+        log: (...args) => logger.log(...args),
+        info: (...args) => logger.log(...args),
+        debug: (...args) => logger.log(...args),
+        warn: (...args) => logger.warn(...args),
+        error: (...args) => logger.error(...args)
+    };
+}
+
+// Emscripten keeps its file system API in a top-level FS (Module.FS is a stub
+// unless exported at build time); put it on Module for writeProjectFiles
+const EXPOSE_FS = '\nModule.arrayboxFS = FS;';
+
+/**
+ * Load an Emscripten module.
+ * Browser: inject the script into a hidden iframe with Module preconfigured.
+ * Node: evaluate the script in a function scope with require/process/__dirname.
+ * Worker: evaluate the fetched script in a function scope; the importScripts
+ * stand-in makes it take its worker code path.
+ * Resolves with the initialized Module.
+ */
+async function loadEmscripten(dir, script, label, config) {
+    if (isWorker) {
+        const source = await (await fetch(wasmUrl(dir + script))).text() + EXPOSE_FS;
+        return new Promise((resolve, reject) => {
+            const Module = {
+                ...config,
+                locateFile: (path) => wasmUrl(dir + path),
+                onRuntimeInitialized: () => resolve(Module),
+                onAbort: (what) => reject(new Error(what || `${label} WASM initialization aborted`))
+            };
+            new Function('Module', 'importScripts', 'console', source)(Module, () => {}, interpreterConsole());
+        });
+    }
+
+    if (isNode) {
+        const baseUrl = new URL(dir, wasmBaseUrl);
+        const { fs, createRequire, fileURLToPath } = await nodeModules();
+        const scriptPath = fileURLToPath(new URL(script, baseUrl));
+        const source = fs.readFileSync(scriptPath, 'utf8') + EXPOSE_FS;
+
+        return new Promise((resolve, reject) => {
+            const Module = {
+                ...config,
+                // Keep the CLI's own argv away from the interpreter's main()
+                arguments: [],
+                locateFile: (path) => fileURLToPath(new URL(path, baseUrl)),
+                onRuntimeInitialized: () => resolve(Module),
+                onAbort: (what) => reject(new Error(what || `${label} WASM initialization aborted`))
+            };
+            new Function('Module', 'require', 'process', '__dirname', '__filename', 'console', source)(
+                Module, createRequire(scriptPath), process, fileURLToPath(baseUrl), scriptPath, interpreterConsole()
+            );
+        });
+    }
+
+    // Drop the iframe of a previous load (reset() reloads the interpreter)
+    document.querySelectorAll(`iframe[data-runtime="${label}"]`).forEach(el => el.remove());
+
+    // Create an iframe to load the script in isolation
+    const iframe = document.createElement('iframe');
+    iframe.style.display = 'none';
+    iframe.dataset.runtime = label;
+    document.body.appendChild(iframe);
+
+    // Use document.open/write/close to claim the iframe document.
+    // Firefox asynchronously replaces the initial about:blank document,
+    // which would wipe out any injected scripts. Writing explicitly
+    // prevents that replacement.
+    const iframeDoc = iframe.contentWindow.document;
+    iframeDoc.open();
+    iframeDoc.write('<!DOCTYPE html><html><head></head><body></body></html>');
+    iframeDoc.close();
+
+    return new Promise((resolve, reject) => {
+        // Setup Module config in the iframe BEFORE loading the script
+        iframe.contentWindow.Module = {
+            ...config,
+            // Absolute URL so WASM fetches resolve correctly from the iframe
+            locateFile: (path) => wasmUrl(dir + path),
+            onRuntimeInitialized: () => resolve(Object.assign(iframe.contentWindow.Module, { arrayboxFS: iframe.contentWindow.FS })),
+            onAbort: (what) => reject(new Error(what || `${label} WASM initialization aborted`))
+        };
+
+        const scriptEl = iframeDoc.createElement('script');
+        scriptEl.src = wasmUrl(dir + script);
+        scriptEl.onerror = () => reject(new Error(`Failed to load ${label} WASM script`));
+        iframeDoc.head.appendChild(scriptEl);
+    });
+}
+
+// ============================================================================
+// BQN (CBQN)
+// ============================================================================
+
+// cbqn_runLine(char*, i64) - evaluates with error catching, prints results/errors
+let cbqn_runLine = null;
+let bqnFS = null;
+
+// •Import keeps the result of each path it imported, so the interpreter is
+// reloaded when project files change after code that imported
+let bqnProjectFiles = null;
+let bqnImported = false;
+
+// Output capture buffers
+let bqnStdout = '';
+let bqnStderr = '';
+
+const bqnState = createLoader('BQN', async () => {
+    const Module = await loadEmscripten('bqn/', 'BQN.js', 'CBQN', {
+        // Capture stdout - cbqn_runLine sends formatted results here
+        print: (text) => {
+            bqnStdout += text + '\n';
+            streamOutput(text + '\n');
+        },
+        // Capture stderr - cbqn_runLine sends error messages here
+        printErr: (text) => { bqnStderr += text + '\n'; },
+        // •GetLine and •FLines "/dev/stdin" read the input data
+        stdin: readInputByte
+    });
+    cbqn_runLine = Module.cwrap('cbqn_runLine', null, ['string', 'number']);
+    bqnFS = Module.arrayboxFS;
+    bqnImported = false;
+});
+
+async function evalBQN(code, options = {}) {
+    const notReady = await waitForLoad(bqnState, 'CBQN', 15000, 'CBQN WASM loading...');
+    if (notReady) return notReady;
+
+    if (options.files) {
+        const key = JSON.stringify(options.files);
+        if (bqnImported && key !== bqnProjectFiles && !(await bqnState.reload())) {
+            return { success: false, output: `CBQN failed to load: ${bqnState.error.message}` };
+        }
+        bqnProjectFiles = key;
+    }
+
+    bqnStdout = '';
+    bqnStderr = '';
+    setProgramInput(options.input);
+
+    try {
+        if (options.files) writeProjectFiles(bqnFS, options.files);
+        if (code.includes('•Import')) bqnImported = true;
+        // cbqn_runLine expects the code string and its UTF-8 byte length
+        cbqn_runLine(code, new TextEncoder().encode(code).length);
+
+        const stdout = bqnStdout.replace(/\n$/, '');
+        const stderr = bqnStderr.replace(/\n$/, '');
+
+        if (stderr) {
+            // CBQN stderr format: "Error: <message>\n<stack trace>"
+            return {
+                success: false,
+                output: stderr.startsWith('Error: ') ? stderr.slice(7) : stderr
+            };
+        }
+
+        return { success: true, output: stdout };
+    } catch (error) {
+        // Shouldn't normally happen since cbqn_runLine catches errors internally
+        return { success: false, output: error.message || String(error) };
+    }
+}
+
+export const bqn = {
+    load: bqnState.load,
+    isReady: () => bqnState.ready,
+    getError: () => bqnState.error,
+    eval: evalBQN,
+    reset: bqnState.reload
+};
+
+// ============================================================================
+// Uiua
+// ============================================================================
+
+let uiuaModule = null;
+
+const uiuaState = createLoader('Uiua', async () => {
+    const module = await import(isNode ? new URL('uiua_wasm.js', wasmBaseUrl).href : wasmUrl('uiua_wasm.js'));
+    if (isNode) {
+        // fetch() can't read file: URLs under Node, so hand wasm-bindgen the bytes
+        const { fs, fileURLToPath } = await nodeModules();
+        await module.default({ module_or_path: fs.readFileSync(fileURLToPath(new URL('uiua_wasm_bg.wasm', wasmBaseUrl))) });
+    } else {
+        await module.default({ module_or_path: wasmUrl('uiua_wasm_bg.wasm') });
+    }
+    uiuaModule = module;
+    logger.log('[Uiua] Version:', module.uiua_version());
+});
+
+// The WASM build has no output device for &ims, &gifs and &ap, so they are
+// rewritten to leave their arguments on the stack as JSON, marked, and taken
+// off again after evaluation. The JSON is split into boxed chunks because the
+// stack formatter abbreviates long strings.
+const UIUA_MEDIA = {
+    ims: '(⊂□"⟦ims⟧"⊕□⌊÷1000°⊏json)',
+    gifs: '(⊂□"⟦gifs⟧"⊕□⌊÷1000°⊏json⊟□⊙□)',
+    ap: '(⊂□"⟦ap⟧"⊕□⌊÷1000°⊏json)'
+};
+const UIUA_MEDIA_ENTRY = /^\["⟦(ims|gifs|ap)⟧"│"(.*)"\]$/s;
+
+// Sample rate Uiua plays audio at (&asr)
+const UIUA_SAMPLE_RATE = 44100;
+
+// Rewrite the media functions outside strings, characters and comments.
+// Returns the code and, per replacement, where it is in the rewritten code
+// (1-based line and column, in characters) to map error positions back.
+function rewriteUiuaMedia(code) {
+    const shifts = [];
+    const lines = code.split('\n').map((line, l) => {
+        const chars = Array.from(line);
+        let out = '';
+        let column = 1;
+        for (let i = 0; i < chars.length; i++) {
+            const c = chars[i];
+            let end = i + 1;
+            if (c === '#' || (c === '$' && (chars[i + 1] === ' ' || i + 1 === chars.length))) {
+                end = chars.length;
+            } else if (c === '"') {
+                while (end < chars.length && chars[end] !== '"') end += chars[end] === '\\' ? 2 : 1;
+                end = Math.min(end + 1, chars.length);
+            } else if (c === '@') {
+                end = Math.min(i + (chars[i + 1] === '\\' ? 3 : 2), chars.length);
+            } else if (c === '&') {
+                while (end < chars.length && /[a-z]/.test(chars[end])) end++;
+                const replacement = UIUA_MEDIA[chars.slice(i + 1, end).join('')];
+                if (replacement) {
+                    const length = Array.from(replacement).length;
+                    shifts.push({ line: l + 1, column, length, delta: length - (end - i) });
+                    out += replacement;
+                    column += length;
+                    i = end - 1;
+                    continue;
+                }
+            }
+            out += chars.slice(i, end).join('');
+            column += end - i;
+            i = end - 1;
+        }
+        return out;
+    });
+    return { code: lines.join('\n'), shifts };
+}
+
+// Undo rewriteUiuaMedia on a result: take the media off the stack, move error
+// positions back and format the original code
+function restoreUiuaMedia(code, shifts, result) {
+    const formatted = JSON.parse(uiuaModule.format_uiua(code)).formatted || null;
+    if (!result.success) {
+        const output = (result.output || '').replace(/^(\d+):(\d+):/gm, (match, line, column) => {
+            let original = Number(column);
+            for (const shift of shifts) {
+                if (shift.line !== Number(line) || shift.column >= Number(column)) continue;
+                original -= Number(column) >= shift.column + shift.length ? shift.delta : Number(column) - shift.column;
+            }
+            return `${line}:${original}:`;
+        });
+        return { ...result, output, formatted };
+    }
+
+    const media = [];
+    const stack = [];
+    for (const entry of result.stack || []) {
+        const match = UIUA_MEDIA_ENTRY.exec(entry);
+        if (!match) {
+            stack.push(entry);
+            continue;
+        }
+        // Numbers need no escaping, so the chunks are the JSON as is
+        const data = JSON.parse(match[2].split('"│"').join(''));
+        if (match[1] === 'ims') media.push({ kind: 'image', data });
+        if (match[1] === 'gifs') media.push({ kind: 'gif', fps: data[0], frames: data[1] });
+        if (match[1] === 'ap') media.push({ kind: 'audio', samples: data, sampleRate: UIUA_SAMPLE_RATE });
+    }
+    // Media are shown in the order they were made; the stack lists the top first
+    media.reverse();
+    return { ...result, output: stack.join('\n'), stack, formatted, media };
+}
+
+export const uiua = {
+    load: uiuaState.load,
+    isReady: () => uiuaState.ready,
+    getError: () => uiuaState.error,
+    eval: (code, options = {}) => {
+        if (!uiuaState.ready) return null;
+        // &sc reads the input data, a line at a time (see scripts/update-uiua-wasm.sh)
+        const { code: rewritten, shifts } = rewriteUiuaMedia(code);
+        const result = JSON.parse(uiuaModule.eval_uiua_with_input(rewritten, options.input || ''));
+        return shifts.length ? restoreUiuaMedia(code, shifts, result) : result;
+    },
+    format: (code) => {
+        if (!uiuaState.ready) return null;
+        return JSON.parse(uiuaModule.format_uiua(code));
+    },
+    version: () => uiuaState.ready ? uiuaModule.uiua_version() : null,
+    // Every Uiua evaluation already starts from scratch
+    reset: () => uiuaState.load()
+};
+
+// ============================================================================
+// TinyAPL
+// ============================================================================
+
+let tinyaplModule = null;
+let tinyaplContext = null;
+
+// Output buffers for capturing TinyAPL I/O
+let tinyaplOutput = '';
+let tinyaplError = '';
+
+/**
+ * Import tinyapl.js under Node.
+ * It imports a browser WASI shim from unpkg and fetches its .wasm, neither of
+ * which works offline in Node, so the source is patched to use Node's WASI.
+ */
+async function importTinyaplNode(dirUrl) {
+    const { fs, fileURLToPath } = await nodeModules();
+    const { WASI: NodeWASI } = await import('node:wasi');
+
+    // Minimal stand-ins for the parts of @bjorn3/browser_wasi_shim tinyapl.js uses
+    globalThis.__arrayboxWasiShim = {
+        File: class { constructor(data) { this.data = data; } },
+        OpenFile: class { constructor(file) { this.file = file; } },
+        ConsoleStdout: { lineBuffered: (write) => ({ write }) },
+        WASI: class {
+            constructor(args) {
+                // GHC's RTS needs at least argv[0]
+                this.inner = new NodeWASI({ version: 'preview1', args: args.length ? args : ['tinyapl'], env: {}, returnOnExit: true });
+                this.wasiImport = this.inner.wasiImport;
+            }
+            initialize(instance) {
+                this.inner.initialize(instance);
+            }
+        }
+    };
+    globalThis.__arrayboxTinyaplWasm = fs.readFileSync(fileURLToPath(new URL('tinyapl-js.wasm', dirUrl)));
+
+    const source = fs.readFileSync(fileURLToPath(new URL('tinyapl.js', dirUrl)), 'utf8')
+        .replace(/^import \{ WASI, OpenFile, File, ConsoleStdout \} from .*$/m,
+            'const { WASI, OpenFile, File, ConsoleStdout } = globalThis.__arrayboxWasiShim;')
+        .replace(`from './ghc_wasm_jsffi.js'`, `from '${new URL('ghc_wasm_jsffi.js', dirUrl).href}'`)
+        .replace(/^const url = .*$/m, '')
+        .replace('WebAssembly.instantiateStreaming(fetch(url),',
+            'WebAssembly.instantiate(globalThis.__arrayboxTinyaplWasm,');
+    return import('data:text/javascript;base64,' + Buffer.from(source).toString('base64'));
+}
+
+/**
+ * Import tinyapl.js from the files given to setWasmFiles: the WASI shim it
+ * imports from unpkg is included under TINYAPL_WASI_SHIM, and its .wasm URL
+ * comes from the files too.
+ */
+export const TINYAPL_WASI_SHIM = 'tinyapl/wasi-shim/index.js';
+
+function importTinyaplFromFiles() {
+    return importFromFiles('tinyapl/tinyapl.js', (source) => source
+        .replace(/from 'https:\/\/unpkg\.com\/@bjorn3\/browser_wasi_shim@[^']*'/, `from './wasi-shim/index.js'`)
+        .replace(/^const url = .*$/m, `const url = '${wasmUrl('tinyapl/tinyapl-js.wasm')}';`));
+}
+
+const tinyaplState = createLoader('TinyAPL', async () => {
+    tinyaplModule = isNode
+        ? await importTinyaplNode(new URL('tinyapl/', wasmBaseUrl))
+        : wasmFiles
+        ? await importTinyaplFromFiles()
+        : await import(wasmUrl('tinyapl/tinyapl.js'));
+    tinyaplContext = await createTinyaplContext();
+});
+
+// Create a context with I/O handlers (a new context has no user definitions)
+function createTinyaplContext() {
+    return tinyaplModule.newContext(
+        async () => readInputLine() ?? '', // input (⍞) - the input data, a line at a time
+        async (what) => { tinyaplOutput += what; streamOutput(what); }, // output
+        async (what) => { tinyaplError += what; }, // error
+        {} // quads - basic primitives only
+    );
+}
+
+// TinyAPL error patterns to detect in output
+const TINYAPL_ERROR_PATTERNS = [
+    /^(?:Syntax|Parse|Value|Domain|Rank|Length|Index|NYI|Internal|Invalid|Assertion) error:/im,
+    /does not exist$/im,
+    /not assigned$/im
+];
+
+// TinyAPL number text: ¯ for negatives, E for exponents, ᴊ between complex parts
+function tinyaplNumber(n) {
+    if (!Number.isFinite(n)) return Number.isNaN(n) ? 'NaN' : n > 0 ? '∞' : '¯∞';
+    return String(n).replace(/-/g, '¯').replace(/e\+?/, 'E');
+}
+
+// TinyAPL values: arrays hold [re, im] numbers, one-character strings,
+// nested arrays and functions/operators ({ type, repr })
+function tinyaplValue(value, budget) {
+    if (Array.isArray(value)) {
+        const [re, im] = value;
+        return { kind: 'number', text: im ? `${tinyaplNumber(re)}ᴊ${tinyaplNumber(im)}` : tinyaplNumber(re) };
+    }
+    if (typeof value === 'string') return { kind: 'char', text: value };
+    if (typeof value.repr === 'string') return { kind: 'other', text: value.repr };
+    if (value.type !== 'array') throw new Error(`No display for TinyAPL ${value.type}`);
+
+    takeCells(budget, value.contents.length);
+    const items = value.contents.map(item => tinyaplValue(item, budget));
+    // A scalar is a shape [] array holding a number, character or function
+    if (value.shape.length === 0 && items[0].kind !== 'array') return items[0];
+    return { kind: 'array', shape: value.shape, items };
+}
+
+async function evalTinyapl(code, options = {}) {
+    const notReady = await waitForLoad(tinyaplState, 'TinyAPL', 30000,
+        'TinyAPL is still loading (7MB WASM file). Please wait a moment and try again.');
+    if (notReady) return notReady;
+
+    tinyaplOutput = '';
+    tinyaplError = '';
+    setProgramInput(options.input);
+
+    try {
+        // For multiline code, join lines with ⋄ (statement separator)
+        // Filter out empty lines and comment-only lines (⍝ comments out rest of line)
+        const lines = code.split('\n')
+            .map(line => line.trim())
+            .filter(line => line && !line.startsWith('⍝'))
+            .map(line => {
+                // Strip inline comments
+                const commentIndex = line.indexOf('⍝');
+                return commentIndex >= 0 ? line.substring(0, commentIndex).trim() : line;
+            })
+            .filter(line => line);
+
+        const processedCode = lines.length > 1 ? lines.join(' ⋄ ') : (lines[0] || '');
+        const result = await tinyaplModule.runCode(tinyaplContext, processedCode);
+
+        // Handle different result formats
+        let value, success;
+        if (Array.isArray(result)) {
+            // Expected format: [Value | Error, boolean]
+            [value, success] = result;
+        } else if (result && typeof result === 'object' && 'success' in result) {
+            success = result.success;
+            value = result.value || result.error || result;
+        } else {
+            // Assume it's a successful value
+            success = true;
+            value = result;
+        }
+
+        // Check the error buffer for errors (errors from ⎕ERR go here)
+        const hasErrorInBuffer = tinyaplError && TINYAPL_ERROR_PATTERNS.some(p => p.test(tinyaplError));
+
+        if (success && !hasErrorInBuffer) {
+            // Use the module's show() function for pretty-printing
+            let output = await tinyaplModule.show(value);
+
+            // Prepend any stdout output (from ⎕← in user code); boxes would hide it
+            const printed = !!tinyaplOutput;
+            if (printed) {
+                output = tinyaplOutput.replace(/\n$/, '') + '\n' + output;
+            }
+
+            // Final check: look for error patterns in the formatted output
+            return {
+                success: !TINYAPL_ERROR_PATTERNS.some(p => p.test(output)),
+                output,
+                value: printed ? null : structuredValue(tinyaplValue, value)
+            };
+        }
+
+        const errorMsg = await tinyaplModule.show(value);
+        return {
+            success: false,
+            output: tinyaplError + (tinyaplError ? '\n' : '') + errorMsg
+        };
+    } catch (error) {
+        return {
+            success: false,
+            output: `TinyAPL execution error: ${error.message || String(error)}`
+        };
+    }
+}
+
+export const tinyapl = {
+    load: tinyaplState.load,
+    isReady: () => tinyaplState.ready,
+    getError: () => tinyaplState.error,
+    eval: evalTinyapl,
+    version: () => tinyaplState.ready ? 'latest' : null,
+    reset: async () => {
+        if (!(await tinyaplState.load())) return false;
+        tinyaplContext = await createTinyaplContext();
+        return true;
+    }
+};
+
+// ============================================================================
+// J
+// ============================================================================
+
+// J WASM functions
+let jModule = null;
+let jdo1 = null;
+let jsetstr = null;
+
+const jState = createLoader('J', async () => {
+    const Module = await loadEmscripten('j/', 'emj.js', 'J', {
+        print: (text) => logger.log('[J]', text),
+        printErr: (text) => logger.warn('[J]', text),
+        stdin: readInputByte
+    });
+    jModule = Module;
+    jdo1 = Module.cwrap('em_jdo', 'string', ['string']);
+    jsetstr = Module.cwrap('em_jsetstr', 'void', ['string', 'string']);
+    // load and loadd print nothing unless asked to (the build leaves it undefined)
+    jdo1('Displayload_j_=: 0');
+});
+
+// The input data as the file input.txt in the working directory
+// (1!:1 <'input.txt'), unless it's a project file and there is no input data
+function writeJInputFile(options) {
+    if (typeof options.input !== 'string' && options.files && 'input.txt' in options.files) return;
+    jModule.arrayboxFS.writeFile('input.txt', programInput);
+}
+
+async function evalJ(code, options = {}) {
+    const notReady = await waitForLoad(jState, 'J', 10000, 'J WASM loading...');
+    if (notReady) return notReady;
+
+    try {
+        setProgramInput(options.input);
+        if (options.files) writeProjectFiles(jModule.arrayboxFS, options.files);
+        writeJInputFile(options);
+        jsetstr('CODE_jrx_', code);
+        let result = jdo1('(0!:101) CODE_jrx_');
+
+        // Clean up output: remove echoed input lines
+        // J's (0!:101) echoes each input line with leading whitespace
+        if (result.includes('output_jrx_\n')) {
+            const n = result.indexOf('output_jrx_\n');
+            result = result.slice(12 + n);
+        }
+
+        // Build set of trimmed code lines to identify echoed input
+        const codeLines = new Set(code.split('\n').map(l => l.trim()).filter(l => l));
+        result = result.split('\n')
+            .filter(line => {
+                const trimmed = line.trim();
+                if (trimmed === '') return false;
+                // J echoes input lines with leading spaces - remove them
+                return !codeLines.has(trimmed);
+            })
+            .join('\n')
+            .trim();
+
+        // Remove leading blank lines (lines containing only whitespace)
+        result = result.replace(/^(?:[ \t]*\r?\n)+/, '');
+
+        return {
+            success: !result.trimStart().startsWith('|'),
+            output: result
+        };
+    } catch (error) {
+        return {
+            success: false,
+            output: `J execution error: ${error.message || String(error)}`
+        };
+    }
+}
+
+export const j = {
+    load: jState.load,
+    isReady: () => jState.ready,
+    getError: () => jState.error,
+    eval: evalJ,
+    version: () => jState.ready ? 'j9.7' : null,
+    reset: jState.reload
+};
+
+// ============================================================================
+// Kap
+// ============================================================================
+
+let kapApi = null;
+let kapEngine = null;
+// (path, text) => void, adding a file for use() and io:readFile to find
+let kapAddFile = null;
+
+/**
+ * Load the Kap API object (standalonejs).
+ * Browser: navigate an iframe to kap-loader.html so XHR requests for the
+ * standard library resolve relative to wasm/kap/.
+ * Node: evaluate the bundle with a window stub and an XHR that reads from disk,
+ * then fire window.onload to start loading the standard library.
+ * Worker: the same, with the bundle fetched and an XHR resolving its requests
+ * for the standard library under wasm/kap/.
+ */
+async function loadKapApi(dirUrl) {
+    if (isWorker) {
+        const source = await (await fetch(wasmUrl('kap/standalonejs.js'))).text();
+        class KapXMLHttpRequest extends XMLHttpRequest {
+            open(method, url, ...rest) {
+                super.open(method, wasmUrl('kap/' + String(url).replace(/^\.\//, '')), ...rest);
+            }
+        }
+        return evalKapBundle(source, KapXMLHttpRequest);
+    }
+    if (isNode) {
+        const { fs, fileURLToPath } = await nodeModules();
+        const source = fs.readFileSync(fileURLToPath(new URL('standalonejs.js', dirUrl)), 'utf8');
+
+        class FileXMLHttpRequest {
+            open(method, url) { this.url = url; }
+            send() {
+                setTimeout(() => {
+                    try {
+                        this.responseText = fs.readFileSync(fileURLToPath(new URL(this.url, dirUrl)), 'utf8');
+                        this.status = 200;
+                    } catch (e) {
+                        this.responseText = '';
+                        this.status = 404;
+                    }
+                    this.readyState = 4;
+                    if (this.onload) this.onload({});
+                }, 0);
+            }
+        }
+
+        return evalKapBundle(source, FileXMLHttpRequest);
+    }
+
+    if (wasmFiles) return loadKapApiFromFiles();
+
+    const iframe = document.createElement('iframe');
+    iframe.style.display = 'none';
+
+    return new Promise((resolve, reject) => {
+        // Set onload BEFORE setting src and appending
+        iframe.onload = () => {
+            // Skip the initial about:blank load — standalonejs
+            // won't exist until kap-loader.html has loaded the script
+            const kapApi = iframe.contentWindow.standalonejs;
+            if (kapApi) resolve(kapApi);
+        };
+        iframe.onerror = () => reject(new Error('Failed to load Kap JS iframe'));
+
+        // Navigate the iframe to kap-loader.html
+        // (set src before appending to avoid about:blank onload race)
+        iframe.src = new URL('kap-loader.html', dirUrl).href;
+        document.body.appendChild(iframe);
+    });
+}
+
+// Where the bundle's onload adds each standard library file it fetched: the
+// function adding a file and the one converting a string for it
+const KAP_ADD_FILE = /(\w+\(\)\.\w+)\(\w+,\(0,(\w+\.\w+)\)\(\w+\.responseText\)\):console\.log\("Error loading library file: "/;
+
+// Run the Kap bundle in a function scope and start its standard library
+// loading; the bundle is patched to export window.arrayboxAddFile as well
+function evalKapBundle(source, XMLHttpRequestClass) {
+    const match = KAP_ADD_FILE.exec(source);
+    if (match) {
+        source = source.replace('window.onload=',
+            `window.arrayboxAddFile=function(n,t){${match[1]}(n,(0,${match[2]})(t))},window.onload=`);
+    }
+    const windowStub = {};
+    const module = { exports: {} };
+    new Function('module', 'exports', 'window', 'XMLHttpRequest', 'console', source)(
+        module, module.exports, windowStub, XMLHttpRequestClass, interpreterConsole()
+    );
+    kapAddFile = windowStub.arrayboxAddFile || null;
+    if (windowStub.onload) windowStub.onload({});
+    return module.exports;
+}
+
+/**
+ * Load the Kap API from the files given to setWasmFiles: the bundle runs in a
+ * written iframe whose XHR requests for standard-lib/*.kap are sent to the
+ * files' URLs instead.
+ */
+function loadKapApiFromFiles() {
+    const libraryUrls = {};
+    for (const [path, url] of Object.entries(wasmFiles)) {
+        if (path.startsWith('kap/')) libraryUrls[path.slice('kap/'.length)] = url;
+    }
+
+    const iframe = document.createElement('iframe');
+    iframe.style.display = 'none';
+    document.body.appendChild(iframe);
+
+    return new Promise((resolve, reject) => {
+        iframe.__arrayboxKapLoaded = resolve;
+        iframe.__arrayboxKapFailed = () => reject(new Error('Failed to load Kap JS'));
+        const doc = iframe.contentWindow.document;
+        doc.open();
+        doc.write(`<!DOCTYPE html><html><head><meta charset="UTF-8"></head><body>
+<script>
+var urls = ${JSON.stringify(libraryUrls)};
+var open = XMLHttpRequest.prototype.open;
+XMLHttpRequest.prototype.open = function (method, url) {
+    var args = Array.prototype.slice.call(arguments);
+    args[1] = urls[String(url).replace(/^\\.\\//, '')] || url;
+    return open.apply(this, args);
+};
+// After the bundle's own onload, which starts loading the standard library
+window.addEventListener('load', function () {
+    setTimeout(function () { frameElement.__arrayboxKapLoaded(window.standalonejs); });
+});
+<\/script>
+<script src="${wasmUrl('kap/standalonejs.js')}" onerror="frameElement.__arrayboxKapFailed()"><\/script>
+</body></html>`);
+        doc.close();
+    });
+}
+
+const kapState = createLoader('Kap', async () => {
+    let timer;
+    const timeout = new Promise((_, reject) => {
+        timer = setTimeout(() => reject(new Error('Kap JS loading timed out')), 60000);
+    });
+
+    try {
+        await Promise.race([timeout, (async () => {
+            kapApi = await loadKapApi(new URL('kap/', wasmBaseUrl));
+            // Webpack production build flattens exports directly onto the module
+            if (!kapApi.createEngine) {
+                throw new Error('createEngine not found on Kap module. Available keys: ' + Object.keys(kapApi).join(', '));
+            }
+            kapEngine = await kapApi.createEngine();
+        })()]);
+    } finally {
+        clearTimeout(timer);
+    }
+});
+
+// Kap values: JsKapValue wrappers, whose scalars print as the language does
+function kapValue(value, budget) {
+    const types = kapApi.JsKapValueType;
+    const type = value.type();
+    if (type === types.ARRAY) {
+        const shape = Array.from(value.dimensions());
+        const size = shape.reduce((a, b) => a * b, 1);
+        takeCells(budget, size);
+        const items = [];
+        for (let i = 0; i < size; i++) items.push(kapValue(value.valueAt(i), budget));
+        return { kind: 'array', shape, items };
+    }
+    const text = value.formatted();
+    if (type === types.CHAR) return { kind: 'char', text };
+    if (type === types.INTERNAL) return { kind: 'other', text };
+    return { kind: 'number', text };
+}
+
+// Kap has no stdin here: the input data is the string variable `input`
+function kapString(text) {
+    return '"' + text.replace(/[\\"]/g, '\\$&').replace(/\n/g, '\\n') + '"';
+}
+
+async function evalKap(code, options = {}) {
+    // Stdlib loading can take a moment
+    const notReady = await waitForLoad(kapState, 'Kap JS', 30000);
+    if (notReady) return notReady;
+
+    try {
+        // Files removed from the project stay readable until the page reloads
+        if (options.files && kapAddFile) {
+            for (const [path, text] of Object.entries(options.files)) kapAddFile(path, text);
+        }
+        if (typeof options.input === 'string') {
+            kapEngine.parseAndEvalWithFormat(`input ← ${kapString(options.input)}`);
+        }
+        // result.text is an Array<String> of formatted output lines
+        const result = kapEngine.parseAndEvalWithFormat(code);
+        return {
+            success: true,
+            output: result.text.join('\n'),
+            value: structuredValue(kapValue, result.result)
+        };
+    } catch (error) {
+        // Kap throws exceptions for parse/eval errors
+        return { success: false, output: error.message || String(error) };
+    }
+}
+
+export const kap = {
+    load: kapState.load,
+    isReady: () => kapState.ready,
+    getError: () => kapState.error,
+    eval: evalKap,
+    reset: async () => {
+        if (!(await kapState.load())) return false;
+        kapEngine = await kapApi.createEngine();
+        return true;
+    }
+};
+
+// ============================================================================
+// Common entry points
+// ============================================================================
+
+/**
+ * Runtimes by language id (APL runs on the server and has no entry here)
+ */
+export const runtimes = { bqn, uiua, tinyapl, j, kap };
+
+/**
+ * Evaluate code in a language, loading its runtime if needed.
+ * Always resolves to { success, output } (plus `formatted` and `media` for
+ * Uiua and `value` for TinyAPL and Kap).
+ * @param {Object} [options]
+ * @param {string} [options.input] - Input data for the program to read
+ * @param {Object<string, string>} [options.files] - Project files by path
+ */
+export async function evaluate(lang, code, options = {}) {
+    const runtime = runtimes[lang];
+    if (!runtime) {
+        return { success: false, output: `No in-browser runtime for ${lang}` };
+    }
+
+    if (lang === 'uiua') {
+        if (!(await runtime.load())) {
+            const error = runtime.getError();
+            return { success: false, output: `Failed to load Uiua WASM: ${error?.message || 'Unknown error'}` };
+        }
+        try {
+            const result = runtime.eval(code, options);
+            return { success: result.success, output: result.output || '', formatted: result.formatted || null, media: result.media || [] };
+        } catch (error) {
+            return { success: false, output: `Uiua execution error: ${error.message || String(error)}` };
+        }
+    }
+
+    return runtime.eval(code, options);
+}
+
+/**
+ * Discard all state left by earlier evaluations in a language.
+ * Resolves to true once the runtime is ready again.
+ */
+export async function reset(lang) {
+    const runtime = runtimes[lang];
+    return runtime ? runtime.reset() : false;
+}
+
+export default {
+    runtimes,
+    evaluate,
+    reset,
+    setWasmBaseUrl,
+    setWasmFiles,
+    setLogger,
+    setOutputListener,
+    bqn,
+    uiua,
+    tinyapl,
+    j,
+    kap
+};
@@ -0,0 +1,280 @@
+/**
+ * Session (workspace) mode
+ * - Track the names defined by successful evaluations, per language
+ * - Carry Uiua bindings between evaluations (its WASM eval keeps no state)
+ * - Session ids for the APL server's per-session Safe3 namespaces
+ *
+ * The other WASM interpreters (BQN, J, Kap, TinyAPL) keep their state for the
+ * lifetime of the page, so a session there is just the interpreter itself;
+ * resetting one goes through the runtime's reset() in src/runtimes.js.
+ */
+
+import { assignmentOperators, commentTokens } from './editor-features.js';
+
+/**
+ * String delimiters by language (contents are ignored when looking for definitions)
+ */
+const stringQuotes = {
+    apl: `'"`,
+    bqn: `"'`,
+    j: `'`,
+    uiua: `"`,
+    kap: `"`,
+    tinyapl: `"'`
+};
+
+/**
+ * Statement separators that may appear on one line
+ */
+const statementSeparators = {
+    apl: /⋄/,
+    bqn: /[⋄,]/,
+    j: null,
+    uiua: null,
+    kap: /⋄/,
+    tinyapl: /⋄/
+};
+
+/**
+ * Valid user names by language
+ */
+const namePatterns = {
+    apl: '[A-Za-z_∆⍙][A-Za-z0-9_∆⍙¯]*',
+    bqn: '[A-Za-z_][A-Za-z0-9_¯π∞]*',
+    j: '[A-Za-z][A-Za-z0-9_]*',
+    uiua: '[A-Za-z][A-Za-z0-9]*',
+    kap: '[A-Za-z_∆⍙][A-Za-z0-9_∆⍙]*',
+    tinyapl: '[A-Za-z_∆⍙][A-Za-z0-9_∆⍙]*'
+};
+
+// Modification (↩) only changes an existing name, so it doesn't count as a definition
+const modificationOperators = ['↩'];
+
+function escapeRegex(text) {
+    return text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
+
+/**
+ * Blank out comments, strings and block contents ({...}, J's {{...}}) so only
+ * top-level code remains. Line breaks are kept so lines still line up.
+ * @param {string} code - Source code
+ * @param {string} language - Language id
+ * @returns {string} - Code of the same length with nested parts replaced by spaces
+ */
+function topLevelCode(code, language) {
+    const comment = commentTokens[language];
+    const quotes = stringQuotes[language] || '';
+    const [open, close] = language === 'j' ? ['{{', '}}'] : ['{', '}'];
+    let result = '';
+    let depth = 0;
+    let quote = null;
+
+    for (let i = 0; i < code.length; i++) {
+        const char = code[i];
+
+        if (char === '\n') {
+            result += '\n';
+            // J and APL strings can't span lines
+            if (quote === "'" && language !== 'bqn') quote = null;
+            continue;
+        }
+        if (quote) {
+            if (char === quote) quote = null;
+            result += ' ';
+            continue;
+        }
+        if (comment && code.startsWith(comment, i)) {
+            const end = code.indexOf('\n', i);
+            const stop = end === -1 ? code.length : end;
+            result += ' '.repeat(stop - i);
+            i = stop - 1;
+            continue;
+        }
+        if (quotes.includes(char)) {
+            quote = char;
+            result += ' ';
+            continue;
+        }
+        if (code.startsWith(open, i)) {
+            depth++;
+            result += ' '.repeat(open.length);
+            i += open.length - 1;
+            continue;
+        }
+        if (code.startsWith(close, i) && depth > 0) {
+            depth--;
+            result += ' '.repeat(close.length);
+            i += close.length - 1;
+            continue;
+        }
+        result += depth > 0 ? ' ' : char;
+    }
+
+    return result;
+}
+
+/**
+ * Name defined by an APL/Kap traditional function header, e.g. "∇ r←a foo w"
+ */
+function tradfnName(header, name) {
+    const tokens = header.replace(/^\s*∇\s*/, '').replace(/^[^←]*←/, '').trim().split(/\s+/).filter(Boolean);
+    const candidate = tokens.length === 3 ? tokens[1] : tokens[0];
+    return candidate && new RegExp(`^${name}$`).test(candidate) ? candidate : null;
+}
+
+/**
+ * Find the names a piece of code defines at top level
+ * @param {string} code - Source code
+ * @param {string} language - Language id
+ * @returns {Array<{name: string, line: string}>} - Defined names with their source line, in order
+ */
+export function extractDefinitions(code, language) {
+    const operators = (assignmentOperators[language] || []).filter(op => !modificationOperators.includes(op));
+    const name = namePatterns[language];
+    if (!operators.length || !name) return [];
+
+    const assignment = new RegExp(`^\\s*(${name})\\s*(?:${operators.map(escapeRegex).join('|')})`);
+    const separators = statementSeparators[language];
+    const sourceLines = code.split('\n');
+    const definitions = [];
+    let inTradfn = false;
+
+    topLevelCode(code, language).split('\n').forEach((line, index) => {
+        // APL-style ∇ functions: record the header name, skip the body
+        if (line.trim().startsWith('∇')) {
+            const header = line.trim();
+            if (!inTradfn && header !== '∇') {
+                const fnName = tradfnName(header, name);
+                if (fnName) definitions.push({ name: fnName, line: sourceLines[index].trim() });
+                // Kap's "∇ name (args) { body }" closes on the same line
+                inTradfn = language !== 'kap';
+            } else {
+                inTradfn = false;
+            }
+            return;
+        }
+        if (inTradfn) return;
+
+        const statements = separators ? line.split(separators) : [line];
+        for (const statement of statements) {
+            const match = statement.match(assignment);
+            if (match) definitions.push({ name: match[1], line: sourceLines[index].trim() });
+        }
+    });
+
+    return definitions;
+}
+
+/**
+ * Uiua binding blocks (binding line plus continuation lines until brackets balance)
+ */
+function extractUiuaBindings(code) {
+    const bindings = [];
+    const lines = code.split('\n');
+    const topLevel = topLevelCode(code, 'uiua').split('\n');
+    const binding = new RegExp(`^(${namePatterns.uiua})\\s*[←↚]`);
+
+    for (let i = 0; i < lines.length; i++) {
+        const match = topLevel[i].match(binding);
+        if (!match) continue;
+
+        let depth = 0;
+        let end = i;
+        for (; end < lines.length; end++) {
+            for (const char of topLevel[end]) {
+                if ('([{'.includes(char)) depth++;
+                if (')]}'.includes(char)) depth--;
+            }
+            if (depth <= 0) break;
+        }
+        bindings.push({ name: match[1], source: lines.slice(i, end + 1).join('\n') });
+        i = end;
+    }
+
+    return bindings;
+}
+
+/**
+ * Create a random session id (letters and digits only, usable in APL names)
+ */
+export function newSessionId() {
+    const bytes = new Uint8Array(12);
+    crypto.getRandomValues(bytes);
+    return Array.from(bytes, b => b.toString(16).padStart(2, '0')).join('');
+}
+
+/**
+ * Create an empty session for a language
+ * @param {string} language - Language id
+ */
+export function createSession(language) {
+    return {
+        language,
+        id: newSessionId(),
+        names: new Map(),         // name -> source line of its latest definition
+        uiuaBindings: new Map()   // name -> binding source replayed before each Uiua evaluation
+    };
+}
+
+/**
+ * Prepare code for evaluation in a session.
+ * Uiua keeps no state between evaluations, so earlier bindings are prepended.
+ * @returns {{code: string, prefixLines: number}}
+ */
+export function prepareSessionCode(session, code) {
+    if (session.language !== 'uiua' || session.uiuaBindings.size === 0) {
+        return { code, prefixLines: 0 };
+    }
+    const prefix = [...session.uiuaBindings.values()].join('\n');
+    return { code: `${prefix}\n${code}`, prefixLines: prefix.split('\n').length };
+}
+
+/**
+ * Undo the effect of prepareSessionCode on a result: shift Uiua error
+ * positions back to the user's lines and drop the replayed bindings from
+ * the formatted code.
+ */
+export function finishSessionResult(session, result, prefixLines) {
+    if (!prefixLines) return result;
+
+    const output = (result.output || '').replace(/^(\d+):(\d+):/gm, (match, line, column) =>
+        Number(line) > prefixLines ? `${Number(line) - prefixLines}:${column}:` : match
+    );
+    let formatted = null;
+    if (result.formatted) {
+        const lines = result.formatted.split('\n');
+        formatted = lines.slice(prefixLines).join('\n');
+    }
+    return { ...result, output, formatted };
+}
+
+/**
+ * Record the definitions made by a successful evaluation
+ * @param {object} session - Session from createSession
+ * @param {string} code - Code the user evaluated (formatted code for Uiua)
+ * @param {{success: boolean}} result - Evaluation result
+ */
+export function recordEvaluation(session, code, result) {
+    if (!result.success) return;
+
+    for (const { name, line } of extractDefinitions(code, session.language)) {
+        session.names.delete(name);
+        session.names.set(name, line);
+    }
+    if (session.language === 'uiua') {
+        for (const { name, source } of extractUiuaBindings(code)) {
+            // Re-insert so a redefinition is replayed after what it depends on
+            session.uiuaBindings.delete(name);
+            session.uiuaBindings.set(name, source);
+        }
+    }
+}
+
+export default {
+    extractDefinitions,
+    newSessionId,
+    createSession,
+    prepareSessionCode,
+    finishSessionResult,
+    recordEvaluation
+};
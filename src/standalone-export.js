@@ -0,0 +1,377 @@
+/**
+ * Standalone HTML export
+ * - One file with a snippet's code (highlighted), its output, the language's
+ *   font and, for the languages that run in the browser, the interpreter from wasm/
+ * - The file works offline: edit the code and run it again with no server
+ *
+ * Files are embedded base64 in a JSON block. The page turns them into blob:
+ * URLs, imports src/syntax.js, src/array-display.js and src/runtimes.js from
+ * those, and hands runtimes.js the interpreter's files (setWasmFiles).
+ *
+ * APL is evaluated on a server, so APL exports show the saved output only.
+ * Interpreters are large (J and TinyAPL about 15 MB each once encoded).
+ */
+
+import { TINYAPL_WASI_SHIM } from './runtimes.js';
+import { themeVariables } from './themes.js';
+
+// Interpreter files under wasm/ by language (Kap's standard library and
+// TinyAPL's WASI shim are found while collecting)
+const RUNTIME_FILES = {
+    bqn: ['bqn/BQN.js', 'bqn/BQN.wasm'],
+    uiua: ['uiua_wasm.js', 'uiua_wasm_bg.wasm'],
+    j: ['j/emj.js', 'j/emj.wasm', 'j/emj.data'],
+    tinyapl: ['tinyapl/tinyapl.js', 'tinyapl/ghc_wasm_jsffi.js', 'tinyapl/tinyapl-js.wasm'],
+    kap: ['kap/standalonejs.js']
+};
+
+// Font file under fonts/ and CSS family by language (as in src/theme.css)
+const FONTS = {
+    bqn: ['BQN386.ttf', 'BQN'],
+    uiua: ['Uiua386.ttf', 'Uiua'],
+    apl: ['APL387.ttf', 'APL'],
+    kap: ['APL387.ttf', 'Kap'],
+    tinyapl: ['APL387.ttf', 'TinyAPL'],
+    j: ['JetBrainsMono-Regular.ttf', 'JetBrains Mono']
+};
+
+const MIME_TYPES = {
+    js: 'text/javascript',
+    wasm: 'application/wasm',
+    ttf: 'font/ttf',
+    kap: 'text/plain'
+};
+
+function mimeType(path) {
+    return MIME_TYPES[path.split('.').pop()] || 'application/octet-stream';
+}
+
+// Site root, where fonts/, src/ and wasm/ live
+const SITE_ROOT = new URL('../', import.meta.url);
+
+async function fetchBytes(url) {
+    const response = await fetch(url);
+    if (!response.ok) throw new Error(`Could not fetch ${url} (${response.status})`);
+    return new Uint8Array(await response.arrayBuffer());
+}
+
+function decodeText(bytes) {
+    return new TextDecoder().decode(bytes);
+}
+
+// Relative ES module imports in a source file
+function relativeImports(source) {
+    return [...source.matchAll(/\b(?:from|import)\s*['"](\.{1,2}\/[^'"]+)['"]/g)].map(match => match[1]);
+}
+
+/**
+ * Collect the interpreter files for a language
+ * @param {string} lang - Language id
+ * @param {Function} load - async (url) => Uint8Array
+ * @returns {Promise<Object<string, Uint8Array>>} - Bytes by path under wasm/ (empty for APL)
+ */
+async function collectRuntimeFiles(lang, load) {
+    const files = {};
+    for (const path of RUNTIME_FILES[lang] || []) {
+        files[path] = await load(new URL(`wasm/${path}`, SITE_ROOT));
+    }
+
+    // Kap loads its standard library with XHR once the bundle starts
+    if (lang === 'kap') {
+        const bundle = decodeText(files['kap/standalonejs.js']);
+        const library = new Set(bundle.match(/standard-lib\/[\w-]+\.kap/g));
+        for (const file of library) {
+            files[`kap/${file}`] = await load(new URL(`wasm/kap/${file}`, SITE_ROOT));
+        }
+    }
+
+    // TinyAPL imports its WASI shim from unpkg: bring the shim's modules along
+    if (lang === 'tinyapl') {
+        const source = decodeText(files['tinyapl/tinyapl.js']);
+        const shimUrl = /from '(https:\/\/unpkg\.com\/@bjorn3\/browser_wasi_shim@[^']*)'/.exec(source)[1];
+        const pending = [[new URL(shimUrl), TINYAPL_WASI_SHIM]];
+        while (pending.length) {
+            const [url, path] = pending.pop();
+            if (files[path]) continue;
+            files[path] = await load(url);
+            for (const specifier of relativeImports(decodeText(files[path]))) {
+                pending.push([new URL(specifier, url), new URL(specifier, `https://files/${path}`).pathname.slice(1)]);
+            }
+        }
+    }
+    return files;
+}
+
+function toBase64(bytes) {
+    return new Promise((resolve, reject) => {
+        const reader = new FileReader();
+        reader.onload = () => resolve(reader.result.slice(reader.result.indexOf(',') + 1));
+        reader.onerror = () => reject(reader.error);
+        reader.readAsDataURL(new Blob([bytes]));
+    });
+}
+
+function escapeHtml(text) {
+    return text.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;').replace(/"/g, '&quot;');
+}
+
+/**
+ * Build a standalone HTML page for a snippet
+ * @param {object} snippet
+ * @param {string} snippet.lang - Language id
+ * @param {string} snippet.langName - Display name
+ * @param {string} snippet.code - Code
+ * @param {object} [snippet.result] - Last result ({ success, output, value? }) if it is for this code
+ * @param {string} [snippet.theme] - Theme id from themes.js (default: the default theme)
+ * @param {object} [options]
+ * @param {Function} [options.load] - async (url) => Uint8Array (default: fetch)
+ * @param {Function} [options.onProgress] - (message) => void
+ * @returns {Promise<string>} - HTML
+ */
+export async function buildStandaloneHtml(snippet, options = {}) {
+    const load = options.load || fetchBytes;
+    const progress = options.onProgress || (() => {});
+    const [fontFile, fontFamily] = FONTS[snippet.lang] || FONTS.apl;
+
+    progress('collecting files…');
+    const sources = {
+        'src/syntax.js': await load(new URL('src/syntax.js', SITE_ROOT)),
+        'src/array-display.js': await load(new URL('src/array-display.js', SITE_ROOT)),
+        'src/runtimes.js': await load(new URL('src/runtimes.js', SITE_ROOT))
+    };
+    const runtimeFiles = await collectRuntimeFiles(snippet.lang, load);
+    for (const [path, bytes] of Object.entries(runtimeFiles)) sources[`wasm/${path}`] = bytes;
+    const font = await load(new URL(`fonts/${fontFile}`, SITE_ROOT));
+    // Syntax classes from theme.css, without its font faces (they point at
+    // fonts/), then the chosen theme's colors over its defaults
+    const themeCss = decodeText(await load(new URL('src/theme.css', SITE_ROOT)))
+        .replace(/@font-face\s*\{[^}]*\}/g, '');
+    const themeVars = Object.entries(themeVariables(snippet.theme))
+        .map(([name, value]) => `    ${name}: ${value};`).join('\n');
+
+    progress('encoding…');
+    const files = {};
+    for (const [path, bytes] of Object.entries(sources)) files[path] = [mimeType(path), await toBase64(bytes)];
+
+    const data = {
+        lang: snippet.lang,
+        langName: snippet.langName,
+        code: snippet.code,
+        result: snippet.result
+            ? { success: snippet.result.success, output: snippet.result.output || '', value: snippet.result.value || null }
+            : null,
+        runnable: Boolean(RUNTIME_FILES[snippet.lang]),
+        files
+    };
+    // A JSON block can hold anything but "</script"
+    const json = JSON.stringify(data).replace(/</g, '\\u003c');
+
+    return `<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>ArrayBox · ${escapeHtml(snippet.langName)}</title>
+<style>
+@font-face {
+    font-family: '${fontFamily}';
+    src: url(data:font/ttf;base64,${await toBase64(font)}) format('truetype');
+}
+${themeCss}
+:root {
+${themeVars}
+}
+${PAGE_CSS.replace(/FONT_FAMILY/g, fontFamily)}
+</style>
+</head>
+<body>
+<main>
+    <div class="bar">
+        <span class="title">ArrayBox · ${escapeHtml(snippet.langName)}</span>
+        <span class="status" id="status"></span>
+        <button id="run"${data.runnable ? '' : ' hidden'}>run (enter)</button>
+    </div>
+    <div class="editor">
+        <pre class="view" id="view" aria-hidden="true"></pre>
+        <textarea id="code" spellcheck="false"${data.runnable ? '' : ' readonly'}></textarea>
+    </div>
+    <pre class="output" id="output" hidden></pre>
+    <p class="note">${data.runnable
+        ? 'Runs offline: the interpreter is inside this file.'
+        : `${escapeHtml(snippet.langName)} runs on a server, so this file shows the saved output.`}</p>
+</main>
+<script type="application/json" id="arraybox-export">${json}</script>
+<script type="module">${PAGE_SCRIPT}</script>
+</body>
+</html>
+`;
+}
+
+const PAGE_CSS = `
+body {
+    margin: 0;
+    min-height: 100vh;
+    background: var(--bg-gradient);
+    color: var(--text-color);
+    font-family: ui-monospace, 'Courier New', monospace;
+}
+main {
+    max-width: 1000px;
+    margin: 0 auto;
+    padding: 40px 24px;
+}
+.bar {
+    display: flex;
+    align-items: center;
+    gap: 12px;
+    margin-bottom: 16px;
+}
+.title {
+    color: var(--text-secondary);
+    letter-spacing: 1px;
+    margin-right: auto;
+}
+.status {
+    color: var(--text-muted);
+}
+button {
+    background: var(--input-bg);
+    border: 2px solid var(--border-color);
+    border-radius: 8px;
+    color: var(--text-secondary);
+    font: inherit;
+    padding: 4px 12px;
+    cursor: pointer;
+}
+button:hover {
+    color: var(--text-color);
+    border-color: var(--border-hover);
+}
+.editor {
+    position: relative;
+    background: var(--input-bg);
+    border: 3px solid var(--border-color);
+    border-radius: 14px;
+}
+.editor:focus-within {
+    border-color: var(--focus-color);
+}
+.view, #code {
+    margin: 0;
+    padding: 16px 20px;
+    font-family: 'FONT_FAMILY', 'Courier New', monospace;
+    font-size: 28px;
+    line-height: 1.3;
+    white-space: pre-wrap;
+    word-break: break-all;
+    font-variant-ligatures: none;
+}
+.view {
+    color: var(--syntax-default);
+}
+#code {
+    position: absolute;
+    inset: 0;
+    width: 100%;
+    height: 100%;
+    box-sizing: border-box;
+    background: transparent;
+    border: none;
+    outline: none;
+    resize: none;
+    overflow: hidden;
+    color: transparent;
+    caret-color: var(--text-color);
+}
+.output {
+    margin: 16px 0 0;
+    padding: 16px 20px;
+    background: var(--output-bg);
+    border: 3px solid var(--border-color);
+    border-radius: 14px;
+    font-family: 'FONT_FAMILY', 'Courier New', monospace;
+    font-size: 22px;
+    overflow-x: auto;
+}
+.output.error {
+    border-color: var(--error-color);
+    color: #fca5a5;
+}
+.note {
+    color: var(--text-muted);
+    font-size: 13px;
+}
+`;
+
+// The exported page's script (kept free of "</script")
+const PAGE_SCRIPT = `
+const data = JSON.parse(document.getElementById('arraybox-export').textContent);
+const urls = {};
+await Promise.all(Object.entries(data.files).map(async ([path, [type, base64]]) => {
+    const blob = await (await fetch('data:' + type + ';base64,' + base64)).blob();
+    urls[path] = URL.createObjectURL(new Blob([blob], { type }));
+}));
+const { highlightCode, isAplTrainTree, highlightTrainTreeGlyphs } = await import(urls['src/syntax.js']);
+const { resultToHtml } = await import(urls['src/array-display.js']);
+
+const code = document.getElementById('code');
+const view = document.getElementById('view');
+const output = document.getElementById('output');
+const status = document.getElementById('status');
+
+function render() {
+    view.innerHTML = highlightCode(code.value, data.lang) + '\\n';
+}
+
+function showResult(result) {
+    output.hidden = false;
+    output.className = 'output' + (result.success ? '' : ' error');
+    const html = resultToHtml(result);
+    if (html) {
+        output.innerHTML = html;
+    } else if (data.lang === 'apl' && isAplTrainTree(result.output)) {
+        output.innerHTML = highlightTrainTreeGlyphs(result.output);
+    } else {
+        output.textContent = result.output;
+    }
+}
+
+let runtimes = null;
+async function run() {
+    if (!data.runnable || !code.value.trim()) return;
+    if (!runtimes) {
+        status.textContent = 'loading ' + data.langName + '…';
+        runtimes = await import(urls['src/runtimes.js']);
+        const wasm = {};
+        for (const [path, url] of Object.entries(urls)) {
+            if (path.startsWith('wasm/')) wasm[path.slice('wasm/'.length)] = url;
+        }
+        runtimes.setWasmFiles(wasm);
+    }
+    status.textContent = 'running…';
+    const result = await runtimes.evaluate(data.lang, code.value.trim());
+    status.textContent = '';
+    if (result.formatted && result.formatted.trim() !== code.value.trim()) {
+        code.value = result.formatted.trim();
+        render();
+    }
+    showResult(result);
+}
+
+code.value = data.code;
+render();
+if (data.result) showResult(data.result);
+code.addEventListener('input', render);
+code.addEventListener('keydown', (e) => {
+    // Enter runs, Shift+Enter inserts a newline (as on the site)
+    if (e.key === 'Enter' && !e.shiftKey) {
+        e.preventDefault();
+        run();
+    }
+});
+document.getElementById('run').addEventListener('click', run);
+`;
+
+export default {
+    buildStandaloneHtml
+};
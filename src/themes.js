@@ -0,0 +1,261 @@
+/**
+ * Array Box Themes
+ * Every color theme, defined once as data:
+ * - The editor applies a theme as the CSS variables in theme.css (applyTheme);
+ *   the syntax-* classes from highlightCode read those variables
+ * - servers/og-generator.cjs draws images in the same colors
+ * - theme.css's :root block is the default theme, generated from this file
+ *   by scripts/build-theme-css.cjs
+ *
+ * Each theme has `ui` colors for the page and `syntax` colors for code, one
+ * per token role (the roles syntax.js assigns classes for).
+ */
+
+export const DEFAULT_THEME = 'dracula';
+
+export const THEMES = {
+    dracula: {
+        name: 'Dracula',
+        ui: {
+            bgGradient: ['#111827', '#1f2937', '#111827'],  // gray-900 via gray-800
+            bg: '#111827',
+            text: '#e5e7eb',
+            textSoft: '#d1d5db',
+            textSecondary: '#9CA3AF',
+            textMuted: '#6b7280',
+            border: '#4b5563',
+            borderHover: '#6b7280',
+            divider: '#374151',
+            inputBg: '#1f2937',
+            outputBg: '#1f2937',
+            dropdownBg: '#1f2937',
+            dropdownHover: '#374151',
+            shadow: 'rgba(0, 0, 0, 0.4)',
+            focus: '#22c55e',
+            error: '#ef4444',
+        },
+        syntax: {
+            number: '#BD93F9',
+            comment: '#6272A4',
+            default: '#F8F8F2',
+            string: '#F1FA8C',
+            stringIncomplete: '#FF5555',
+            uiuaFunctionMonadic: '#8BE9FD',
+            uiuaFunctionDyadic: '#50FA7B',
+            uiuaModifierMonadic: '#F1FA8C',
+            uiuaModifierDyadic: '#FF79C6',
+            function: '#8BE9FD',
+            modifierMonadic: '#50FA7B',
+            modifierDyadic: '#F1FA8C',
+        },
+    },
+    light: {
+        name: 'Light',
+        ui: {
+            bgGradient: ['#f9fafb', '#f3f4f6', '#f9fafb'],
+            bg: '#f9fafb',
+            text: '#1f2937',
+            textSoft: '#374151',
+            textSecondary: '#4b5563',
+            textMuted: '#6b7280',
+            border: '#d1d5db',
+            borderHover: '#9ca3af',
+            divider: '#e5e7eb',
+            inputBg: '#ffffff',
+            outputBg: '#ffffff',
+            dropdownBg: '#ffffff',
+            dropdownHover: '#f3f4f6',
+            shadow: 'rgba(0, 0, 0, 0.12)',
+            focus: '#16a34a',
+            error: '#dc2626',
+        },
+        syntax: {
+            number: '#7c3aed',
+            comment: '#6b7280',
+            default: '#1f2937',
+            string: '#a16207',
+            stringIncomplete: '#dc2626',
+            uiuaFunctionMonadic: '#0891b2',
+            uiuaFunctionDyadic: '#16a34a',
+            uiuaModifierMonadic: '#a16207',
+            uiuaModifierDyadic: '#db2777',
+            function: '#0891b2',
+            modifierMonadic: '#16a34a',
+            modifierDyadic: '#a16207',
+        },
+    },
+    'high-contrast': {
+        name: 'High Contrast',
+        ui: {
+            bgGradient: ['#000000', '#000000', '#000000'],
+            bg: '#000000',
+            text: '#ffffff',
+            textSoft: '#ffffff',
+            textSecondary: '#e5e5e5',
+            textMuted: '#bfbfbf',
+            border: '#ffffff',
+            borderHover: '#ffff00',
+            divider: '#808080',
+            inputBg: '#000000',
+            outputBg: '#000000',
+            dropdownBg: '#000000',
+            dropdownHover: '#262626',
+            shadow: 'rgba(255, 255, 255, 0.25)',
+            focus: '#ffff00',
+            error: '#ff4d4d',
+        },
+        syntax: {
+            number: '#ffa500',
+            comment: '#a0a0a0',
+            default: '#ffffff',
+            string: '#ffff00',
+            stringIncomplete: '#ff4d4d',
+            uiuaFunctionMonadic: '#00ffff',
+            uiuaFunctionDyadic: '#00ff00',
+            uiuaModifierMonadic: '#ffff00',
+            uiuaModifierDyadic: '#ff80ff',
+            function: '#00ffff',
+            modifierMonadic: '#00ff00',
+            modifierDyadic: '#ffff00',
+        },
+    },
+    'solarized-dark': {
+        name: 'Solarized Dark',
+        ui: {
+            bgGradient: ['#002b36', '#073642', '#002b36'],  // base03 via base02
+            bg: '#002b36',
+            text: '#93a1a1',
+            textSoft: '#93a1a1',
+            textSecondary: '#839496',
+            textMuted: '#657b83',
+            border: '#586e75',
+            borderHover: '#657b83',
+            divider: '#0e4b59',
+            inputBg: '#073642',
+            outputBg: '#073642',
+            dropdownBg: '#073642',
+            dropdownHover: '#0e4b59',
+            shadow: 'rgba(0, 0, 0, 0.4)',
+            focus: '#859900',
+            error: '#dc322f',
+        },
+        syntax: {
+            number: '#6c71c4',
+            comment: '#586e75',
+            default: '#93a1a1',
+            string: '#b58900',
+            stringIncomplete: '#dc322f',
+            uiuaFunctionMonadic: '#2aa198',
+            uiuaFunctionDyadic: '#859900',
+            uiuaModifierMonadic: '#b58900',
+            uiuaModifierDyadic: '#d33682',
+            function: '#268bd2',
+            modifierMonadic: '#859900',
+            modifierDyadic: '#b58900',
+        },
+    },
+    'solarized-light': {
+        name: 'Solarized Light',
+        ui: {
+            bgGradient: ['#eee8d5', '#fdf6e3', '#eee8d5'],  // base2 via base3
+            bg: '#eee8d5',
+            text: '#586e75',
+            textSoft: '#586e75',
+            textSecondary: '#657b83',
+            textMuted: '#93a1a1',
+            border: '#93a1a1',
+            borderHover: '#839496',
+            divider: '#eee8d5',
+            inputBg: '#fdf6e3',
+            outputBg: '#fdf6e3',
+            dropdownBg: '#fdf6e3',
+            dropdownHover: '#eee8d5',
+            shadow: 'rgba(0, 0, 0, 0.12)',
+            focus: '#859900',
+            error: '#dc322f',
+        },
+        syntax: {
+            number: '#6c71c4',
+            comment: '#93a1a1',
+            default: '#586e75',
+            string: '#b58900',
+            stringIncomplete: '#dc322f',
+            uiuaFunctionMonadic: '#2aa198',
+            uiuaFunctionDyadic: '#859900',
+            uiuaModifierMonadic: '#b58900',
+            uiuaModifierDyadic: '#d33682',
+            function: '#268bd2',
+            modifierMonadic: '#859900',
+            modifierDyadic: '#b58900',
+        },
+    },
+};
+
+// CSS variable for each ui color (bgGradient becomes --bg-gradient)
+const UI_VARIABLES = {
+    bg: '--bg-color',
+    text: '--text-color',
+    textSoft: '--text-soft',
+    textSecondary: '--text-secondary',
+    textMuted: '--text-muted',
+    border: '--border-color',
+    borderHover: '--border-hover',
+    divider: '--divider-color',
+    inputBg: '--input-bg',
+    outputBg: '--output-bg',
+    dropdownBg: '--dropdown-bg',
+    dropdownHover: '--dropdown-hover',
+    shadow: '--shadow-color',
+    focus: '--focus-color',
+    error: '--error-color',
+};
+
+/**
+ * Get a theme by id, falling back to the default for unknown ids
+ * @param {string} [id]
+ * @returns {object} - { name, ui, syntax }
+ */
+export function getTheme(id) {
+    return THEMES[id] || THEMES[DEFAULT_THEME];
+}
+
+/**
+ * The CSS variables for a theme
+ * Syntax roles map to --syntax-<role> in kebab case (uiuaFunctionMonadic is
+ * --syntax-uiua-function-monadic, the variable behind that syntax class)
+ * @param {string} id - Theme id
+ * @returns {Object<string, string>} - Variable name -> value
+ */
+export function themeVariables(id) {
+    const { ui, syntax } = getTheme(id);
+    const variables = {
+        '--bg-gradient': `linear-gradient(to bottom right, ${ui.bgGradient.join(', ')})`,
+    };
+    for (const [key, name] of Object.entries(UI_VARIABLES)) {
+        variables[name] = ui[key];
+    }
+    for (const [role, color] of Object.entries(syntax)) {
+        variables[`--syntax-${role.replace(/[A-Z]/g, c => '-' + c.toLowerCase())}`] = color;
+    }
+    return variables;
+}
+
+/**
+ * Apply a theme to a page by setting its CSS variables on an element
+ * @param {string} id - Theme id
+ * @param {HTMLElement} [root=document.documentElement]
+ */
+export function applyTheme(id, root = document.documentElement) {
+    for (const [name, value] of Object.entries(themeVariables(id))) {
+        root.style.setProperty(name, value);
+    }
+    root.dataset.theme = THEMES[id] ? id : DEFAULT_THEME;
+}
+
+export default {
+    DEFAULT_THEME,
+    THEMES,
+    getTheme,
+    themeVariables,
+    applyTheme
+};
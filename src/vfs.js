@@ -0,0 +1,122 @@
+/**
+ * Virtual file system
+ * Project files kept in the browser's IndexedDB, for programs to import:
+ * the runtimes write them where each language's imports look (see the
+ * `files` option in src/runtimes.js) and the APL server fixes them for
+ * ⎕FIX 'file://name'.
+ *
+ * Files are text stored by path ('lib.bqn', 'util/strings.ijs'); directories
+ * exist only as the paths' prefixes. Every function returns a promise.
+ */
+
+const DB_NAME = 'arraybox';
+const DB_VERSION = 1;
+const STORE = 'files';
+
+let database = null;
+
+function openDatabase() {
+    if (!database) {
+        database = new Promise((resolve, reject) => {
+            const request = indexedDB.open(DB_NAME, DB_VERSION);
+            request.onupgradeneeded = () => request.result.createObjectStore(STORE, { keyPath: 'path' });
+            request.onsuccess = () => resolve(request.result);
+            request.onerror = () => reject(request.error);
+        });
+    }
+    return database;
+}
+
+// Run fn(store) in a transaction; resolves, once it is committed, to the
+// result of the request fn returned
+async function transaction(mode, fn) {
+    const db = await openDatabase();
+    return new Promise((resolve, reject) => {
+        const tx = db.transaction(STORE, mode);
+        const request = fn(tx.objectStore(STORE));
+        tx.oncomplete = () => resolve(request ? request.result : undefined);
+        tx.onerror = () => reject(tx.error);
+        tx.onabort = () => reject(tx.error);
+    });
+}
+
+/**
+ * Clean up a path: forward slashes, no leading slash or empty and '.' parts.
+ * Returns null for paths that are empty or leave the project ('..').
+ */
+export function normalizePath(path) {
+    const parts = String(path).split(/[\\/]+/).filter(part => part && part !== '.');
+    if (parts.length === 0 || parts.includes('..')) return null;
+    return parts.join('/');
+}
+
+function checkedPath(path) {
+    const normalized = normalizePath(path);
+    if (!normalized) throw new Error(`Invalid file path: ${path}`);
+    return normalized;
+}
+
+/**
+ * All files as { path: text }, in path order
+ */
+export async function getFiles() {
+    const records = await transaction('readonly', store => store.getAll());
+    return Object.fromEntries(records.map(({ path, text }) => [path, text]));
+}
+
+/**
+ * Text of a file, or null if there is none
+ */
+export async function readFile(path) {
+    const record = await transaction('readonly', store => store.get(checkedPath(path)));
+    return record ? record.text : null;
+}
+
+/**
+ * Create or replace a file; resolves to its normalized path
+ */
+export async function writeFile(path, text) {
+    const normalized = checkedPath(path);
+    await transaction('readwrite', store => { store.put({ path: normalized, text: String(text) }); });
+    return normalized;
+}
+
+export async function deleteFile(path) {
+    await transaction('readwrite', store => { store.delete(checkedPath(path)); });
+}
+
+/**
+ * Move a file to a new path (replacing any file there); resolves to the new path
+ */
+export async function renameFile(from, to) {
+    const source = checkedPath(from);
+    const target = checkedPath(to);
+    const text = await readFile(source);
+    if (text === null) throw new Error(`No such file: ${from}`);
+    await transaction('readwrite', store => {
+        store.delete(source);
+        store.put({ path: target, text });
+    });
+    return target;
+}
+
+/**
+ * Replace every file with `files` ({ path: text }), e.g. a project permalink's
+ */
+export async function replaceFiles(files) {
+    const records = Object.entries(files).map(([path, text]) => ({ path: checkedPath(path), text: String(text) }));
+    await transaction('readwrite', store => {
+        store.clear();
+        for (const record of records) store.put(record);
+    });
+}
+
+export default {
+    normalizePath,
+    getFiles,
+    readFile,
+    writeFile,
+    deleteFile,
+    renameFile,
+    replaceFiles
+};
@@ -0,0 +1,143 @@
+/**
+ * Worker Runtimes
+ * The runtimes of runtimes.js, each running in its own Web Worker
+ * (src/runtime-worker.js) so a long evaluation never freezes the page.
+ *
+ * Every runtime exposes { load, isReady, getError, eval, reset } like its
+ * runtimes.js counterpart, but eval is always async and takes options:
+ *   signal   - AbortSignal; aborting it stops the evaluation
+ *   onOutput - (text) => void, output as it is printed (BQN, TinyAPL)
+ *   input    - Input data for the program to read (see runtimes.js)
+ *   files    - Project files for the program to import (see runtimes.js)
+ * Evaluations running longer than the time limit are stopped as well.
+ *
+ * Stopping terminates the worker and loads the interpreter in a new one, so
+ * everything defined in it is lost; the evaluation resolves to
+ * { success: false, output, stopped: true }.
+ */
+
+const WORKER_URL = new URL('./runtime-worker.js', import.meta.url);
+
+// Default time limit, the same as the APL server's
+export const DEFAULT_TIME_LIMIT = 10000;
+
+let timeLimit = DEFAULT_TIME_LIMIT;
+
+/**
+ * Set the time limit for evaluations
+ * @param {number} ms - Milliseconds; 0 for none
+ */
+export function setTimeLimit(ms) {
+    timeLimit = ms;
+}
+
+export function getTimeLimit() {
+    return timeLimit;
+}
+
+function createWorkerRuntime(lang, label) {
+    let worker = null;
+    let nextId = 0;
+    const pending = new Map();  // Request id -> { resolve, onOutput }
+    const state = { ready: false, error: null, version: null, loading: null };
+
+    function start() {
+        worker = new Worker(WORKER_URL, { type: 'module' });
+        worker.onmessage = ({ data }) => {
+            const request = pending.get(data.id);
+            if (!request) return;
+            if ('output' in data) {
+                if (request.onOutput) request.onOutput(data.output);
+                return;
+            }
+            pending.delete(data.id);
+            request.resolve(data.value);
+        };
+        worker.onerror = (event) => {
+            event.preventDefault();
+            const message = `${label} worker error: ${event.message || 'Unknown error'}`;
+            end({ success: false, output: message, loaded: false, error: message });
+        };
+    }
+
+    function send(action, code, onOutput, { input, files } = {}) {
+        if (!worker) start();
+        const id = nextId++;
+        return new Promise((resolve) => {
+            pending.set(id, { resolve, onOutput });
+            worker.postMessage({ id, action, lang, code, input, files });
+        });
+    }
+
+    // End the worker, resolving everything waiting on it with value
+    function end(value) {
+        if (worker) worker.terminate();
+        worker = null;
+        for (const { resolve } of pending.values()) resolve(value);
+        pending.clear();
+        state.ready = false;
+        state.loading = null;
+    }
+
+    function load() {
+        if (state.ready) return Promise.resolve(true);
+        if (!state.loading) {
+            state.loading = send('load').then(({ loaded, error, version }) => {
+                state.ready = !!loaded;
+                state.error = loaded ? null : new Error(error || 'Unknown error');
+                state.version = version;
+                return state.ready;
+            });
+        }
+        return state.loading;
+    }
+
+    async function evaluate(code, options = {}) {
+        if (!(await load())) {
+            return { success: false, output: `${label} failed to load: ${state.error.message}` };
+        }
+        const { signal, onOutput, input, files } = options;
+        if (signal && signal.aborted) return { success: false, output: 'Stopped', stopped: true };
+
+        const stop = (output) => {
+            end({ success: false, output, stopped: true });
+            load();
+        };
+        const onAbort = () => stop('Stopped');
+        const timer = timeLimit ? setTimeout(() => stop(`Stopped after ${timeLimit / 1000} s (time limit)`), timeLimit) : null;
+        if (signal) signal.addEventListener('abort', onAbort);
+        try {
+            return await send('eval', code, onOutput, { input, files });
+        } finally {
+            clearTimeout(timer);
+            if (signal) signal.removeEventListener('abort', onAbort);
+        }
+    }
+
+    return {
+        load,
+        isReady: () => state.ready,
+        getError: () => state.error,
+        eval: evaluate,
+        version: () => state.version,
+        format: (code) => send('format', code),
+        reset: async () => (await load()) && send('reset')
+    };
+}
+
+export const bqn = createWorkerRuntime('bqn', 'CBQN');
+export const uiua = createWorkerRuntime('uiua', 'Uiua');
+export const tinyapl = createWorkerRuntime('tinyapl', 'TinyAPL');
+export const j = createWorkerRuntime('j', 'J');
+export const kap = createWorkerRuntime('kap', 'Kap JS');
+
+export default {
+    DEFAULT_TIME_LIMIT,
+    setTimeLimit,
+    getTimeLimit,
+    bqn,
+    uiua,
+    tinyapl,
+    j,
+    kap
+};
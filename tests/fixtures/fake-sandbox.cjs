@@ -0,0 +1,25 @@
+/**
+ * Preload for servers in tests/servers.mjs (node --require): sandbox.cjs is
+ * replaced by a fake whose "evaluation" echoes the program it was given, so
+ * tests see exactly what a server would run in Docker
+ */
+
+const Module = require('module');
+const path = require('path');
+
+const SANDBOX = path.join(__dirname, '..', '..', 'servers', 'sandbox.cjs');
+
+const fakeSandbox = {
+    CONFIG: { images: { apl: 'fake', bqn: 'fake' }, prewarmLanguages: [] },
+    executeInSandbox: async (lang, code) => ({ success: true, output: code }),
+    isSandboxAvailable: async () => true,
+    isValidSessionId: (id) => typeof id === 'string' && /^[\w-]{1,64}$/.test(id),
+    resetAplSession: () => {},
+    getStatus: () => ({ fake: true })
+};
+
+const load = Module._load;
+Module._load = function (request, parent, isMain) {
+    if (Module._resolveFilename(request, parent, isMain) === SANDBOX) return fakeSandbox;
+    return load.apply(this, arguments);
+};
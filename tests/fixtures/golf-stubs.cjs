@@ -0,0 +1,40 @@
+/**
+ * Preload for eval-server.cjs in tests/servers.mjs (node --require):
+ * - sandbox.cjs is the fake of fake-sandbox.cjs, whose "evaluation" echoes
+ *   the program, so a verdict that leaked hidden cases would carry their inputs
+ * - leaderboard.cjs loads one problem with a hidden case instead of problems/
+ */
+
+require('./fake-sandbox.cjs');
+
+const Module = require('module');
+const path = require('path');
+
+const SERVERS_DIR = path.join(__dirname, '..', '..', 'servers');
+
+const PROBLEM = {
+    id: 'secret-sum',
+    title: 'Secret Sum',
+    statement: 'Sum a list.',
+    tests: {
+        bqn: [
+            { input: '1‿2', expected: '3' },
+            { input: '31337‿1', expected: '31338', hidden: true }
+        ]
+    }
+};
+
+const load = Module._load;
+Module._load = function (request, parent, isMain) {
+    const exports = load.apply(this, arguments);
+    if (Module._resolveFilename(request, parent, isMain) === path.join(SERVERS_DIR, 'leaderboard.cjs')) {
+        return {
+            ...exports,
+            loadProblems: async () => {
+                const { normalizeProblem } = await exports.loadGolf();
+                return { [PROBLEM.id]: normalizeProblem(PROBLEM).problem };
+            }
+        };
+    }
+    return exports;
+};
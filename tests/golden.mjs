@@ -0,0 +1,253 @@
+#!/usr/bin/env node
+/**
+ * Golden-output regression tests for every Array Box language
+ *
+ * Each tests/corpus/<lang>.txt holds a list of cases; the output of running
+ * each case is compared against tests/golden/<lang>.txt so that updating a
+ * WASM build (scripts/update-uiua-wasm.sh, update-j-wasm.sh, ...) cannot
+ * silently change output formatting.
+ *
+ * BQN, Uiua, TinyAPL, J and Kap are evaluated with src/runtimes.js - the same
+ * evaluate paths index.html uses. APL is sent to apl-server's POST /eval, which
+ * needs Dyalog; without a reachable server APL is skipped, and the summary says
+ * so (--strict makes that a failure). tests/golden/apl.txt is recorded with
+ * --update against a server, so until then APL has a corpus but no golden file.
+ *
+ * Usage:
+ *   node tests/golden.mjs [options] [lang...]
+ *
+ * Options:
+ *   --update           Rewrite golden files from the current output
+ *   --apl-server <url> APL server to test against (default: http://localhost:8081)
+ *   --strict           Fail when a language is skipped (e.g. no APL server)
+ *   --verbose          Show interpreter loading messages on stderr
+ *
+ * File format (corpus and golden): each case starts with a header line
+ * "==== <name>" and runs until the next header. Golden headers carry the
+ * result status: "==== <name> [ok]" or "==== <name> [error]". In the corpus,
+ * lines after a "---- input" line are the case's input data (eval's `input`).
+ *
+ * Exit status: 0 if every case matched, 1 on any mismatch or missing golden
+ * (or, with --strict, any skipped language).
+ */
+
+import fs from 'node:fs';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+// Node's WASI (used for TinyAPL) prints an experimental warning on first use
+process.removeAllListeners('warning');
+
+const { runtimes, evaluate, setLogger } = await import('../src/runtimes.js');
+
+const TESTS_DIR = path.dirname(fileURLToPath(import.meta.url));
+const CORPUS_DIR = path.join(TESTS_DIR, 'corpus');
+const GOLDEN_DIR = path.join(TESTS_DIR, 'golden');
+
+const HEADER = /^==== (\S+)(?: \[(ok|error)\])?$/;
+const INPUT_SEPARATOR = '\n---- input\n';
+const LANGUAGES = ['apl', ...Object.keys(runtimes)];
+
+function parseArgs(argv) {
+    const options = { update: false, aplServer: 'http://localhost:8081', verbose: false, strict: false, languages: [] };
+    for (let i = 0; i < argv.length; i++) {
+        const arg = argv[i];
+        if (arg === '--update') {
+            options.update = true;
+        } else if (arg === '--apl-server') {
+            options.aplServer = argv[++i];
+        } else if (arg.startsWith('--apl-server=')) {
+            options.aplServer = arg.slice('--apl-server='.length);
+        } else if (arg === '--verbose') {
+            options.verbose = true;
+        } else if (arg === '--strict') {
+            options.strict = true;
+        } else if (LANGUAGES.includes(arg)) {
+            options.languages.push(arg);
+        } else {
+            console.error(`Unknown argument: ${arg}`);
+            console.error(`Usage: node tests/golden.mjs [--update] [--strict] [--apl-server <url>] [${LANGUAGES.join('|')}...]`);
+            process.exit(2);
+        }
+    }
+    if (options.languages.length === 0) options.languages = LANGUAGES;
+    return options;
+}
+
+/**
+ * Parse a corpus or golden file into [{ name, status, text }]
+ */
+function parseCases(file) {
+    const cases = [];
+    let current = null;
+    for (const line of fs.readFileSync(file, 'utf8').split('\n')) {
+        const match = line.match(HEADER);
+        if (match) {
+            current = { name: match[1], status: match[2] || null, lines: [] };
+            cases.push(current);
+        } else if (current) {
+            current.lines.push(line);
+        }
+    }
+    return cases.map(({ name, status, lines }) => ({
+        name,
+        status,
+        text: lines.join('\n').replace(/\n+$/, '')
+    }));
+}
+
+function formatGolden(results) {
+    return results
+        .map(({ name, success, output }) => `==== ${name} [${success ? 'ok' : 'error'}]\n${output}`.replace(/\n+$/, ''))
+        .join('\n') + '\n';
+}
+
+/**
+ * Evaluate APL through apl-server's POST /eval, the same request index.html makes
+ */
+async function evaluateApl(server, code, input) {
+    const response = await fetch(`${server.replace(/\/$/, '')}/eval`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ code, input })
+    });
+    const data = await response.json();
+    return { success: data.success !== false, output: data.output || data.error || '' };
+}
+
+// apl-server has no health route; any HTTP response means it is listening
+async function isServerUp(server) {
+    try {
+        await fetch(server, { signal: AbortSignal.timeout(2000) });
+        return true;
+    } catch {
+        return false;
+    }
+}
+
+/**
+ * Line diff (LCS) between expected and actual output, in unified style
+ */
+function diffLines(expected, actual) {
+    const a = expected.split('\n');
+    const b = actual.split('\n');
+    const lcs = Array.from({ length: a.length + 1 }, () => new Array(b.length + 1).fill(0));
+    for (let i = a.length - 1; i >= 0; i--) {
+        for (let j = b.length - 1; j >= 0; j--) {
+            lcs[i][j] = a[i] === b[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+        }
+    }
+
+    const lines = [];
+    let i = 0;
+    let j = 0;
+    while (i < a.length || j < b.length) {
+        if (i < a.length && j < b.length && a[i] === b[j]) {
+            lines.push(`  ${a[i++]}`);
+            j++;
+        } else if (j < b.length && (i === a.length || lcs[i][j + 1] >= lcs[i + 1][j])) {
+            lines.push(`+ ${b[j++]}`);
+        } else {
+            lines.push(`- ${a[i++]}`);
+        }
+    }
+    return lines;
+}
+
+async function runLanguage(lang, options) {
+    const corpusFile = path.join(CORPUS_DIR, `${lang}.txt`);
+    const goldenFile = path.join(GOLDEN_DIR, `${lang}.txt`);
+    const summary = { passed: 0, failed: 0, skipped: null };
+
+    if (!fs.existsSync(corpusFile)) {
+        summary.skipped = 'no corpus';
+        console.log(`${lang}: skipped (${summary.skipped})`);
+        return summary;
+    }
+
+    let run;
+    if (lang === 'apl') {
+        if (!(await isServerUp(options.aplServer))) {
+            summary.skipped = `no APL server at ${options.aplServer}` +
+                (fs.existsSync(goldenFile) ? '' : `; ${path.relative(process.cwd(), goldenFile)} not recorded yet`);
+            console.log(`${lang}: skipped (${summary.skipped})`);
+            return summary;
+        }
+        run = (code, input) => evaluateApl(options.aplServer, code, input);
+    } else {
+        run = (code, input) => evaluate(lang, code, input === undefined ? {} : { input });
+    }
+
+    const cases = parseCases(corpusFile);
+    const results = [];
+    for (const testCase of cases) {
+        const [code, input] = `${testCase.text}\n`.split(INPUT_SEPARATOR);
+        const result = await run(code.replace(/\n$/, ''), input);
+        results.push({ name: testCase.name, success: result.success, output: (result.output || '').replace(/\n+$/, '') });
+    }
+
+    if (options.update) {
+        fs.mkdirSync(GOLDEN_DIR, { recursive: true });
+        fs.writeFileSync(goldenFile, formatGolden(results));
+        console.log(`${lang}: wrote ${results.length} golden outputs`);
+        summary.passed = results.length;
+        return summary;
+    }
+
+    const golden = fs.existsSync(goldenFile)
+        ? new Map(parseCases(goldenFile).map((c) => [c.name, c]))
+        : new Map();
+
+    for (const result of results) {
+        const expected = golden.get(result.name);
+        const status = result.success ? 'ok' : 'error';
+        if (!expected) {
+            console.log(`✗ ${lang}/${result.name}: no golden output (run with --update)`);
+            summary.failed++;
+        } else if (expected.status !== status || expected.text !== result.output) {
+            console.log(`✗ ${lang}/${result.name}`);
+            if (expected.status !== status) {
+                console.log(`    status: expected [${expected.status}], got [${status}]`);
+            }
+            for (const line of diffLines(expected.text, result.output)) {
+                console.log(`    ${line}`);
+            }
+            summary.failed++;
+        } else {
+            summary.passed++;
+        }
+    }
+
+    console.log(`${lang}: ${summary.passed} passed, ${summary.failed} failed`);
+    return summary;
+}
+
+async function main() {
+    const options = parseArgs(process.argv.slice(2));
+    setLogger(options.verbose ? { log: console.error, warn: console.error, error: console.error } : null);
+
+    let failed = 0;
+    const skipped = [];
+    for (const lang of options.languages) {
+        const summary = await runLanguage(lang, options);
+        failed += summary.failed;
+        if (summary.skipped) skipped.push(`${lang} (${summary.skipped})`);
+    }
+
+    if (skipped.length > 0) {
+        console.log(`\nNot tested: ${skipped.join(', ')}`);
+    }
+    if (failed > 0) {
+        console.log(`\n${failed} case(s) differ from golden output`);
+        return 1;
+    }
+    return options.strict && skipped.length > 0 ? 1 : 0;
+}
+
+main().then(
+    (status) => process.exit(status),
+    (error) => {
+        console.error(error);
+        process.exit(1);
+    }
+);
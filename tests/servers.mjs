@@ -0,0 +1,722 @@
+#!/usr/bin/env node
+/**
+ * Server tests: start servers/*.cjs on a free port and check what they answer
+ *
+ * Each test starts the servers it needs with its own flags and storage in a
+ * temporary directory, so nothing touches storage/. Servers that cannot start
+ * (e.g. permalink-server.cjs before `npm install`) fail their tests. The
+ * sandbox test loads servers/sandbox.cjs itself, with a stand-in docker from
+ * tests/fixtures/bin.
+ *
+ * Usage:
+ *   node tests/servers.mjs [name...]     # all tests, or those whose name contains a filter
+ *
+ * Tests needing something this machine lacks (e.g. a SQLite driver) say so
+ * and count as not tested.
+ *
+ * Exit status: 0 if no test failed, 1 otherwise.
+ */
+
+import { spawn, spawnSync } from 'node:child_process';
+import fs from 'node:fs';
+import { createRequire } from 'node:module';
+import net from 'node:net';
+import os from 'node:os';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const ROOT = path.join(path.dirname(fileURLToPath(import.meta.url)), '..');
+const SERVERS_DIR = path.join(ROOT, 'servers');
+const FIXTURES_DIR = path.join(ROOT, 'tests', 'fixtures');
+
+const tests = [];
+
+function test(name, fn) {
+    tests.push({ name, fn });
+}
+
+class NotTested extends Error {}
+
+// End a test that cannot run here, saying why
+function notTested(reason) {
+    throw new NotTested(reason);
+}
+
+function assert(condition, message) {
+    if (!condition) throw new Error(message);
+}
+
+async function freePort() {
+    return new Promise((resolve, reject) => {
+        const probe = net.createServer();
+        probe.on('error', reject);
+        probe.listen(0, '127.0.0.1', () => {
+            const { port } = probe.address();
+            probe.close(() => resolve(port));
+        });
+    });
+}
+
+/**
+ * Start node servers/<script> <port> ...args and wait for GET /health
+ * @param {object} [options] - { env, preload: a module to --require first }
+ * @returns {Promise<{ url: string, stop: Function }>}
+ */
+async function startServer(script, args = [], options = {}) {
+    const port = await freePort();
+    const nodeArgs = options.preload ? ['--require', options.preload] : [];
+    const child = spawn(process.execPath, [...nodeArgs, path.join(SERVERS_DIR, script), String(port), ...args], {
+        env: { ...process.env, ...options.env },
+        stdio: ['ignore', 'pipe', 'pipe']
+    });
+    let log = '';
+    child.stdout.on('data', chunk => { log += chunk; });
+    child.stderr.on('data', chunk => { log += chunk; });
+
+    const url = `http://127.0.0.1:${port}`;
+    const stop = () => new Promise(resolve => {
+        if (child.exitCode !== null) return resolve();
+        child.once('exit', resolve);
+        child.kill();
+    });
+
+    const deadline = Date.now() + 10000;
+    while (Date.now() < deadline) {
+        if (child.exitCode !== null) break;
+        try {
+            await fetch(`${url}/health`, { signal: AbortSignal.timeout(1000) });
+            return { url, stop };
+        } catch {
+            await new Promise(resolve => setTimeout(resolve, 100));
+        }
+    }
+    await stop();
+    throw new Error(`${script} did not start:\n${log.trim()}`);
+}
+
+function tempDir() {
+    return fs.mkdtempSync(path.join(os.tmpdir(), 'arraybox-test-'));
+}
+
+// ---- Permalink storage and migration ----
+
+const permalinkStore = createRequire(import.meta.url)(path.join(SERVERS_DIR, 'permalink-store.cjs'));
+
+/**
+ * Write links to a store, reopen it and describe what it holds
+ * @returns {Promise<object>} - { count, entries, versions, shared, owned }
+ */
+async function storeRoundTrip(spec) {
+    const { createStore, contentHash, countVersions } = permalinkStore;
+    let store = createStore(spec);
+    await store.insert('AbCd', { lang: 'bqn', code: '1+1', owner: 'f00d' });
+    await store.insertMany([
+        ['AbCd@2', { lang: 'bqn', code: '2+2' }],
+        ['Lgcy', { lang: 'j', code: 'i. 3' }],
+        ['Gone', { lang: 'j', code: 'i. 4' }]
+    ]);
+    await store.replace('Lgcy', { lang: 'j', code: 'i. 5' });
+    await store.delete('Gone');
+    await store.close();
+
+    store = createStore(spec);
+    try {
+        return {
+            count: store.count(),
+            entries: permalinkStore.canonicalJson(Object.fromEntries(store.entries())),
+            versions: countVersions(store, 'AbCd'),
+            shared: store.findByHash(contentHash({ lang: 'j', code: 'i. 5' })),
+            owned: store.findByHash(contentHash({ lang: 'bqn', code: '1+1' }))
+        };
+    } finally {
+        await store.close();
+    }
+}
+
+test('permalink json store keeps links across a reopen', async () => {
+    const dir = tempDir();
+    try {
+        const summary = await storeRoundTrip(`json:${path.join(dir, 'permalinks.json')}`);
+        const expected = {
+            count: 3,
+            entries: permalinkStore.canonicalJson({
+                AbCd: { lang: 'bqn', code: '1+1', owner: 'f00d' },
+                'AbCd@2': { lang: 'bqn', code: '2+2' },
+                Lgcy: { lang: 'j', code: 'i. 5' }
+            }),
+            versions: 2,
+            shared: 'Lgcy',
+            owned: null
+        };
+        assert(JSON.stringify(summary) === JSON.stringify(expected), `unexpected store contents: ${JSON.stringify(summary)}`);
+    } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink sqlite store holds the same links as the json store', async () => {
+    const dir = tempDir();
+    try {
+        const json = await storeRoundTrip(`json:${path.join(dir, 'permalinks.json')}`);
+        let sqlite;
+        try {
+            sqlite = await storeRoundTrip(`sqlite:${path.join(dir, 'permalinks.db')}`);
+        } catch (e) {
+            if (e.message.startsWith('SQLite storage needs')) notTested('no SQLite driver (Node 22.5+ or better-sqlite3)');
+            throw e;
+        }
+        assert(JSON.stringify(sqlite) === JSON.stringify(json),
+            `sqlite store differs:\n  sqlite ${JSON.stringify(sqlite)}\n  json   ${JSON.stringify(json)}`);
+    } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink migration copies new links and reports conflicts', async () => {
+    const dir = tempDir();
+    const from = path.join(dir, 'from.json');
+    const to = path.join(dir, 'to.json');
+    fs.writeFileSync(from, JSON.stringify({
+        Same: { lang: 'bqn', code: '1' },
+        Diff: { lang: 'bqn', code: 'source' },
+        Newx: { lang: 'bqn', code: '3' }
+    }));
+    fs.writeFileSync(to, JSON.stringify({
+        Same: { lang: 'bqn', code: '1' },
+        Diff: { lang: 'bqn', code: 'destination' }
+    }));
+    try {
+        const run = spawnSync(process.execPath, [path.join(ROOT, 'scripts', 'migrate-permalinks.cjs'),
+            '--from', `json:${from}`, '--to', `json:${to}`], { encoding: 'utf8' });
+        const log = run.stdout + run.stderr;
+        assert(run.status === 1, `expected exit status 1 for a conflict, got ${run.status}:\n${log}`);
+        assert(/Conflict: Diff /.test(log) && /Copied 1, skipped 1 already present, 1 conflicts/.test(log),
+            `expected the conflict and counts to be reported:\n${log}`);
+        const migrated = JSON.parse(fs.readFileSync(to, 'utf8'));
+        assert(migrated.Diff.code === 'destination' && migrated.Newx.code === '3',
+            `expected the destination's Diff to be kept and Newx copied, got ${JSON.stringify(migrated)}`);
+    } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+// ---- Permalink server: rate limits per client IP ----
+
+function postWithForwardedFor(url, forwardedFor) {
+    return fetch(`${url}/p`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json', 'X-Forwarded-For': forwardedFor },
+        body: '{}'
+    });
+}
+
+test('permalink rate limit ignores x-forwarded-for from clients', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [
+        `--storage=json:${path.join(dir, 'permalinks.json')}`, '--rate-limit=2/60', '--trusted-proxy=off'
+    ]);
+    try {
+        // A new spoofed address on every request must not buy a new bucket
+        const statuses = [];
+        for (const spoofed of ['1.1.1.1', '2.2.2.2', '3.3.3.3, 4.4.4.4']) {
+            statuses.push((await postWithForwardedFor(server.url, spoofed)).status);
+        }
+        assert(statuses[2] === 429, `expected the third request to be limited, got ${statuses.join(', ')}`);
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink rate limit keys on the entry a trusted proxy appended', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [
+        `--storage=json:${path.join(dir, 'permalinks.json')}`, '--rate-limit=2/60', '--trusted-proxy=127.0.0.1,::1'
+    ]);
+    try {
+        // Spoofed entries before the proxy's own one are ignored...
+        const statuses = [];
+        for (const spoofed of ['1.1.1.1', '2.2.2.2', '3.3.3.3']) {
+            statuses.push((await postWithForwardedFor(server.url, `${spoofed}, 10.0.0.1`)).status);
+        }
+        assert(statuses[2] === 429, `expected the third request from 10.0.0.1 to be limited, got ${statuses.join(', ')}`);
+
+        // ...while another client behind the proxy has its own bucket
+        const other = await postWithForwardedFor(server.url, '10.0.0.1, 10.0.0.2');
+        assert(other.status !== 429, 'expected 10.0.0.2 not to be limited');
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+// ---- Permalink server: what it stores ----
+
+function postPermalink(url, body) {
+    return fetch(`${url}/p`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(body)
+    });
+}
+
+function getPermalink(url, ref) {
+    return fetch(`${url}/p/${ref}`, { headers: { Accept: 'application/json' } });
+}
+
+test('permalink notebooks reject unknown cell languages', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${path.join(dir, 'permalinks.json')}`]);
+    try {
+        const rejected = await postPermalink(server.url, { notebook: { cells: [{ type: 'code', lang: 'constructor', code: '1' }] } });
+        assert(rejected.status === 400, `expected 400 for an unknown language, got ${rejected.status}`);
+        const accepted = await postPermalink(server.url, { notebook: { cells: [{ type: 'code', lang: 'bqn', code: '1' }] } });
+        assert(accepted.ok, `expected a bqn notebook to be saved, got ${accepted.status}`);
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink snippets reject unknown languages', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${path.join(dir, 'permalinks.json')}`]);
+    try {
+        const bodies = [
+            { lang: 'zzz', code: '1' },
+            { lang: { a: 1 }, code: '1' },
+            { collection: { snippets: [{ lang: 'bqn', code: '1' }, { lang: 'zzz', code: '2' }] } }
+        ];
+        for (const body of bodies) {
+            const response = await postPermalink(server.url, body);
+            assert(response.status === 400, `expected 400 for ${JSON.stringify(body)}, got ${response.status}`);
+        }
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink size limits count markdown and problem text', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [
+        `--storage=json:${path.join(dir, 'permalinks.json')}`, '--max-code=50', '--max-result=50'
+    ]);
+    try {
+        const long = 'x'.repeat(51);
+        const tests = (testCase) => ({ bqn: [{ input: '1', expected: '1', ...testCase }] });
+        const bodies = {
+            markdown: { notebook: { cells: [{ type: 'markdown', text: long }, { type: 'code', lang: 'bqn', code: '1' }] } },
+            statement: { problem: { title: 'T', statement: long, tests: tests({}) } },
+            'test input': { problem: { title: 'T', tests: tests({ input: long }) } },
+            'expected output': { problem: { title: 'T', tests: tests({ expected: long }) } }
+        };
+        for (const [what, body] of Object.entries(bodies)) {
+            const response = await postPermalink(server.url, body);
+            assert(response.status === 413, `expected 413 for a long ${what}, got ${response.status}`);
+        }
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink OG images are only served for live permalink refs', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${path.join(dir, 'permalinks.json')}`]);
+    try {
+        for (const ref of ['..%2F..%2Fpackage', '..%2Fpermalinks', 'AbCd%2F..%2F..%2Fserver']) {
+            for (const extension of ['png', 'svg']) {
+                const response = await fetch(`${server.url}/og/${ref}.${extension}`);
+                assert(response.status === 404, `expected 404 for /og/${ref}.${extension}, got ${response.status}`);
+            }
+        }
+
+        const { id, token } = await (await postPermalink(server.url, { lang: 'bqn', code: '1+1' })).json();
+        // Deleting takes the image down, so no version's image may be cached for long
+        const pinned = await fetch(`${server.url}/og/${id}@1.svg`);
+        const cacheControl = pinned.headers.get('cache-control') || '';
+        assert(pinned.ok && cacheControl === 'public, max-age=300' && pinned.headers.get('etag'),
+            `expected a short max-age and an ETag for a pinned image, got ${pinned.status} ${cacheControl}`);
+
+        const deleted = await fetch(`${server.url}/p/${id}`, { method: 'DELETE', headers: { Authorization: `Bearer ${token}` } });
+        assert(deleted.ok, `expected the permalink to be deleted, got ${deleted.status}`);
+        const image = await fetch(`${server.url}/og/${id}.png`);
+        assert(image.status === 404, `expected 404 for a deleted permalink's image, got ${image.status}`);
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink dedup never hands out a link someone else owns', async () => {
+    const dir = tempDir();
+    const file = path.join(dir, 'permalinks.json');
+    // A link from before owner tokens, which nobody can change
+    fs.writeFileSync(file, JSON.stringify({ Lgcy: { lang: 'bqn', code: '2+2' } }));
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${file}`]);
+    try {
+        const a = await (await postPermalink(server.url, { lang: 'bqn', code: '1+1' })).json();
+        const b = await (await postPermalink(server.url, { lang: 'bqn', code: '1+1' })).json();
+        assert(b.id !== a.id && b.token, `expected a second poster to get their own link, got ${JSON.stringify(b)}`);
+
+        await fetch(`${server.url}/p/${a.id}`, {
+            method: 'PUT',
+            headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${a.token}` },
+            body: JSON.stringify({ lang: 'bqn', code: 'evil' })
+        });
+        const shared = await (await fetch(`${server.url}/p/${b.id}`, { headers: { Accept: 'application/json' } })).json();
+        assert(shared.code === '1+1', `expected the second poster's link to keep its code, got ${JSON.stringify(shared)}`);
+
+        const legacy = await (await postPermalink(server.url, { lang: 'bqn', code: '2+2' })).json();
+        assert(legacy.id === 'Lgcy' && !legacy.token, `expected the unowned link to be shared, got ${JSON.stringify(legacy)}`);
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink versions never change once stored', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${path.join(dir, 'permalinks.json')}`]);
+    const put = (ref, token, code) => fetch(`${server.url}/p/${ref}`, {
+        method: 'PUT',
+        headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${token}` },
+        body: JSON.stringify({ lang: 'bqn', code })
+    });
+    const codeAt = async (ref) => (await (await fetch(`${server.url}/p/${ref}`, { headers: { Accept: 'application/json' } })).json()).code;
+    try {
+        const { id, token } = await (await postPermalink(server.url, { lang: 'bqn', code: '1+1' })).json();
+        const refused = await put(`${id}@1`, token, 'evil');
+        assert(refused.status === 405, `expected PUT on a numbered version to be refused, got ${refused.status}`);
+
+        const added = await (await put(id, token, '2+2')).json();
+        assert(added.id === `${id}@2` && added.version === 2, `expected PUT on the code to add version 2, got ${JSON.stringify(added)}`);
+        assert(await codeAt(`${id}@1`) === '1+1', 'expected version 1 to keep its code');
+        assert(await codeAt(id) === '2+2', 'expected the bare code to show the new version');
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+test('permalink versions resolve by number and list in history, and forks remember theirs', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${path.join(dir, 'permalinks.json')}`]);
+    const addVersion = async (code, token, body) => (await fetch(`${server.url}/p/${code}/versions`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${token}` },
+        body: JSON.stringify(body)
+    })).json();
+    try {
+        const { id, token } = await (await postPermalink(server.url, { lang: 'bqn', code: '1+1' })).json();
+        const second = await addVersion(id, token, { lang: 'bqn', code: '2+2\n3+3' });
+        assert(second.id === `${id}@2` && second.version === 2, `expected version 2, got ${JSON.stringify(second)}`);
+        const again = await addVersion(id, token, { lang: 'bqn', code: '2+2\n3+3' });
+        assert(again.version === 2, `expected saving the latest again not to add a version, got ${JSON.stringify(again)}`);
+
+        const latest = await (await getPermalink(server.url, id)).json();
+        assert(latest.code === '2+2\n3+3' && latest.version === 2 && latest.versions === 2, `unexpected latest: ${JSON.stringify(latest)}`);
+        const first = await (await getPermalink(server.url, `${id}@1`)).json();
+        assert(first.code === '1+1' && first.version === 1 && first.id === id, `unexpected version 1: ${JSON.stringify(first)}`);
+        for (const ref of [`${id}@3`, `${id}@0`]) {
+            const missing = await getPermalink(server.url, ref);
+            assert(missing.status === 404, `expected 404 for ${ref}, got ${missing.status}`);
+        }
+
+        const history = await (await fetch(`${server.url}/p/${id}/history`)).json();
+        const listed = history.versions.map(({ version, id, preview }) => `${version} ${id} ${preview}`);
+        assert(JSON.stringify(listed) === JSON.stringify([`1 ${id} 1+1`, `2 ${id}@2 2+2`]) && history.forkOf === null,
+            `unexpected history: ${JSON.stringify(history)}`);
+
+        // A fork of the bare code remembers the version it was made from
+        const fork = await (await postPermalink(server.url, { lang: 'bqn', code: '4+4', forkOf: id })).json();
+        const forkHistory = await (await fetch(`${server.url}/p/${fork.id}/history`)).json();
+        assert(forkHistory.forkOf === `${id}@2`, `expected the fork to remember ${id}@2, got ${JSON.stringify(forkHistory)}`);
+        const orphan = await postPermalink(server.url, { lang: 'bqn', code: '5+5', forkOf: `${id}@9` });
+        assert(orphan.status === 400, `expected 400 for a fork of a missing version, got ${orphan.status}`);
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+// ---- Permalink server: search ----
+
+test('permalink search finds saved links and forgets deleted ones', async () => {
+    const dir = tempDir();
+    const server = await startServer('permalink-server.cjs', [`--storage=json:${path.join(dir, 'permalinks.json')}`]);
+    const search = async (query) => {
+        const { total, results } = await (await fetch(`${server.url}/p?${new URLSearchParams(query)}`)).json();
+        return { total, codes: results.map(result => result.code).sort(), results };
+    };
+    try {
+        const reverse = await (await postPermalink(server.url, { lang: 'bqn', code: '⌽ 1‿2‿3' })).json();
+        const jReverse = await (await postPermalink(server.url, { lang: 'j', code: '|. 1 2 3 NB. Reverse' })).json();
+        const sum = await (await postPermalink(server.url, { lang: 'bqn', code: '+´ 1‿2‿3' })).json();
+        await postPermalink(server.url, {
+            problem: { title: 'Sum', statement: 'Add them up', tests: { bqn: [{ input: '31337', expected: '1', hidden: true }] } }
+        });
+
+        const glyph = await search({ q: '⌽' });
+        assert(glyph.total === 1 && glyph.codes[0] === reverse.id, `expected ⌽ to find one link, got ${JSON.stringify(glyph)}`);
+        const word = await search({ q: 'REVERSE' });
+        assert(word.codes.join() === jReverse.id, `expected a case-insensitive word match, got ${JSON.stringify(word)}`);
+        const both = await search({ q: '1‿2‿3 +´' });
+        assert(both.codes.join() === sum.id, `expected every term to have to match, got ${JSON.stringify(both)}`);
+        const phrase = await search({ q: '"1 2 3"' });
+        assert(phrase.codes.join() === jReverse.id, `expected a quoted phrase to match whole, got ${JSON.stringify(phrase)}`);
+        const inBqn = await search({ q: '1', lang: 'bqn' });
+        assert(inBqn.codes.join() === [reverse.id, sum.id].sort().join(), `expected the language filter to keep BQN links, got ${JSON.stringify(inBqn)}`);
+        const hidden = await search({ q: '31337' });
+        assert(hidden.total === 0, `hidden test input is searchable: ${JSON.stringify(hidden)}`);
+
+        // A new version is what search sees...
+        await fetch(`${server.url}/p/${sum.id}/versions`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${sum.token}` },
+            body: JSON.stringify({ lang: 'bqn', code: '×´ 1‿2‿3' })
+        });
+        const product = await search({ q: '×´' });
+        assert(product.codes.join() === sum.id && product.results[0].version === 2,
+            `expected the latest version to be found, got ${JSON.stringify(product)}`);
+        assert((await search({ q: '+´' })).total === 0, 'expected the old version not to be found');
+
+        // ...and a deleted link is gone
+        await fetch(`${server.url}/p/${reverse.id}`, { method: 'DELETE', headers: { Authorization: `Bearer ${reverse.token}` } });
+        const deleted = await search({ q: '⌽' });
+        assert(deleted.total === 0, `expected a deleted link not to be found, got ${JSON.stringify(deleted)}`);
+    } finally {
+        await server.stop();
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+});
+
+// ---- Eval server: LeetGolf never reveals hidden cases ----
+
+test('golf problem and submit responses leave out hidden cases', async () => {
+    // The stubbed problem's hidden case is 31337‿1 -> 31338
+    const server = await startServer('eval-server.cjs', [], { preload: path.join(FIXTURES_DIR, 'golf-stubs.cjs') });
+    try {
+        const problemText = await (await fetch(`${server.url}/golf/problems/secret-sum`)).text();
+        const { problem } = JSON.parse(problemText);
+        assert(problem && problem.tests.bqn.length === 2, `unexpected problem response: ${problemText}`);
+        assert(JSON.stringify(problem.tests.bqn[1]) === '{"hidden":true}', `hidden case exposed: ${problemText}`);
+        assert(!/3133[78]/.test(problemText), `hidden case data in problem response: ${problemText}`);
+
+        const verdictText = await (await fetch(`${server.url}/golf/submit`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ problem: 'secret-sum', lang: 'bqn', code: '+´', name: 'test' })
+        })).text();
+        const verdict = JSON.parse(verdictText);
+        assert(verdict.cases && verdict.cases.length === 2, `unexpected submit response: ${verdictText}`);
+        assert(JSON.stringify(verdict.cases[1]) === '{"hidden":true,"passed":false}', `hidden case exposed: ${verdictText}`);
+        assert(!/3133[78]/.test(verdictText), `hidden case data in submit response: ${verdictText}`);
+    } finally {
+        await server.stop();
+    }
+});
+
+test('golf judge fails submissions that only pass the visible cases', async () => {
+    // Judged with the in-browser BQN runtime, as bin/arraybox does
+    const { judge, normalizeProblem, publicVerdict } = await import('../src/golf.js');
+    const { evaluate, setLogger } = await import('../src/runtimes.js');
+    setLogger(null);
+    const { problem } = normalizeProblem({
+        id: 'secret-sum',
+        title: 'Secret Sum',
+        tests: {
+            bqn: [
+                { input: '1‿2', expected: '3' },
+                { input: '31337‿1', expected: '31338', hidden: true }
+            ]
+        }
+    });
+    const run = (lang, program) => evaluate(lang, program);
+
+    const hardcoded = publicVerdict(await judge(problem, 'bqn', '3˙', run));
+    assert(!hardcoded.passed, `hardcoded answer accepted: ${JSON.stringify(hardcoded)}`);
+    assert(hardcoded.cases[0].passed, `visible case failed: ${JSON.stringify(hardcoded)}`);
+    assert(JSON.stringify(hardcoded.cases[1]) === '{"hidden":true,"passed":false}',
+        `unexpected hidden verdict: ${JSON.stringify(hardcoded)}`);
+
+    const solved = publicVerdict(await judge(problem, 'bqn', '+´', run));
+    assert(solved.passed, `correct answer rejected: ${JSON.stringify(solved)}`);
+    assert(JSON.stringify(solved.cases[1]) === '{"hidden":true,"passed":true}',
+        `unexpected hidden verdict: ${JSON.stringify(solved)}`);
+});
+
+// ---- Eval server: request size ----
+
+test('eval body limit counts bytes, not characters', async () => {
+    const server = await startServer('eval-server.cjs', [], { preload: path.join(FIXTURES_DIR, 'fake-sandbox.cjs') });
+    const post = (code) => fetch(`${server.url}/eval`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ lang: 'bqn', code })
+    });
+    try {
+        // 100000 three-byte characters: under 256 KiB in UTF-16 units, over it in bytes
+        const accepted = await post('⍝'.repeat(1000));
+        assert(accepted.ok && (await accepted.json()).output === '⍝'.repeat(1000), 'expected a small body to be evaluated intact');
+        const rejected = await post('⍝'.repeat(100000)).then(response => response.status, () => 'dropped');
+        assert(rejected === 'dropped', `expected an oversized body to be dropped, got ${rejected}`);
+    } finally {
+        await server.stop();
+    }
+});
+
+test('eval rejects code and languages that are not strings', async () => {
+    const server = await startServer('eval-server.cjs', [], { preload: path.join(FIXTURES_DIR, 'fake-sandbox.cjs') });
+    const post = (body) => fetch(`${server.url}/eval`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: typeof body === 'string' ? body : JSON.stringify(body)
+    });
+    try {
+        for (const body of [
+            { lang: 'bqn', code: { x: 1 } },
+            { lang: 'bqn', code: 5 },
+            { lang: ['bqn'], code: '1+1' },
+            { language: 7, code: '1+1' },
+            'null',
+            '{"lang":'
+        ]) {
+            const response = await post(body);
+            const text = await response.text();
+            assert(response.status === 400 && JSON.parse(text).success === false,
+                `expected 400 for ${JSON.stringify(body)}, got ${response.status}: ${text}`);
+        }
+        const accepted = await post({ language: 'BQN', code: '1+1' });
+        assert(accepted.ok && (await accepted.json()).output === '1+1', 'expected a well-formed request to be evaluated');
+    } finally {
+        await server.stop();
+    }
+});
+
+// ---- APL server: project files in the sandbox ----
+
+function evalApl(url, body) {
+    return fetch(`${url}/eval`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(body)
+    });
+}
+
+test('apl sandbox rejects project files ⎕FX cannot fix', async () => {
+    // A stand-in dyalog on the PATH; the fake sandbox echoes the program it gets
+    const server = await startServer('apl-server.cjs', ['--sandbox'], {
+        preload: path.join(FIXTURES_DIR, 'fake-sandbox.cjs'),
+        env: { PATH: `${path.join(FIXTURES_DIR, 'bin')}${path.delimiter}${process.env.PATH}` }
+    });
+    try {
+        const code = "⎕FIX 'file://lib.aplf'\n1 Add 2";
+        const two = await (await evalApl(server.url, { code, files: { 'lib.aplf': 'Add←{⍺+⍵}\nMul←{⍺×⍵}' } })).json();
+        assert(two.success === false && /lib\.aplf holds more than one function/.test(two.output),
+            `expected a two-function file to be rejected, got ${JSON.stringify(two)}`);
+
+        const namespace = await (await evalApl(server.url, {
+            code, files: { 'lib.aplf': ':Namespace lib\nAdd←{⍺+⍵}\n:EndNamespace' }
+        })).json();
+        assert(namespace.success === false && /:Namespace script/.test(namespace.output),
+            `expected a namespace script to be rejected, got ${JSON.stringify(namespace)}`);
+
+        const one = await (await evalApl(server.url, { code, files: { 'lib.aplf': 'Add←{\n  ⍺+⍵\n}' } })).json();
+        assert(one.success === true && one.output.includes('⎕FX'), `expected a one-function file to be fixed, got ${JSON.stringify(one)}`);
+    } finally {
+        await server.stop();
+    }
+});
+
+// ---- Sandbox: warm containers ----
+
+/**
+ * Run fn(sandbox, dockerLog) with servers/sandbox.cjs loaded in this process,
+ * the stand-in docker from tests/fixtures/bin on the PATH and a short timeout
+ */
+async function withFakeDocker(fn) {
+    const dir = tempDir();
+    const log = path.join(dir, 'docker.log');
+    const saved = { PATH: process.env.PATH, FAKE_DOCKER_LOG: process.env.FAKE_DOCKER_LOG, log: console.log, warn: console.warn };
+    process.env.PATH = `${path.join(FIXTURES_DIR, 'bin')}${path.delimiter}${process.env.PATH}`;
+    process.env.FAKE_DOCKER_LOG = log;
+    console.log = console.warn = () => {};
+    const sandbox = createRequire(import.meta.url)(path.join(SERVERS_DIR, 'sandbox.cjs'));
+    sandbox.CONFIG.prewarmOnStartup = false;
+    sandbox.CONFIG.timeout = 300;
+    try {
+        await fn(sandbox, log);
+    } finally {
+        sandbox.cleanupWarmContainers();
+        process.env.PATH = saved.PATH;
+        if (saved.FAKE_DOCKER_LOG === undefined) delete process.env.FAKE_DOCKER_LOG;
+        else process.env.FAKE_DOCKER_LOG = saved.FAKE_DOCKER_LOG;
+        console.log = saved.log;
+        console.warn = saved.warn;
+        fs.rmSync(dir, { recursive: true, force: true });
+    }
+}
+
+test('sandbox replaces warm containers that time out', () => withFakeDocker(async (sandbox, log) => {
+    // The stand-in's containers never answer this code
+    for (const lang of ['apl', 'bqn']) {
+        fs.writeFileSync(log, '');
+        for (let i = 0; i < 2; i++) {
+            const result = await sandbox.executeInSandbox(lang, 'hang');
+            assert(!result.success, `expected ${lang} request ${i + 1} to time out, got ${JSON.stringify(result)}`);
+        }
+        const starts = fs.readFileSync(log, 'utf8').split('\n').filter(Boolean).length;
+        assert(starts === 2, `expected a new ${lang} container after a timeout, got ${starts} started`);
+    }
+}));
+
+test('sandbox streams APL output as it is printed', () => withFakeDocker(async (sandbox) => {
+    // The stand-in prints "first", pauses a second, then prints "second"
+    sandbox.CONFIG.timeout = 5000;
+    const chunks = [];
+    const started = Date.now();
+    const result = await sandbox.executeInSandbox('apl', 'run', {
+        onOutput: (text) => chunks.push({ text, at: Date.now() - started })
+    });
+    const finished = Date.now() - started;
+    assert(result.success && result.output === 'first\nsecond', `unexpected result: ${JSON.stringify(result)}`);
+    assert(chunks.length > 0 && chunks[0].text === 'first\n' && chunks[0].at < finished - 500,
+        `expected "first" to arrive before the result, got ${JSON.stringify(chunks)} (result after ${finished} ms)`);
+}));
+
+async function main() {
+    const filters = process.argv.slice(2);
+    const selected = tests.filter(({ name }) => filters.length === 0 || filters.some(filter => name.includes(filter)));
+
+    let failed = 0;
+    let untested = 0;
+    for (const { name, fn } of selected) {
+        try {
+            await fn();
+            console.log(`✓ ${name}`);
+        } catch (error) {
+            if (error instanceof NotTested) {
+                console.log(`- ${name} (not tested: ${error.message})`);
+                untested++;
+                continue;
+            }
+            console.log(`✗ ${name}`);
+            for (const line of String(error.message).split('\n')) console.log(`    ${line}`);
+            failed++;
+        }
+    }
+
+    console.log(`\n${selected.length - failed - untested} passed, ${failed} failed` +
+        (untested ? `, ${untested} not tested` : ''));
+    return failed > 0 ? 1 : 0;
+}
+
+main().then(
+    (status) => process.exit(status),
+    (error) => {
+        console.error(error);
+        process.exit(1);
+    }
+);